@@ -0,0 +1,69 @@
+use std::hint::black_box;
+
+use chess_bevy_refactor::board::Board;
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+
+const PERFT_DEPTH: usize = 4;
+const SEARCH_DEPTH: usize = 3;
+
+// The start position, a middlegame full of tactics (Kiwipete), an endgame, and a position with promotions and checks
+const POSITIONS: [(&str, &str); 4] = [
+    (
+        "start",
+        "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+    ),
+    (
+        "kiwipete",
+        "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+    ),
+    ("endgame", "8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1"),
+    (
+        "promotions",
+        "r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 w kq - 0 1",
+    ),
+];
+
+fn bench_movegen(c: &mut Criterion) {
+    let mut group = c.benchmark_group("all_legal_moves");
+
+    // The board is cloned each time, so that the legal move cache starts empty
+    for (name, fen) in POSITIONS {
+        let board = Board::from_fen(fen).unwrap();
+        group.bench_with_input(BenchmarkId::from_parameter(name), &board, |b, board| {
+            b.iter(|| black_box(board.clone()).get_all_legal_moves())
+        });
+    }
+
+    group.finish();
+}
+
+fn bench_perft(c: &mut Criterion) {
+    let mut group = c.benchmark_group("perft");
+    group.sample_size(10);
+
+    let board = Board::default();
+    group.bench_with_input(
+        BenchmarkId::new("start", PERFT_DEPTH),
+        &board,
+        |b, board| b.iter(|| black_box(board.clone()).perft(PERFT_DEPTH)),
+    );
+
+    group.finish();
+}
+
+fn bench_search(c: &mut Criterion) {
+    let mut group = c.benchmark_group("best_move");
+    group.sample_size(10);
+
+    for (name, fen) in POSITIONS {
+        let board = Board::from_fen(fen).unwrap();
+        group.bench_with_input(BenchmarkId::new(name, SEARCH_DEPTH), &board, |b, board| {
+            b.iter(|| black_box(board.clone()).best_move(SEARCH_DEPTH, None))
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_movegen, bench_perft, bench_search);
+criterion_main!(benches);
@@ -0,0 +1,186 @@
+use std::path::Path;
+
+use bevy::{
+    prelude::*,
+    tasks::{block_on, futures_lite::future, AsyncComputeTaskPool, Task},
+};
+
+use crate::{
+    board::{Board, Player},
+    editor::EditorMode,
+    fen_input::fen_input_closed,
+    game_over::GameState,
+    opening_book::{OpeningBook, OPENING_BOOK_PATH},
+    piece::{piece_move_event_reader, PieceMove, PieceMoveEvent},
+};
+
+pub const DEFAULT_AI_DEPTH: usize = 3;
+
+// Which side the computer plays as
+#[derive(Resource, Clone, Copy)]
+pub struct AiPlayer(pub Player);
+
+impl Default for AiPlayer {
+    fn default() -> Self {
+        AiPlayer(Player::Black)
+    }
+}
+
+#[derive(Resource)]
+pub struct AiSettings {
+    pub enabled: bool,
+    pub depth: usize,
+}
+
+impl Default for AiSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            depth: DEFAULT_AI_DEPTH,
+        }
+    }
+}
+
+// A search running in the background, along with the position it was started from
+#[derive(Resource, Default)]
+pub struct AiTask {
+    task: Option<(u64, Task<Option<PieceMove>>)>,
+}
+
+pub struct ChessAiPlugin;
+
+impl Plugin for ChessAiPlugin {
+    fn build(&self, app: &mut App) {
+        // Play without a book when there isn't one to load
+        let book = OpeningBook::load(Path::new(OPENING_BOOK_PATH)).unwrap_or_else(|e| {
+            error!("{e}");
+            OpeningBook::default()
+        });
+
+        app.insert_resource(book)
+            .init_resource::<AiPlayer>()
+            .init_resource::<AiSettings>()
+            .init_resource::<AiTask>()
+            .add_systems(
+                Update,
+                (
+                    ai_toggle_input.run_if(fen_input_closed),
+                    start_ai_search
+                        .after(piece_move_event_reader)
+                        .after(ai_toggle_input),
+                    poll_ai_search.after(start_ai_search),
+                ),
+            );
+    }
+}
+
+// Turn the computer opponent on and off with A
+pub fn ai_toggle_input(keys: Res<ButtonInput<KeyCode>>, mut settings: ResMut<AiSettings>) {
+    if keys.just_pressed(KeyCode::KeyA) {
+        settings.enabled = !settings.enabled;
+    }
+}
+
+// Search for a reply when a move hands the turn to the computer, or when it is switched on during its turn
+#[allow(clippy::too_many_arguments)]
+pub fn start_ai_search(
+    mut ev_piece_move: EventReader<PieceMoveEvent>,
+    board: Res<Board>,
+    ai_player: Res<AiPlayer>,
+    settings: Res<AiSettings>,
+    book: Res<OpeningBook>,
+    editor_mode: Res<EditorMode>,
+    game_state: Res<GameState>,
+    mut ai_task: ResMut<AiTask>,
+) {
+    let moved = ev_piece_move.read().count() > 0;
+
+    // The position being composed in the editor isn't played from until the editor is closed,
+    // and a search started before the game ended is abandoned
+    if !settings.enabled || editor_mode.enabled || game_state.is_over() {
+        ai_task.task = None;
+        return;
+    }
+
+    if !(moved || settings.is_changed() || ai_player.is_changed())
+        || board.player != ai_player.0
+        || ai_task.task.is_some()
+    {
+        return;
+    }
+
+    let mut search_board = board.clone();
+    let depth = settings.depth;
+    let book = book.clone();
+    let task = AsyncComputeTaskPool::get()
+        .spawn(async move { search_board.best_move(depth, Some(&book)) });
+
+    ai_task.task = Some((board.zobrist_hash(), task));
+}
+
+// Send the computer's move once the search finishes, so long as the position hasn't changed since
+pub fn poll_ai_search(
+    board: Res<Board>,
+    mut ai_task: ResMut<AiTask>,
+    mut ev_piece_move: EventWriter<PieceMoveEvent>,
+) {
+    let Some((hash, task)) = &mut ai_task.task else {
+        return;
+    };
+
+    let Some(best_move) = block_on(future::poll_once(task)) else {
+        return;
+    };
+
+    if *hash == board.zobrist_hash() {
+        if let Some(piece_move) = best_move {
+            if let Some(entity) = board.get_entity(piece_move.from) {
+                ev_piece_move.send(PieceMoveEvent { piece_move, entity });
+            }
+        }
+    }
+
+    ai_task.task = None;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        game_over::{GameEndReason, GameOverEvent},
+        headless::ChessCorePlugin,
+    };
+
+    fn ai_app(ai_player: Player) -> App {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, ChessCorePlugin))
+            .insert_resource(AiPlayer(ai_player))
+            .init_resource::<AiSettings>()
+            .init_resource::<AiTask>()
+            .init_resource::<OpeningBook>()
+            .init_resource::<EditorMode>()
+            .add_systems(Update, start_ai_search.after(piece_move_event_reader));
+
+        app
+    }
+
+    #[test]
+    fn searches_on_its_turn() {
+        let mut app = ai_app(Player::White);
+        app.update();
+
+        assert!(app.world.resource::<AiTask>().task.is_some());
+    }
+
+    #[test]
+    fn no_search_after_game_over() {
+        let mut app = ai_app(Player::White);
+        app.world.send_event(GameOverEvent {
+            winner: Some(Player::Black),
+            reason: GameEndReason::Resignation,
+        });
+        app.update();
+
+        assert!(app.world.resource::<AiTask>().task.is_none());
+    }
+}
@@ -0,0 +1,329 @@
+use bevy::{
+    prelude::*,
+    sprite::{Anchor, MaterialMesh2dBundle, Mesh2dHandle},
+    tasks::{block_on, futures_lite::future, AsyncComputeTaskPool, Task},
+};
+use bevy_mod_picking::prelude::*;
+
+use crate::{
+    board::{Board, Player},
+    display::{BoardLayout, BOARD_SIZE},
+    evaluate::{evaluate, CHECKMATE_SCORE},
+    piece::piece_move_event_reader,
+};
+
+// The search is deepened one ply at a time up to this depth, then stops until the position changes
+pub const ANALYSIS_MAX_DEPTH: usize = 5;
+
+// Scores beyond this many centipawns either way fill the whole bar
+pub const EVAL_BAR_RANGE: i32 = 1000;
+
+// Scores this close to the checkmate score are forced mates, counted in plies from the position
+const MATE_THRESHOLD: i32 = CHECKMATE_SCORE - 1000;
+
+// The bar is drawn beside the board, with its width and font as fractions of a square
+const EVAL_BAR_WIDTH: f32 = 0.12;
+const EVAL_BAR_FONT_SIZE: f32 = 0.1;
+const EVAL_BAR_WHITE: Color = Color::rgb(0.95, 0.95, 0.95);
+const EVAL_BAR_BLACK: Color = Color::rgb(0.2, 0.2, 0.2);
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum EvalScore {
+    // Positive when White is better
+    Centipawns(i32),
+    // The winner can force checkmate in this many moves
+    Mate { winner: Player, moves: usize },
+}
+
+impl EvalScore {
+    // Convert a score in centipawns from White's point of view, picking out forced mates
+    pub fn from_white_score(score: i32) -> Self {
+        if score.abs() < MATE_THRESHOLD {
+            return EvalScore::Centipawns(score);
+        }
+
+        let plies = (CHECKMATE_SCORE - score.abs()).max(0) as usize;
+        let winner = if score > 0 {
+            Player::White
+        } else {
+            Player::Black
+        };
+
+        EvalScore::Mate {
+            winner,
+            moves: plies.div_ceil(2),
+        }
+    }
+}
+
+// How much of the bar is filled by White, with forced mates filling it for the winner
+pub fn get_bar_fraction(score: EvalScore) -> f32 {
+    match score {
+        EvalScore::Centipawns(centipawns) => {
+            let clamped = centipawns.clamp(-EVAL_BAR_RANGE, EVAL_BAR_RANGE);
+
+            0.5 + clamped as f32 / (2 * EVAL_BAR_RANGE) as f32
+        }
+        EvalScore::Mate {
+            winner: Player::White,
+            ..
+        } => 1.,
+        EvalScore::Mate {
+            winner: Player::Black,
+            ..
+        } => 0.,
+    }
+}
+
+pub fn get_eval_text(score: EvalScore) -> String {
+    match score {
+        EvalScore::Centipawns(centipawns) => format!("{:+.1}", centipawns as f32 / 100.),
+        EvalScore::Mate {
+            winner: Player::White,
+            moves,
+        } => format!("#{moves}"),
+        EvalScore::Mate {
+            winner: Player::Black,
+            moves,
+        } => format!("#-{moves}"),
+    }
+}
+
+// Search the position to this depth, scoring it from White's point of view
+pub fn get_analysis_score(board: &Board, depth: usize) -> EvalScore {
+    let mut board = board.clone();
+    let player = board.player;
+
+    let Some(result) = board.search(depth) else {
+        // Without any legal moves the game is already over
+        return if board.is_in_check(player) {
+            EvalScore::Mate {
+                winner: player.opponent(),
+                moves: 0,
+            }
+        } else {
+            EvalScore::Centipawns(0)
+        };
+    };
+
+    EvalScore::from_white_score(match player {
+        Player::White => result.score,
+        Player::Black => -result.score,
+    })
+}
+
+// The latest score for the position on the board, along with the deeper search running in the background
+#[derive(Resource, Default)]
+pub struct Analysis {
+    pub score: Option<EvalScore>,
+    pub depth: usize,
+    hash: u64,
+    task: Option<Task<EvalScore>>,
+}
+
+// Part of the bar showing who is better
+#[derive(Component)]
+pub struct EvalBar;
+
+pub struct AnalysisPlugin;
+
+impl Plugin for AnalysisPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Analysis>().add_systems(
+            Update,
+            (
+                start_analysis.after(piece_move_event_reader),
+                poll_analysis.after(start_analysis),
+                display_eval_bar.after(poll_analysis),
+            ),
+        );
+    }
+}
+
+// Restart from the static evaluation whenever the position changes, then search one ply deeper each time a search finishes
+pub fn start_analysis(board: Res<Board>, mut analysis: ResMut<Analysis>) {
+    let hash = board.zobrist_hash();
+    if hash != analysis.hash || analysis.score.is_none() {
+        // Dropping the task cancels the search of the old position
+        *analysis = Analysis {
+            score: Some(EvalScore::from_white_score(evaluate(&board))),
+            depth: 0,
+            hash,
+            task: None,
+        };
+    }
+
+    if analysis.task.is_some() || analysis.depth >= ANALYSIS_MAX_DEPTH {
+        return;
+    }
+
+    let search_board = board.clone();
+    let depth = analysis.depth + 1;
+    analysis.task = Some(
+        AsyncComputeTaskPool::get().spawn(async move { get_analysis_score(&search_board, depth) }),
+    );
+}
+
+pub fn poll_analysis(mut analysis: ResMut<Analysis>) {
+    let Some(task) = &mut analysis.task else {
+        return;
+    };
+
+    let Some(score) = block_on(future::poll_once(task)) else {
+        return;
+    };
+
+    analysis.score = Some(score);
+    analysis.depth += 1;
+    analysis.task = None;
+}
+
+// Draw the bar to the left of the board, with White's share on White's side of the board
+pub fn display_eval_bar(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    analysis: Res<Analysis>,
+    layout: Res<BoardLayout>,
+    bar_query: Query<Entity, With<EvalBar>>,
+) {
+    if !analysis.is_changed() && !layout.is_changed() {
+        return;
+    }
+
+    let Some(score) = analysis.score else {
+        return;
+    };
+
+    for entity in bar_query.iter() {
+        commands.entity(entity).despawn();
+    }
+
+    let square_size = layout.get_square_size();
+    let height = square_size * BOARD_SIZE as f32;
+    let width = square_size * EVAL_BAR_WIDTH;
+    let x = -height / 2. - layout.spacing - width / 2.;
+
+    // The bottom of the bar belongs to whichever player is at the bottom of the board
+    let white_fraction = get_bar_fraction(score);
+    let (bottom_fraction, bottom_colour, top_colour) = match layout.orientation.get_bottom_player()
+    {
+        Player::White => (white_fraction, EVAL_BAR_WHITE, EVAL_BAR_BLACK),
+        Player::Black => (1. - white_fraction, EVAL_BAR_BLACK, EVAL_BAR_WHITE),
+    };
+    let bottom_height = height * bottom_fraction;
+
+    for (section_height, centre_y, colour) in [
+        (
+            bottom_height,
+            -height / 2. + bottom_height / 2.,
+            bottom_colour,
+        ),
+        (
+            height - bottom_height,
+            height / 2. - (height - bottom_height) / 2.,
+            top_colour,
+        ),
+    ] {
+        commands.spawn((
+            MaterialMesh2dBundle {
+                mesh: Mesh2dHandle(meshes.add(Rectangle::new(width, section_height))),
+                material: materials.add(colour),
+                transform: Transform::from_xyz(x, centre_y, 1.),
+                ..default()
+            },
+            Pickable::IGNORE,
+            EvalBar,
+        ));
+    }
+
+    // The score is written along the bar, at the end belonging to the player who is better
+    let is_bottom_better = bottom_fraction >= 0.5;
+    let (label_y, label_colour) = if is_bottom_better {
+        (-height / 2. + layout.spacing, top_colour)
+    } else {
+        (height / 2. - layout.spacing, bottom_colour)
+    };
+    let anchor = if is_bottom_better {
+        Anchor::CenterLeft
+    } else {
+        Anchor::CenterRight
+    };
+
+    commands.spawn((
+        Text2dBundle {
+            text: Text::from_section(
+                get_eval_text(score),
+                TextStyle {
+                    font_size: square_size * EVAL_BAR_FONT_SIZE,
+                    color: label_colour,
+                    ..default()
+                },
+            ),
+            text_anchor: anchor,
+            transform: Transform::from_xyz(x, label_y, 2.)
+                .with_rotation(Quat::from_rotation_z(std::f32::consts::FRAC_PI_2)),
+            ..default()
+        },
+        Pickable::IGNORE,
+        EvalBar,
+    ));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bar_fractions() {
+        assert_eq!(get_bar_fraction(EvalScore::Centipawns(0)), 0.5);
+        assert_eq!(
+            get_bar_fraction(EvalScore::Centipawns(EVAL_BAR_RANGE / 2)),
+            0.75
+        );
+
+        // Scores past the range fill the bar without going over
+        assert_eq!(
+            get_bar_fraction(EvalScore::Centipawns(5 * EVAL_BAR_RANGE)),
+            1.
+        );
+        assert_eq!(
+            get_bar_fraction(EvalScore::Centipawns(-5 * EVAL_BAR_RANGE)),
+            0.
+        );
+
+        assert_eq!(
+            get_bar_fraction(EvalScore::Mate {
+                winner: Player::White,
+                moves: 3
+            }),
+            1.
+        );
+        assert_eq!(
+            get_bar_fraction(EvalScore::Mate {
+                winner: Player::Black,
+                moves: 1
+            }),
+            0.
+        );
+    }
+
+    #[test]
+    fn mate_scores() {
+        assert_eq!(
+            EvalScore::from_white_score(CHECKMATE_SCORE - 3),
+            EvalScore::Mate {
+                winner: Player::White,
+                moves: 2
+            }
+        );
+        assert_eq!(
+            EvalScore::from_white_score(-CHECKMATE_SCORE + 1),
+            EvalScore::Mate {
+                winner: Player::Black,
+                moves: 1
+            }
+        );
+        assert_eq!(EvalScore::from_white_score(350), EvalScore::Centipawns(350));
+    }
+}
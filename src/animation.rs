@@ -0,0 +1,120 @@
+use std::time::Duration;
+
+use bevy::prelude::*;
+
+pub const DEFAULT_ANIMATION_DURATION: Duration = Duration::from_millis(150);
+
+// Pieces are drawn above the rest of the board while they're moving
+const ANIMATION_Z: f32 = 10.;
+
+#[derive(Resource)]
+pub struct AnimationSettings {
+    pub duration: Duration,
+}
+
+impl Default for AnimationSettings {
+    fn default() -> Self {
+        Self {
+            duration: DEFAULT_ANIMATION_DURATION,
+        }
+    }
+}
+
+// Slides a piece's sprite from one position to another, replacing any animation the piece already had
+#[derive(Component, Clone, Debug)]
+pub struct MoveAnimation {
+    pub start: Vec3,
+    pub end: Vec3,
+    pub timer: Timer,
+}
+
+impl MoveAnimation {
+    pub fn new(start: Vec3, end: Vec3, duration: Duration) -> Self {
+        Self {
+            start,
+            end,
+            timer: Timer::new(duration, TimerMode::Once),
+        }
+    }
+
+    pub fn get_translation(&self) -> Vec3 {
+        if self.timer.finished() {
+            return self.end;
+        }
+
+        let translation = lerp_translation(self.start, self.end, self.timer.fraction());
+
+        Vec3::new(translation.x, translation.y, ANIMATION_Z)
+    }
+}
+
+// Starts slowly, speeds up, then slows down again as it arrives, with t between 0 and 1
+pub fn ease_in_out(t: f32) -> f32 {
+    let t = t.clamp(0., 1.);
+
+    if t < 0.5 {
+        4. * t * t * t
+    } else {
+        1. - (-2. * t + 2.).powi(3) / 2.
+    }
+}
+
+// The position a fraction t of the way through the animation
+pub fn lerp_translation(start: Vec3, end: Vec3, t: f32) -> Vec3 {
+    start.lerp(end, ease_in_out(t))
+}
+
+// Start animating a piece from where it currently is, or snap it there if animations are turned off
+pub fn animate_to(
+    commands: &mut Commands,
+    entity: Entity,
+    transform: &mut Transform,
+    end: Vec3,
+    settings: &AnimationSettings,
+) {
+    if settings.duration.is_zero() {
+        transform.translation = end;
+        commands.entity(entity).remove::<MoveAnimation>();
+    } else {
+        commands.entity(entity).insert(MoveAnimation::new(
+            transform.translation,
+            end,
+            settings.duration,
+        ));
+    }
+}
+
+pub fn animate_moves(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut animation_query: Query<(Entity, &mut Transform, &mut MoveAnimation)>,
+) {
+    for (entity, mut transform, mut animation) in animation_query.iter_mut() {
+        animation.timer.tick(time.delta());
+
+        transform.translation = animation.get_translation();
+
+        if animation.timer.finished() {
+            commands.entity(entity).remove::<MoveAnimation>();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lerp_endpoints_and_midpoint() {
+        let start = Vec3::new(-100., 50., 1.);
+        let end = Vec3::new(60., -30., 1.);
+
+        assert!(lerp_translation(start, end, 0.).abs_diff_eq(start, 1e-4));
+        assert!(lerp_translation(start, end, 0.5).abs_diff_eq((start + end) / 2., 1e-4));
+        assert!(lerp_translation(start, end, 1.).abs_diff_eq(end, 1e-4));
+
+        // Going past either end of the animation stays at that end
+        assert!(lerp_translation(start, end, -0.5).abs_diff_eq(start, 1e-4));
+        assert!(lerp_translation(start, end, 1.5).abs_diff_eq(end, 1e-4));
+    }
+}
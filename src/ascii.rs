@@ -0,0 +1,91 @@
+use std::fmt;
+
+use crate::{
+    board::{Board, TilePos},
+    display::BOARD_SIZE,
+    piece::Piece,
+};
+
+impl Board {
+    // A grid of FEN piece letters, with rank 8 at the top like FEN
+    pub fn to_ascii(&self) -> String {
+        self.to_text('.', |piece| piece.to_algebraic())
+    }
+
+    // The same grid drawn with the chess glyphs
+    pub fn to_unicode(&self) -> String {
+        self.to_text('·', |piece| piece.to_unicode())
+    }
+
+    fn to_text(&self, empty: char, piece_to_char: impl Fn(Piece) -> char) -> String {
+        let dimensions = self.get_dimensions();
+        let border = format!("  +{}+\n", "-".repeat(dimensions.ranks * 2 + 1));
+        let mut text = border.clone();
+
+        for file in dimensions.get_top_file()..BOARD_SIZE {
+            text += format!("{} |", BOARD_SIZE - file).as_str();
+
+            for rank in 0..dimensions.ranks {
+                let piece = self.get_piece(TilePos::new(file, rank));
+
+                text.push(' ');
+                text.push(if piece == Piece::None {
+                    empty
+                } else {
+                    piece_to_char(piece)
+                });
+            }
+
+            text += " |\n";
+        }
+
+        text += border.as_str();
+
+        // Label the columns with their letters
+        text += "   ";
+        for rank in 0..dimensions.ranks {
+            text.push(' ');
+            text.push((b'a' + rank as u8) as char);
+        }
+
+        text
+    }
+}
+
+impl fmt::Display for Board {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.to_ascii())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn start_position_snapshot() {
+        let expected = "  +-----------------+
+8 | r n b q k b n r |
+7 | p p p p p p p p |
+6 | . . . . . . . . |
+5 | . . . . . . . . |
+4 | . . . . . . . . |
+3 | . . . . . . . . |
+2 | P P P P P P P P |
+1 | R N B Q K B N R |
+  +-----------------+
+    a b c d e f g h";
+
+        assert_eq!(Board::default().to_ascii(), expected);
+        assert_eq!(Board::default().to_string(), expected);
+    }
+
+    #[test]
+    fn unicode_glyphs() {
+        let text = Board::default().to_unicode();
+
+        assert!(text.contains("8 | ♜ ♞ ♝ ♛ ♚ ♝ ♞ ♜ |"));
+        assert!(text.contains("1 | ♖ ♘ ♗ ♕ ♔ ♗ ♘ ♖ |"));
+        assert!(text.contains("4 | · · · · · · · · |"));
+    }
+}
@@ -0,0 +1,178 @@
+use std::sync::LazyLock;
+
+use crate::{bitboard::BitBoard, board::TilePos, display::BOARD_SIZE};
+
+// Steps as (d_file, d_rank) pairs, which are the arguments of TilePos::offset
+const KNIGHT_OFFSETS: [(isize, isize); 8] = [
+    (-2, -1),
+    (-2, 1),
+    (-1, -2),
+    (-1, 2),
+    (1, -2),
+    (1, 2),
+    (2, -1),
+    (2, 1),
+];
+
+const KING_OFFSETS: [(isize, isize); 8] = [
+    (-1, -1),
+    (-1, 0),
+    (-1, 1),
+    (0, -1),
+    (0, 1),
+    (1, -1),
+    (1, 0),
+    (1, 1),
+];
+
+// Orthogonal directions followed by diagonal directions
+pub const SLIDING_DIRS: [(isize, isize); 8] = [
+    (-1, 0),
+    (1, 0),
+    (0, -1),
+    (0, 1),
+    (-1, -1),
+    (-1, 1),
+    (1, -1),
+    (1, 1),
+];
+
+// The squares a knight or king attacks from each square, indexed the same way as bitboards
+pub static KNIGHT_ATTACKS: LazyLock<[BitBoard; BOARD_SIZE * BOARD_SIZE]> =
+    LazyLock::new(|| get_offset_attacks(&KNIGHT_OFFSETS));
+pub static KING_ATTACKS: LazyLock<[BitBoard; BOARD_SIZE * BOARD_SIZE]> =
+    LazyLock::new(|| get_offset_attacks(&KING_OFFSETS));
+
+// Every square from each square to the edge of the board in each direction, ignoring any pieces in the way
+static RAYS: LazyLock<[[BitBoard; BOARD_SIZE * BOARD_SIZE]; 8]> =
+    LazyLock::new(|| SLIDING_DIRS.map(get_rays));
+
+pub fn get_knight_attacks(tile_pos: TilePos) -> BitBoard {
+    KNIGHT_ATTACKS[tile_pos.to_index()]
+}
+
+pub fn get_king_attacks(tile_pos: TilePos) -> BitBoard {
+    KING_ATTACKS[tile_pos.to_index()]
+}
+
+// Squares a rook could move to, including the first piece in each direction, given which squares are occupied
+pub fn get_orthogonal_attacks(tile_pos: TilePos, occupancy: BitBoard) -> BitBoard {
+    (0..4).fold(BitBoard::default(), |attacks, dir_index| {
+        attacks | get_ray_attacks(tile_pos, dir_index, occupancy)
+    })
+}
+
+// Squares a bishop could move to, including the first piece in each direction, given which squares are occupied
+pub fn get_diagonal_attacks(tile_pos: TilePos, occupancy: BitBoard) -> BitBoard {
+    (4..8).fold(BitBoard::default(), |attacks, dir_index| {
+        attacks | get_ray_attacks(tile_pos, dir_index, occupancy)
+    })
+}
+
+fn get_ray_attacks(tile_pos: TilePos, dir_index: usize, occupancy: BitBoard) -> BitBoard {
+    let rays = &RAYS[dir_index];
+    let ray = rays[tile_pos.to_index()];
+
+    // The nearest blocker is the lowest set bit when the ray goes towards higher indices, and the highest otherwise
+    let (d_file, d_rank) = SLIDING_DIRS[dir_index];
+    let blockers = ray & occupancy;
+    let blocker = if d_file * BOARD_SIZE as isize + d_rank > 0 {
+        blockers.first_index()
+    } else {
+        blockers.last_index()
+    };
+
+    // Remove the squares behind the blocker, which are the blocker's own ray in the same direction
+    match blocker {
+        Some(index) => ray ^ rays[index],
+        None => ray,
+    }
+}
+
+fn get_rays(dir: (isize, isize)) -> [BitBoard; BOARD_SIZE * BOARD_SIZE] {
+    let mut rays = [BitBoard::default(); BOARD_SIZE * BOARD_SIZE];
+
+    for (index, ray) in rays.iter_mut().enumerate() {
+        let mut current = TilePos::from_index(index);
+
+        while let Some(next) = current.offset(dir.0, dir.1) {
+            ray.set_bit_at(next, true);
+            current = next;
+        }
+    }
+
+    rays
+}
+
+fn get_offset_attacks(offsets: &[(isize, isize)]) -> [BitBoard; BOARD_SIZE * BOARD_SIZE] {
+    let mut attacks = [BitBoard::default(); BOARD_SIZE * BOARD_SIZE];
+
+    for (index, attack) in attacks.iter_mut().enumerate() {
+        let from = TilePos::from_index(index);
+
+        for &(d_file, d_rank) in offsets {
+            if let Some(to) = from.offset(d_file, d_rank) {
+                attack.set_bit_at(to, true);
+            }
+        }
+    }
+
+    attacks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pos(algebraic: &str) -> TilePos {
+        TilePos::from_algebraic(algebraic).unwrap()
+    }
+
+    #[test]
+    fn attack_counts() {
+        // From a corner, an edge, and the centre
+        for (square, knight_count, king_count) in [
+            ("a1", 2, 3),
+            ("h8", 2, 3),
+            ("a4", 4, 5),
+            ("e1", 4, 5),
+            ("e4", 8, 8),
+            ("d5", 8, 8),
+        ] {
+            assert_eq!(
+                get_knight_attacks(pos(square)).count(),
+                knight_count,
+                "{square}"
+            );
+            assert_eq!(
+                get_king_attacks(pos(square)).count(),
+                king_count,
+                "{square}"
+            );
+        }
+    }
+
+    #[test]
+    fn corner_knight_attacks() {
+        let attacks = get_knight_attacks(pos("a1"));
+
+        assert!(attacks.get_bit_at(pos("b3")) && attacks.get_bit_at(pos("c2")));
+    }
+
+    #[test]
+    fn edge_king_attacks_stay_on_board() {
+        // A king on the h-file must not wrap around to the a-file, or a king on the first rank to the eighth
+        for (square, expected) in [
+            ("h4", ["g3", "g4", "g5", "h3", "h5"]),
+            ("d1", ["c1", "c2", "d2", "e1", "e2"]),
+        ] {
+            let mut attacks = get_king_attacks(pos(square))
+                .iter()
+                .map(|tile_pos| tile_pos.to_algebraic())
+                .collect::<Vec<_>>();
+            attacks.sort();
+
+            assert_eq!(attacks, expected, "{square}");
+        }
+    }
+}
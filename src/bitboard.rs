@@ -6,19 +6,66 @@ use crate::{
     piece::{Piece, COLOUR_AMT, PIECE_AMT},
 };
 
-#[derive(Copy, Clone, Default)]
+// The squares in the a and h columns, which are the first and last ranks of a TilePos
+const A_FILE: u64 = 0x0101010101010101;
+const H_FILE: u64 = A_FILE << (BOARD_SIZE - 1);
+
+#[derive(Copy, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct BitBoard {
     bits: u64,
 }
 
 #[allow(dead_code)]
 impl BitBoard {
+    pub const fn from_bits(bits: u64) -> Self {
+        Self { bits }
+    }
+
+    pub fn get_bits(&self) -> u64 {
+        self.bits
+    }
+
+    pub fn count(&self) -> u32 {
+        self.bits.count_ones()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bits == 0
+    }
+
+    // Each occupied square, found by repeatedly taking the lowest set bit
+    pub fn iter(&self) -> impl Iterator<Item = TilePos> {
+        let mut bits = self.bits;
+
+        std::iter::from_fn(move || {
+            if bits == 0 {
+                return None;
+            }
+
+            let index = bits.trailing_zeros() as usize;
+            bits &= bits - 1;
+
+            Some(TilePos::from_index(index))
+        })
+    }
+
+    // Index of the lowest set bit
+    pub fn first_index(&self) -> Option<usize> {
+        (self.bits != 0).then(|| self.bits.trailing_zeros() as usize)
+    }
+
+    // Index of the highest set bit
+    pub fn last_index(&self) -> Option<usize> {
+        (self.bits != 0).then(|| 63 - self.bits.leading_zeros() as usize)
+    }
+
     pub fn get_bit(&self, index: usize) -> bool {
         (self.bits >> index) & 1 == 1
     }
 
     pub fn get_bit_at(&self, tile_pos: TilePos) -> bool {
-        (self.bits >> (tile_pos.file * BOARD_SIZE + tile_pos.rank)) & 1 == 1
+        self.get_bit(tile_pos.to_index())
     }
 
     pub fn set_bit(&mut self, index: usize, value: bool) {
@@ -28,7 +75,7 @@ impl BitBoard {
     }
 
     pub fn set_bit_at(&mut self, tile_pos: TilePos, value: bool) {
-        self.set_bit(tile_pos.file * BOARD_SIZE + tile_pos.rank, value);
+        self.set_bit(tile_pos.to_index(), value);
     }
 
     pub fn set_file(&mut self, file: usize, file_value: u8) {
@@ -49,6 +96,127 @@ impl BitBoard {
             | ((rank_value as u64) & (1 << 6)) << (6 * (BOARD_SIZE - 1) + rank)
             | ((rank_value as u64) & (1 << 7)) << (7 * (BOARD_SIZE - 1) + rank);
     }
+
+    // Move every square one step up the board towards Black, whose back rank is file 0
+    pub fn north(self) -> Self {
+        self >> BOARD_SIZE
+    }
+
+    pub fn south(self) -> Self {
+        self << BOARD_SIZE
+    }
+
+    // Squares which would go past the edge of the board are dropped, rather than wrapping onto the next file
+    pub fn east(self) -> Self {
+        BitBoard {
+            bits: (self.bits << 1) & !A_FILE,
+        }
+    }
+
+    pub fn west(self) -> Self {
+        BitBoard {
+            bits: (self.bits >> 1) & !H_FILE,
+        }
+    }
+
+    pub fn north_east(self) -> Self {
+        self.north().east()
+    }
+
+    pub fn north_west(self) -> Self {
+        self.north().west()
+    }
+
+    pub fn south_east(self) -> Self {
+        self.south().east()
+    }
+
+    pub fn south_west(self) -> Self {
+        self.south().west()
+    }
+}
+
+impl ops::BitAnd for BitBoard {
+    type Output = BitBoard;
+
+    fn bitand(self, rhs: Self) -> Self::Output {
+        BitBoard {
+            bits: self.bits & rhs.bits,
+        }
+    }
+}
+
+impl ops::BitAndAssign for BitBoard {
+    fn bitand_assign(&mut self, rhs: Self) {
+        self.bits &= rhs.bits;
+    }
+}
+
+impl ops::BitXor for BitBoard {
+    type Output = BitBoard;
+
+    fn bitxor(self, rhs: Self) -> Self::Output {
+        BitBoard {
+            bits: self.bits ^ rhs.bits,
+        }
+    }
+}
+
+impl ops::BitXorAssign for BitBoard {
+    fn bitxor_assign(&mut self, rhs: Self) {
+        self.bits ^= rhs.bits;
+    }
+}
+
+impl ops::Not for BitBoard {
+    type Output = BitBoard;
+
+    fn not(self) -> Self::Output {
+        BitBoard { bits: !self.bits }
+    }
+}
+
+impl ops::BitOr for BitBoard {
+    type Output = BitBoard;
+
+    fn bitor(self, rhs: Self) -> Self::Output {
+        BitBoard {
+            bits: self.bits | rhs.bits,
+        }
+    }
+}
+
+impl ops::BitOrAssign for BitBoard {
+    fn bitor_assign(&mut self, rhs: Self) {
+        self.bits |= rhs.bits;
+    }
+}
+
+// Shifting by the whole board or more leaves it empty, instead of overflowing
+impl ops::Shl<usize> for BitBoard {
+    type Output = BitBoard;
+
+    fn shl(self, rhs: usize) -> Self::Output {
+        BitBoard {
+            bits: u32::try_from(rhs)
+                .ok()
+                .and_then(|rhs| self.bits.checked_shl(rhs))
+                .unwrap_or(0),
+        }
+    }
+}
+
+impl ops::Shr<usize> for BitBoard {
+    type Output = BitBoard;
+
+    fn shr(self, rhs: usize) -> Self::Output {
+        BitBoard {
+            bits: u32::try_from(rhs)
+                .ok()
+                .and_then(|rhs| self.bits.checked_shr(rhs))
+                .unwrap_or(0),
+        }
+    }
 }
 
 impl fmt::Display for BitBoard {
@@ -77,7 +245,8 @@ impl fmt::Display for BitBoard {
     }
 }
 
-#[derive(Default)]
+#[derive(Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct BitBoards {
     boards: [BitBoard; PIECE_AMT * COLOUR_AMT],
 }
@@ -101,3 +270,80 @@ impl ops::IndexMut<Piece> for BitBoards {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::Board;
+
+    fn square(algebraic: &str) -> BitBoard {
+        let mut bitboard = BitBoard::default();
+        bitboard.set_bit_at(TilePos::from_algebraic(algebraic).unwrap(), true);
+
+        bitboard
+    }
+
+    #[test]
+    fn east_of_h_file_is_empty() {
+        assert!(BitBoard::from_bits(H_FILE).east().is_empty());
+        assert!(BitBoard::from_bits(A_FILE).west().is_empty());
+        assert!(square("a8").north().is_empty() && square("a1").south().is_empty());
+    }
+
+    #[test]
+    fn single_steps() {
+        let e4 = square("e4");
+
+        for (shifted, expected) in [
+            (e4.north(), "e5"),
+            (e4.south(), "e3"),
+            (e4.east(), "f4"),
+            (e4.west(), "d4"),
+            (e4.north_east(), "f5"),
+            (e4.north_west(), "d5"),
+            (e4.south_east(), "f3"),
+            (e4.south_west(), "d3"),
+        ] {
+            assert!(shifted == square(expected), "{expected}");
+        }
+    }
+
+    #[test]
+    fn shift_and_mask_matches_hand_built_mask() {
+        // Every square attacked by White's pawns at the start is on the third rank
+        let mut pawns = BitBoard::default();
+        pawns.set_file(BOARD_SIZE - 2, 0xFF);
+        let attacks = pawns.north_east() | pawns.north_west();
+
+        let mut third_rank = BitBoard::default();
+        for algebraic in ["a3", "b3", "c3", "d3", "e3", "f3", "g3", "h3"] {
+            third_rank |= square(algebraic);
+        }
+        assert!(attacks == third_rank);
+
+        // A lone pawn on the h-file only attacks towards the centre
+        let h2 = square("h2");
+        assert!(h2.north_east() | h2.north_west() == square("g3"));
+    }
+
+    #[test]
+    fn shifts_past_the_end() {
+        assert!(BitBoard::from_bits(1) << 64 == BitBoard::default());
+        assert!(BitBoard::from_bits(1) << 63 >> 63 == BitBoard::from_bits(1));
+    }
+
+    #[test]
+    fn iterate_starting_pawns() {
+        let board = Board::default();
+
+        for (pawn, rank) in [(Piece::WPawn, '2'), (Piece::BPawn, '7')] {
+            let squares = board.positions[pawn]
+                .iter()
+                .map(|tile_pos| tile_pos.to_algebraic())
+                .collect::<Vec<_>>();
+
+            assert_eq!(squares.len(), 8);
+            assert!(squares.iter().all(|square| square.ends_with(rank)));
+        }
+    }
+}
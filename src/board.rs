@@ -1,20 +1,48 @@
+use std::{
+    collections::HashMap,
+    error::Error,
+    fmt,
+    hash::{Hash, Hasher},
+    str::FromStr,
+};
+
 use bevy::prelude::*;
 
 use crate::{
-    bitboard::BitBoards,
+    bitboard::{BitBoard, BitBoards},
+    dimensions::BoardDimensions,
     display::BOARD_SIZE,
-    piece::{Piece, COLOUR_AMT, PIECE_AMT},
+    move_history::{PieceMoveHistory, PieceMoveRecord},
+    piece::{MoveFlag, Piece, PieceMove, PieceType, COLOUR_AMT, PIECE_AMT, PROMOTION_PIECE_TYPES},
+    validate::PositionError,
 };
 
-#[derive(Default)]
+// Rank (column) which both kings start on in standard chess
+pub const KING_START_RANK: usize = 4;
+// Ranks which the (king side, queen side) castling king and rook land on, wherever they started
+pub const CASTLING_KING_RANKS: (usize, usize) = (6, 2);
+pub const CASTLING_ROOK_RANKS: (usize, usize) = (5, 3);
+
+#[derive(Default, Copy, Clone, PartialEq, Eq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Player {
     #[default]
     White,
     Black,
 }
 
+impl Player {
+    pub const fn opponent(self) -> Player {
+        match self {
+            Player::White => Player::Black,
+            Player::Black => Player::White,
+        }
+    }
+}
+
 #[allow(dead_code)]
-#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+#[derive(Component, Copy, Clone, PartialEq, Eq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TilePos {
     pub file: usize,
     pub rank: usize,
@@ -24,19 +52,128 @@ impl TilePos {
     pub fn new(file: usize, rank: usize) -> Self {
         Self { file, rank }
     }
+
+    // Only create the position if it is on the board
+    pub fn try_new(file: usize, rank: usize) -> Option<Self> {
+        let tile_pos = Self::new(file, rank);
+
+        tile_pos.is_on_board().then_some(tile_pos)
+    }
+
+    pub fn is_on_board(&self) -> bool {
+        self.file < BOARD_SIZE && self.rank < BOARD_SIZE
+    }
+
+    // The bit of a BitBoard which represents this position, counting along each file from the top left (a8 is 0, h1 is 63)
+    pub fn to_index(&self) -> usize {
+        self.file * BOARD_SIZE + self.rank
+    }
+
+    pub fn from_index(index: usize) -> Self {
+        Self::new(index / BOARD_SIZE, index % BOARD_SIZE)
+    }
+
+    // Move the position by the given amount, unless it would leave the board
+    pub fn offset(&self, d_file: isize, d_rank: isize) -> Option<Self> {
+        let file = self.file.checked_add_signed(d_file)?;
+        let rank = self.rank.checked_add_signed(d_rank)?;
+
+        Self::try_new(file, rank)
+    }
+
+    // Files are counted from the top of the FEN string (rank 8), ranks are counted from the a-file
+    pub fn to_algebraic(&self) -> String {
+        format!(
+            "{}{}",
+            (b'a' + self.rank as u8) as char,
+            BOARD_SIZE - self.file
+        )
+    }
+
+    // The inverse of to_algebraic, such as "e4"
+    pub fn from_algebraic(algebraic: &str) -> Result<Self, String> {
+        let chars = algebraic.chars().collect::<Vec<_>>();
+
+        match chars[..] {
+            [file_chr @ 'a'..='h', rank_chr @ '1'..='8'] => Ok(Self::new(
+                BOARD_SIZE - (rank_chr as u8 - b'0') as usize,
+                (file_chr as u8 - b'a') as usize,
+            )),
+            _ => Err(format!("Invalid square: \"{algebraic}\"")),
+        }
+    }
+}
+
+impl FromStr for TilePos {
+    type Err = String;
+
+    fn from_str(algebraic: &str) -> Result<Self, Self::Err> {
+        TilePos::from_algebraic(algebraic)
+    }
 }
 
-#[derive(Resource)]
+#[derive(Resource, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Board {
     pub positions: BitBoards,
     pub player: Player,
+    dimensions: BoardDimensions,
     castling_rights: [(bool, bool); COLOUR_AMT],
+    // The ranks which the king and (king side, queen side) rooks start on, which only differ from normal in Chess960
+    king_start_rank: usize,
+    castling_rook_ranks: (usize, usize),
     en_passant_on_last_move: Option<TilePos>,
     pub half_move_counter: usize,
     pub full_move_counter: usize,
+    // Entities only exist while the app is running, so are relinked when the pieces are spawned
+    #[cfg_attr(feature = "serde", serde(skip))]
     entities: [[Option<Entity>; BOARD_SIZE]; BOARD_SIZE],
+    move_history: PieceMoveHistory,
+    // The legal moves of the player to move, worked out the first time they're asked for and cleared whenever the position changes
+    #[cfg_attr(feature = "serde", serde(skip))]
+    legal_move_cache: Option<HashMap<TilePos, Vec<TilePos>>>,
+}
+
+// What happened as a result of a move, so that the display can be updated to match
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct MoveOutcome {
+    pub captured: Option<(Piece, TilePos)>,
+    pub captured_entity: Option<Entity>,
+    pub castling_rook_move: Option<PieceMove>,
+    pub promotion: Option<Piece>,
+    // Where the moved piece ended up, which is only different from the move when castling onto a rook
+    pub destination: TilePos,
+    // Whether the move put the other player in check
+    pub check: bool,
+}
+
+// What a null move changed, so that it can be undone
+// The side to move key of the zobrist hash is restored along with the player, since the hash is worked out from the board
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct NullMoveUndo {
+    player: Player,
+    en_passant_on_last_move: Option<TilePos>,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum MoveError {
+    NoPiece,
+    Illegal,
 }
 
+impl fmt::Display for MoveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Could not move piece:")?;
+
+        match self {
+            MoveError::NoPiece => write!(f, "There is no piece to move"),
+            MoveError::Illegal => write!(f, "Move is not legal in this position"),
+        }
+    }
+}
+
+impl Error for MoveError {}
+
 impl Default for Board {
     fn default() -> Self {
         const DEFAULT_FEN: &str = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1";
@@ -45,27 +182,140 @@ impl Default for Board {
     }
 }
 
+#[derive(Clone, PartialEq, Eq, Debug)]
+// Each error holds the section of the FEN string it was found in, or the whole string if there isn't one section to blame
+pub enum FenError {
+    InvalidPiece { chr: char, placement: String },
+    InvalidPlayer(String),
+    InvalidCastling(String),
+    InvalidEnPassant(String),
+    InvalidCounter(String),
+    InvalidDimensions(String),
+    TooFewSections(String),
+    IllegalPosition(Vec<PositionError>),
+}
+
+impl fmt::Display for FenError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Could not create board using FEN string:")?;
+
+        match self {
+            FenError::InvalidPiece { chr, placement } => write!(
+                f,
+                "'{chr}' in \"{placement}\" is not algebraic notation for any piece"
+            ),
+            FenError::InvalidPlayer(player) => write!(f, "\"{player}\" is not a valid player"),
+            FenError::InvalidCastling(castling) => write!(
+                f,
+                "\"{castling}\" does not provide valid castling rights information"
+            ),
+            FenError::InvalidEnPassant(algebraic) => {
+                write!(f, "\"{algebraic}\" is not a valid en passant square")
+            }
+            FenError::InvalidCounter(counter) => {
+                write!(f, "\"{counter}\" is not a valid move counter")
+            }
+            FenError::InvalidDimensions(placement) => write!(
+                f,
+                "\"{placement}\" must fit within {BOARD_SIZE}x{BOARD_SIZE} squares, with no file longer than the first"
+            ),
+            FenError::TooFewSections(fen) => {
+                write!(f, "\"{fen}\" does not contain enough sections")
+            }
+            FenError::IllegalPosition(errors) => write!(
+                f,
+                "{}",
+                errors
+                    .iter()
+                    .map(ToString::to_string)
+                    .collect::<Vec<_>>()
+                    .join("\n")
+            ),
+        }
+    }
+}
+
+impl Error for FenError {}
+
+// The section of a FEN string at this index, where sections are separated by spaces
+fn get_fen_section(fen: &str, index: usize) -> String {
+    fen.split(' ').nth(index).unwrap_or_default().to_string()
+}
+
+// Boards are equal when they hold the same position, regardless of the entities on screen or how the position was reached
+impl PartialEq for Board {
+    fn eq(&self, other: &Self) -> bool {
+        self.positions == other.positions
+            && self.player == other.player
+            && self.dimensions == other.dimensions
+            && self.castling_rights == other.castling_rights
+            && self.king_start_rank == other.king_start_rank
+            && self.castling_rook_ranks == other.castling_rook_ranks
+            && self.en_passant_on_last_move == other.en_passant_on_last_move
+            && self.half_move_counter == other.half_move_counter
+            && self.full_move_counter == other.full_move_counter
+    }
+}
+
+impl Eq for Board {}
+
+impl Hash for Board {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.positions.hash(state);
+        self.player.hash(state);
+        self.dimensions.hash(state);
+        self.castling_rights.hash(state);
+        self.king_start_rank.hash(state);
+        self.castling_rook_ranks.hash(state);
+        self.en_passant_on_last_move.hash(state);
+        self.half_move_counter.hash(state);
+        self.full_move_counter.hash(state);
+    }
+}
+
+impl FromStr for Board {
+    type Err = FenError;
+
+    fn from_str(fen: &str) -> Result<Self, Self::Err> {
+        Board::from_fen(fen)
+    }
+}
+
 impl Board {
-    fn from_fen<T: AsRef<str>>(fen_string: T) -> Result<Self, String> {
-        let fen = fen_string.as_ref();
+    pub fn from_fen<T: AsRef<str>>(fen_string: T) -> Result<Self, FenError> {
+        // Sections after the player are optional, and missing ones are left at their defaults
+        let fen = fen_string.as_ref().trim();
 
         let mut section_index = 0;
+        let mut has_player = false;
 
+        // Smaller boards are placed in the bottom left corner
+        let dimensions = Self::get_fen_dimensions(fen)
+            .ok_or_else(|| FenError::InvalidDimensions(get_fen_section(fen, 0)))?;
         let mut rank = 0;
-        let mut file = 0;
+        let mut file = dimensions.get_top_file();
 
         let mut board = Board {
             // squares: [[Piece::None; BOARD_SIZE]; BOARD_SIZE],
             positions: BitBoards::default(),
             player: Player::default(),
+            dimensions,
             castling_rights: [(false, false); COLOUR_AMT],
+            king_start_rank: KING_START_RANK,
+            castling_rook_ranks: (BOARD_SIZE - 1, 0),
             en_passant_on_last_move: None,
             half_move_counter: 0,
             full_move_counter: 1,
             entities: [[None; BOARD_SIZE]; BOARD_SIZE],
+            move_history: PieceMoveHistory::default(),
+            legal_move_cache: None,
         };
 
-        for (chr_index, chr) in fen.char_indices() {
+        let mut algebraic_en_passant = String::new();
+        let mut half_move_digits = String::new();
+        let mut full_move_digits = String::new();
+
+        for chr in fen.chars() {
             match section_index {
                 // Read positions from FEN
                 0 => match chr {
@@ -75,25 +325,41 @@ impl Board {
                     }
                     '1'..='8' => rank += (chr as u8 - b'0') as usize,
                     ' ' => section_index += 1,
+                    // Empty squares are only written as digits
                     _ => {
-                        if let Some(piece) = Piece::from_algebraic(chr) {
+                        if let Some(piece) =
+                            Piece::from_algebraic(chr).filter(|&piece| piece != Piece::None)
+                        {
                             let tile_pos = TilePos::new(file, rank);
+                            if !dimensions.contains(tile_pos) {
+                                return Err(FenError::InvalidDimensions(get_fen_section(fen, 0)));
+                            }
+
                             board.set_piece(tile_pos, piece);
                             board.positions[piece].set_bit_at(tile_pos, true);
 
                             rank += 1;
                         } else {
-                            return Err(format!("Could not create board using FEN string [{fen}]:\n'{chr}' is not algebraic notation for any piece"));
+                            return Err(FenError::InvalidPiece {
+                                chr,
+                                placement: get_fen_section(fen, 0),
+                            });
                         }
                     }
                 },
-                // Read the current player's turn from FEN
+                // Read the current player's turn from FEN, which is a single character
                 1 => match chr {
-                    'w' => board.player = Player::White,
-                    'b' => board.player = Player::Black,
-                    ' ' => section_index += 1,
+                    'w' | 'b' if !has_player => {
+                        board.player = if chr == 'w' {
+                            Player::White
+                        } else {
+                            Player::Black
+                        };
+                        has_player = true;
+                    }
+                    ' ' if has_player => section_index += 1,
                     _ => {
-                        return Err(format!("Could not create board using FEN string [{fen}]:\n'{chr}' is not a valid player"));
+                        return Err(FenError::InvalidPlayer(get_fen_section(fen, 1)));
                     }
                 },
                 // Read the castling rights from FEN
@@ -105,37 +371,158 @@ impl Board {
                     '-' => board.castling_rights = [(false, false); COLOUR_AMT],
                     ' ' => section_index += 1,
                     _ => {
-                        return Err(format!("Could not create board using FEN string [{fen}]:\n'{chr}' does not provide valid castling rights information"));
+                        return Err(FenError::InvalidCastling(get_fen_section(fen, 2)));
                     }
                 },
                 // Reached the en passant part of FEN
                 3 => match chr {
                     '-' => board.en_passant_on_last_move = None,
-                    ' ' => section_index += 1,
-                    _ => {
-                        let algebraic_en_passant =
-                            fen.chars().skip(chr_index - 1).take(2).collect::<Vec<_>>();
-
-                        match (algebraic_en_passant[0], algebraic_en_passant[1]) {
-                            ('a'..='h', '0'..='8') => {
-                                board.en_passant_on_last_move = Some(TilePos::new(
-                                    (algebraic_en_passant[0] as u8 - b'a') as usize,
-                                    (algebraic_en_passant[1] as u8 - b'0') as usize,
-                                ));
-                            }
-                            _ => {
-                                return Err(format!("Could not create board using FEN string [{fen}]:\n\"{}{}\" is not a valid en passant square", algebraic_en_passant[0], algebraic_en_passant[1]));
-                            }
+                    ' ' => {
+                        if !algebraic_en_passant.is_empty() {
+                            board.en_passant_on_last_move =
+                                Some(Self::en_passant_from_fen(&algebraic_en_passant)?);
                         }
+
+                        section_index += 1;
                     }
+                    _ => algebraic_en_passant.push(chr),
+                },
+                // Read the half move counter from FEN
+                4 => match chr {
+                    ' ' => section_index += 1,
+                    _ => half_move_digits.push(chr),
+                },
+                // Read the full move counter from FEN
+                5 => match chr {
+                    ' ' => section_index += 1,
+                    _ => full_move_digits.push(chr),
                 },
                 _ => break,
             }
         }
 
+        // The FEN string may end directly after the en passant square
+        if section_index == 3 && !algebraic_en_passant.is_empty() {
+            board.en_passant_on_last_move = Some(Self::en_passant_from_fen(&algebraic_en_passant)?);
+        }
+
+        // The board and player sections are always required
+        if !has_player {
+            return Err(FenError::TooFewSections(fen.to_string()));
+        }
+
+        if !half_move_digits.is_empty() {
+            board.half_move_counter = Self::counter_from_fen(half_move_digits)?;
+        }
+        if !full_move_digits.is_empty() {
+            board.full_move_counter = Self::counter_from_fen(full_move_digits)?;
+        }
+
+        board.find_castling_ranks();
+        board.move_history = PieceMoveHistory::new(board.zobrist_hash());
+
         Ok(board)
     }
 
+    // The number of files in the piece placement, and the number of ranks in its first file
+    fn get_fen_dimensions(fen: &str) -> Option<BoardDimensions> {
+        let placement = fen.split(' ').next().unwrap_or_default();
+        let first_file = placement.split('/').next().unwrap_or_default();
+
+        let ranks = first_file
+            .chars()
+            .map(|chr| chr.to_digit(10).map_or(1, |digit| digit as usize))
+            .sum();
+
+        BoardDimensions::try_new(placement.split('/').count(), ranks)
+    }
+
+    fn en_passant_from_fen(algebraic: &str) -> Result<TilePos, FenError> {
+        TilePos::from_algebraic(algebraic)
+            .map_err(|_| FenError::InvalidEnPassant(algebraic.to_string()))
+    }
+
+    // Counters are only written with digits, so signs such as "+5" aren't allowed even though they parse
+    fn counter_from_fen(digits: String) -> Result<usize, FenError> {
+        if !digits.chars().all(|chr| chr.is_ascii_digit()) {
+            return Err(FenError::InvalidCounter(digits));
+        }
+
+        digits.parse().map_err(|_| FenError::InvalidCounter(digits))
+    }
+
+    pub fn to_fen(&self) -> String {
+        let mut fen = String::new();
+
+        // Write positions, collapsing runs of empty squares into digits
+        for file in self.dimensions.get_top_file()..BOARD_SIZE {
+            let mut empty_count = 0;
+
+            for rank in 0..self.dimensions.ranks {
+                let piece = self.get_piece(TilePos::new(file, rank));
+
+                if piece == Piece::None {
+                    empty_count += 1;
+                } else {
+                    if empty_count > 0 {
+                        fen += empty_count.to_string().as_str();
+                        empty_count = 0;
+                    }
+
+                    fen.push(piece.to_algebraic());
+                }
+            }
+
+            if empty_count > 0 {
+                fen += empty_count.to_string().as_str();
+            }
+
+            if file < BOARD_SIZE - 1 {
+                fen.push('/');
+            }
+        }
+
+        // Write the current player's turn
+        fen.push(' ');
+        fen.push(match self.player {
+            Player::White => 'w',
+            Player::Black => 'b',
+        });
+
+        // Write the castling rights
+        fen.push(' ');
+        let castling_len = fen.len();
+        for (player_index, (king_side, queen_side)) in self.castling_rights.iter().enumerate() {
+            let (king_chr, queen_chr) = if player_index == Player::White as usize {
+                ('K', 'Q')
+            } else {
+                ('k', 'q')
+            };
+
+            if *king_side {
+                fen.push(king_chr);
+            }
+            if *queen_side {
+                fen.push(queen_chr);
+            }
+        }
+        if fen.len() == castling_len {
+            fen.push('-');
+        }
+
+        // Write the en passant square
+        fen.push(' ');
+        match self.en_passant_on_last_move {
+            Some(tile_pos) => fen += tile_pos.to_algebraic().as_str(),
+            None => fen.push('-'),
+        }
+
+        // Write the move counters
+        fen += format!(" {} {}", self.half_move_counter, self.full_move_counter).as_str();
+
+        fen
+    }
+
     pub fn get_piece(&self, tile_pos: TilePos) -> Piece {
         for i in 0..(PIECE_AMT * COLOUR_AMT) {
             if self.positions[Into::<Piece>::into(i)].get_bit_at(tile_pos) {
@@ -146,7 +533,36 @@ impl Board {
         Piece::None
     }
 
+    // Every square with a piece on it
+    pub fn occupancy(&self) -> BitBoard {
+        let mut occupancy = BitBoard::default();
+        for i in 0..(PIECE_AMT * COLOUR_AMT) {
+            occupancy |= self.positions[Into::<Piece>::into(i)];
+        }
+
+        occupancy
+    }
+
+    // Every square with one of this player's pieces on it
+    pub fn occupancy_of(&self, player: Player) -> BitBoard {
+        let mut occupancy = BitBoard::default();
+        for i in 0..(PIECE_AMT * COLOUR_AMT) {
+            let piece = Into::<Piece>::into(i);
+            if piece.to_player() == Some(player) {
+                occupancy |= self.positions[piece];
+            }
+        }
+
+        occupancy
+    }
+
+    pub fn is_empty(&self, tile_pos: TilePos) -> bool {
+        !self.occupancy().get_bit_at(tile_pos)
+    }
+
     pub fn set_piece(&mut self, tile_pos: TilePos, piece: Piece) {
+        self.invalidate_legal_moves();
+
         // Clear all the other bitboards at this position, except this piece's position bitboard
         for i in 0..(PIECE_AMT * COLOUR_AMT) {
             let piece_i = Into::<Piece>::into(i);
@@ -158,11 +574,1087 @@ impl Board {
         }
     }
 
+    pub fn get_king_pos(&self, player: Player) -> Option<TilePos> {
+        let king = match player {
+            Player::White => Piece::WKing,
+            Player::Black => Piece::BKing,
+        };
+
+        self.positions[king].iter().next()
+    }
+
+    // Get the (king side, queen side) castling rights for this player
+    pub fn get_castling_rights(&self, player: Player) -> (bool, bool) {
+        self.castling_rights[player as usize]
+    }
+
+    pub fn set_castling_rights(&mut self, player: Player, castling_rights: (bool, bool)) {
+        self.castling_rights[player as usize] = castling_rights;
+        self.invalidate_legal_moves();
+    }
+
+    pub fn get_dimensions(&self) -> BoardDimensions {
+        self.dimensions
+    }
+
+    pub fn get_king_start_rank(&self) -> usize {
+        self.king_start_rank
+    }
+
+    // Get the ranks of the (king side, queen side) rooks which can castle
+    pub fn get_castling_rook_ranks(&self) -> (usize, usize) {
+        self.castling_rook_ranks
+    }
+
+    pub fn get_en_passant_on_last_move(&self) -> Option<TilePos> {
+        self.en_passant_on_last_move
+    }
+
+    pub fn set_en_passant_on_last_move(&mut self, en_passant: Option<TilePos>) {
+        self.en_passant_on_last_move = en_passant;
+        self.invalidate_legal_moves();
+    }
+
+    // Find which square this piece's entity is on
+    pub fn get_entity_pos(&self, entity: Entity) -> Option<TilePos> {
+        (0..BOARD_SIZE)
+            .flat_map(|file| (0..BOARD_SIZE).map(move |rank| TilePos::new(file, rank)))
+            .find(|&tile_pos| self.get_entity(tile_pos) == Some(entity))
+    }
+
+    // Positions off the board never have an entity
     pub fn get_entity(&self, tile_pos: TilePos) -> Option<Entity> {
+        if !tile_pos.is_on_board() {
+            return None;
+        }
+
         self.entities[tile_pos.file][tile_pos.rank]
     }
 
+    // Panics if the position is off the board, since the entity would be lost
     pub fn set_entity(&mut self, tile_pos: TilePos, entity: Option<Entity>) {
         self.entities[tile_pos.file][tile_pos.rank] = entity;
     }
+
+    // Get the legal moves from this square, which are cached for the player to move until the position changes
+    // Squares without a piece belonging to the player to move have no cached moves
+    pub fn legal_moves_from(&mut self, from: TilePos) -> &[TilePos] {
+        if self.legal_move_cache.is_none() {
+            let cache = self
+                .occupancy_of(self.player)
+                .iter()
+                .map(|from| (from, self.get_legal_moves(from)))
+                .collect();
+            self.legal_move_cache = Some(cache);
+        }
+
+        self.legal_move_cache
+            .as_ref()
+            .and_then(|cache| cache.get(&from))
+            .map_or(&[], Vec::as_slice)
+    }
+
+    fn invalidate_legal_moves(&mut self) {
+        self.legal_move_cache = None;
+    }
+
+    // Apply a move, so long as it is legal for the player whose turn it is
+    pub fn move_piece(&mut self, piece_move: PieceMove) -> Result<MoveOutcome, MoveError> {
+        let piece = self.get_piece(piece_move.from);
+        if piece == Piece::None {
+            return Err(MoveError::NoPiece);
+        }
+
+        if piece.to_player() != Some(self.player)
+            || !self
+                .legal_moves_from(piece_move.from)
+                .contains(&piece_move.to)
+        {
+            return Err(MoveError::Illegal);
+        }
+
+        // Pawns can only be promoted to one of their own queen, rook, bishop, or knight,
+        // and flagging drops the promotion from any move which isn't a promotion, so it is read first
+        let promotion = piece_move.get_promotion();
+        let piece_move = self.flag_move(piece_move);
+        match piece_move.flag {
+            MoveFlag::Promotion(promotion)
+                if !PROMOTION_PIECE_TYPES
+                    .map(|piece_type| Piece::new(piece_type, self.player))
+                    .contains(&promotion) =>
+            {
+                return Err(MoveError::Illegal)
+            }
+            MoveFlag::Promotion(_) => {}
+            _ if promotion.is_some() => return Err(MoveError::Illegal),
+            _ => {}
+        }
+
+        Ok(self.apply_move(piece_move))
+    }
+
+    // Apply a move without checking that it is legal, flagging it first if it hasn't been
+    // A chosen promotion is flagged again, in case the move isn't a promotion at all
+    pub fn apply_move(&mut self, piece_move: PieceMove) -> MoveOutcome {
+        self.invalidate_legal_moves();
+
+        let piece_move = match piece_move.flag {
+            MoveFlag::Quiet | MoveFlag::Promotion(_) => self.flag_move(piece_move),
+            _ => piece_move,
+        };
+        let piece = self.get_piece(piece_move.from);
+        let entity = self.get_entity(piece_move.from);
+
+        let castling = match piece_move.flag {
+            MoveFlag::Castle(_) => self.get_castling_piece_moves(piece_move),
+            _ => None,
+        };
+        let to = castling.map_or(piece_move.to, |(king_move, _)| king_move.to);
+
+        let mut record = PieceMoveRecord {
+            piece_move,
+            piece,
+            captured: None,
+            castling,
+            castling_rights: self.castling_rights,
+            en_passant_on_last_move: self.en_passant_on_last_move,
+            half_move_counter: self.half_move_counter,
+            full_move_counter: self.full_move_counter,
+            hash: 0,
+        };
+
+        // Keep hold of the captured entity before the moving piece's entity replaces it (castling onto a rook isn't a capture)
+        let mut captured_entity = None;
+        if castling.is_none() && self.get_piece(piece_move.to) != Piece::None {
+            record.captured = Some((self.get_piece(piece_move.to), piece_move.to));
+            captured_entity = self.get_entity(piece_move.to);
+        }
+
+        // A pawn moving diagonally onto the empty en passant square captures the pawn beside it
+        if piece_move.flag == MoveFlag::EnPassant {
+            let captured_pos = TilePos::new(piece_move.from.file, piece_move.to.rank);
+
+            record.captured = Some((self.get_piece(captured_pos), captured_pos));
+            captured_entity = self.get_entity(captured_pos);
+            self.set_piece(captured_pos, Piece::None);
+            self.set_entity(captured_pos, None);
+        }
+
+        // The half move counter is reset by pawn moves and captures
+        if piece.piece_type() == Some(PieceType::Pawn) || record.captured.is_some() {
+            self.half_move_counter = 0;
+        } else {
+            self.half_move_counter += 1;
+        }
+
+        // Castling also moves the rook to the other side of the king, which is taken off the board first in case they swap squares
+        let castling_rook =
+            castling.map(|(_, rook_move)| (rook_move, self.take_piece(rook_move.from)));
+
+        // Moving the king or a rook, or capturing a rook, revokes castling rights
+        self.update_castling_rights(piece_move);
+
+        // Pawns reaching the final file are promoted
+        let placed_piece = match piece_move.flag {
+            MoveFlag::Promotion(promotion) => promotion,
+            _ => piece,
+        };
+
+        // Move the piece and its entity, leaving the original square empty
+        self.take_piece(piece_move.from);
+        self.put_piece(to, (placed_piece, entity));
+
+        if let Some((rook_move, rook)) = castling_rook {
+            self.put_piece(rook_move.to, rook);
+        }
+
+        // A pawn which moved two squares can be captured on the square it skipped over
+        self.en_passant_on_last_move = match piece_move.flag {
+            MoveFlag::DoublePawnPush => piece_move.from.offset(Self::get_vertical_dir(piece), 0),
+            _ => None,
+        };
+
+        self.next_player();
+
+        let outcome = MoveOutcome {
+            captured: record.captured,
+            captured_entity,
+            castling_rook_move: castling.map(|(_, rook_move)| rook_move),
+            promotion: (placed_piece != piece).then_some(placed_piece),
+            destination: to,
+            check: self.is_in_check(self.player),
+        };
+
+        record.hash = self.zobrist_hash();
+        self.move_history.push(record);
+
+        outcome
+    }
+
+    // Take back the last move, restoring any captured piece (without an entity)
+    pub fn undo_move(&mut self) -> Option<PieceMove> {
+        let record = self.move_history.undo()?;
+        let piece_move = record.piece_move;
+        self.invalidate_legal_moves();
+
+        // Take a castled rook off the board first, since it may be on the king's starting square
+        let castling_rook = record
+            .castling
+            .map(|(_, rook_move)| (rook_move, self.take_piece(rook_move.to)));
+        let to = record
+            .castling
+            .map_or(piece_move.to, |(king_move, _)| king_move.to);
+
+        // Move the piece back, undoing any promotion
+        let (_, entity) = self.take_piece(to);
+        self.put_piece(piece_move.from, (record.piece, entity));
+
+        if let Some((captured_piece, captured_pos)) = record.captured {
+            self.set_piece(captured_pos, captured_piece);
+        }
+
+        // Move the rook back to its starting square if this move was castling
+        if let Some((rook_move, rook)) = castling_rook {
+            self.put_piece(rook_move.from, rook);
+        }
+
+        self.castling_rights = record.castling_rights;
+        self.en_passant_on_last_move = record.en_passant_on_last_move;
+        self.half_move_counter = record.half_move_counter;
+        self.full_move_counter = record.full_move_counter;
+        self.player = record.piece.to_player().unwrap_or_default();
+
+        Some(piece_move)
+    }
+
+    // The moves played to reach this position, which can't be changed other than by making and undoing moves
+    pub fn get_move_history(&self) -> &PieceMoveHistory {
+        &self.move_history
+    }
+
+    pub fn get_last_move(&self) -> Option<PieceMove> {
+        self.move_history.get_last_move()
+    }
+
+    pub fn get_ply(&self) -> usize {
+        self.move_history.get_ply()
+    }
+
+    pub fn get_redo_move(&self) -> Option<PieceMove> {
+        self.move_history.get_redo_move()
+    }
+
+    // The piece captured by the last move, and the square it was captured on
+    pub fn get_last_capture(&self) -> Option<(Piece, TilePos)> {
+        self.move_history.get_last_record()?.captured
+    }
+
+    // The piece which would be captured again by a redo
+    pub fn get_redo_capture(&self) -> Option<(Piece, TilePos)> {
+        self.move_history.get_redo_record()?.captured
+    }
+
+    // Replay the last move which was undone
+    pub fn redo_move(&mut self) -> Option<PieceMove> {
+        let piece_move = self.get_redo_move()?;
+        self.apply_move(piece_move);
+
+        Some(piece_move)
+    }
+
+    // Remove the piece and its entity from this square, returning them
+    fn take_piece(&mut self, tile_pos: TilePos) -> (Piece, Option<Entity>) {
+        let taken = (self.get_piece(tile_pos), self.get_entity(tile_pos));
+
+        self.set_piece(tile_pos, Piece::None);
+        self.set_entity(tile_pos, None);
+
+        taken
+    }
+
+    fn put_piece(&mut self, tile_pos: TilePos, (piece, entity): (Piece, Option<Entity>)) {
+        self.set_piece(tile_pos, piece);
+        self.set_entity(tile_pos, entity);
+    }
+
+    // Either player can claim a draw once the position repeats three times
+    pub fn is_threefold_repetition(&self) -> bool {
+        self.move_history.count_position(self.zobrist_hash()) >= 3
+    }
+
+    // The game is drawn without a claim once the position repeats five times
+    pub fn is_fivefold_repetition(&self) -> bool {
+        self.move_history.count_position(self.zobrist_hash()) >= 5
+    }
+
+    // How many of this piece are on the board, where empty squares aren't counted
+    pub fn piece_count(&self, piece: Piece) -> u32 {
+        match piece {
+            Piece::None => 0,
+            _ => self.positions[piece].count(),
+        }
+    }
+
+    // How many pieces this player has on the board, including their king
+    pub fn count_player(&self, player: Player) -> u32 {
+        (0..PIECE_AMT * COLOUR_AMT)
+            .map(Into::<Piece>::into)
+            .filter(|piece| piece.to_player() == Some(player))
+            .map(|piece| self.piece_count(piece))
+            .sum()
+    }
+
+    // Neither player has enough pieces left to deliver checkmate
+    pub fn is_insufficient_material(&self) -> bool {
+        let heavy_pieces = [
+            Piece::WPawn,
+            Piece::BPawn,
+            Piece::WRook,
+            Piece::BRook,
+            Piece::WQueen,
+            Piece::BQueen,
+        ];
+        if heavy_pieces
+            .iter()
+            .any(|&piece| self.piece_count(piece) > 0)
+        {
+            return false;
+        }
+
+        let knights = self.piece_count(Piece::WKnight) + self.piece_count(Piece::BKnight);
+        let bishops = self.piece_count(Piece::WBishop) + self.piece_count(Piece::BBishop);
+
+        // A lone minor piece can't mate, and neither can any number of bishops on one square colour
+        if knights + bishops <= 1 {
+            return true;
+        }
+        if knights > 0 {
+            return false;
+        }
+
+        let mut bishop_square_colours = self.positions[Piece::WBishop]
+            .iter()
+            .chain(self.positions[Piece::BBishop].iter())
+            .map(|tile_pos| (tile_pos.file + tile_pos.rank) % 2);
+
+        let first_colour = bishop_square_colours.next();
+        bishop_square_colours.all(|colour| Some(colour) == first_colour)
+    }
+
+    // This player could never checkmate, since they only have a king and at most one minor piece
+    pub fn has_insufficient_material(&self, player: Player) -> bool {
+        let count = |piece_type| self.piece_count(Piece::new(piece_type, player));
+
+        [PieceType::Pawn, PieceType::Rook, PieceType::Queen]
+            .into_iter()
+            .all(|piece_type| count(piece_type) == 0)
+            && count(PieceType::Knight) + count(PieceType::Bishop) <= 1
+    }
+
+    // Fifty moves by each player without a pawn move or a capture, after which either player can claim a draw
+    pub fn is_fifty_move_draw(&self) -> bool {
+        self.half_move_counter >= 100
+    }
+
+    // Seventy-five moves by each player without a pawn move or a capture, which draws the game without a claim
+    pub fn is_seventy_five_move_draw(&self) -> bool {
+        self.half_move_counter >= 150
+    }
+
+    pub fn next_player(&mut self) {
+        // A full move is completed once Black has moved
+        if self.player == Player::Black {
+            self.full_move_counter += 1;
+        }
+
+        self.player = self.get_next_player();
+        self.invalidate_legal_moves();
+    }
+
+    pub fn get_next_player(&self) -> Player {
+        self.player.opponent()
+    }
+
+    // Give the turn to this player without a move being made, which loses the chance to capture en passant
+    pub fn pass_turn_to(&mut self, player: Player) {
+        if self.player != player {
+            self.player = player;
+            self.en_passant_on_last_move = None;
+            self.invalidate_legal_moves();
+        }
+    }
+
+    // Pass the turn without moving a piece, for null move pruning in the search
+    // A player in check can't pass, since the other player could then take their king, so callers must check first
+    pub fn make_null_move(&mut self) -> NullMoveUndo {
+        debug_assert!(
+            !self.is_in_check(self.player),
+            "Null move made while in check"
+        );
+
+        let undo = NullMoveUndo {
+            player: self.player,
+            en_passant_on_last_move: self.en_passant_on_last_move,
+        };
+
+        self.pass_turn_to(self.get_next_player());
+
+        undo
+    }
+
+    pub fn undo_null_move(&mut self, undo: NullMoveUndo) {
+        self.player = undo.player;
+        self.en_passant_on_last_move = undo.en_passant_on_last_move;
+        self.invalidate_legal_moves();
+    }
+
+    // If this move is a king castling, get the move which the rook makes alongside it
+    pub fn get_castling_rook_move(&self, piece_move: PieceMove) -> Option<PieceMove> {
+        self.get_castling_piece_moves(piece_move)
+            .map(|(_, rook_move)| rook_move)
+    }
+
+    // If this move is a king castling, get where the king and rook actually move to
+    // Castling is written as the king moving two squares, or as the king moving onto its own rook when it
+    // doesn't start two squares away from where it lands (as can happen in Chess960)
+    pub fn get_castling_piece_moves(
+        &self,
+        piece_move: PieceMove,
+    ) -> Option<(PieceMove, PieceMove)> {
+        let king = self.get_piece(piece_move.from);
+        let player = king.to_player()?;
+        let home_file = self.get_home_file(player);
+
+        if king.piece_type() != Some(PieceType::King)
+            || piece_move.from != TilePos::new(home_file, self.king_start_rank)
+            || piece_move.to.file != home_file
+        {
+            return None;
+        }
+
+        let (king_side_rook, queen_side_rook) = self.castling_rook_ranks;
+        let is_onto_rook = self.get_piece(piece_move.to) == Piece::new(PieceType::Rook, player);
+        let is_king_side = if is_onto_rook && piece_move.to.rank == king_side_rook {
+            true
+        } else if is_onto_rook && piece_move.to.rank == queen_side_rook {
+            false
+        } else if piece_move.from.rank.abs_diff(piece_move.to.rank) == 2 {
+            piece_move.to.rank > piece_move.from.rank
+        } else {
+            return None;
+        };
+
+        let (king_to, rook_from, rook_to) = if is_king_side {
+            (CASTLING_KING_RANKS.0, king_side_rook, CASTLING_ROOK_RANKS.0)
+        } else {
+            (
+                CASTLING_KING_RANKS.1,
+                queen_side_rook,
+                CASTLING_ROOK_RANKS.1,
+            )
+        };
+
+        // Moving two squares is only castling if the king lands where it would after castling
+        if !is_onto_rook && piece_move.to.rank != king_to {
+            return None;
+        }
+
+        Some((
+            PieceMove::new(piece_move.from, TilePos::new(home_file, king_to)),
+            PieceMove::new(
+                TilePos::new(home_file, rook_from),
+                TilePos::new(home_file, rook_to),
+            ),
+        ))
+    }
+
+    // Castling rights in FEN don't say which rooks they refer to, so use the outermost rook on each side of the king (as in X-FEN)
+    fn find_castling_ranks(&mut self) {
+        for player in [Player::White, Player::Black] {
+            let (king_side, queen_side) = self.get_castling_rights(player);
+            let home_file = self.get_home_file(player);
+
+            let Some(king_pos) = self
+                .get_king_pos(player)
+                .filter(|king_pos| king_pos.file == home_file && (king_side || queen_side))
+            else {
+                continue;
+            };
+
+            let rook = Piece::new(PieceType::Rook, player);
+            let rook_ranks: Vec<usize> = (0..BOARD_SIZE)
+                .filter(|&rank| self.get_piece(TilePos::new(home_file, rank)) == rook)
+                .collect();
+
+            self.king_start_rank = king_pos.rank;
+            if let Some(&rank) = rook_ranks.iter().rfind(|&&rank| rank > king_pos.rank) {
+                self.castling_rook_ranks.0 = rank;
+            }
+            if let Some(&rank) = rook_ranks.iter().find(|&&rank| rank < king_pos.rank) {
+                self.castling_rook_ranks.1 = rank;
+            }
+
+            return;
+        }
+    }
+
+    fn update_castling_rights(&mut self, piece_move: PieceMove) {
+        self.invalidate_legal_moves();
+
+        match self.get_piece(piece_move.from) {
+            Piece::WKing => self.castling_rights[Player::White as usize] = (false, false),
+            Piece::BKing => self.castling_rights[Player::Black as usize] = (false, false),
+            _ => {}
+        }
+
+        // Any move from or onto a rook's starting square means that rook can no longer castle
+        for tile_pos in [piece_move.from, piece_move.to] {
+            let player = match tile_pos.file {
+                f if f == self.get_home_file(Player::Black) => Player::Black,
+                f if f == self.get_home_file(Player::White) => Player::White,
+                _ => continue,
+            };
+
+            if tile_pos.rank == self.castling_rook_ranks.0 {
+                self.castling_rights[player as usize].0 = false;
+            } else if tile_pos.rank == self.castling_rook_ranks.1 {
+                self.castling_rights[player as usize].1 = false;
+            }
+        }
+    }
+
+    // The file which this player's pieces start on
+    pub fn get_home_file(&self, player: Player) -> usize {
+        match player {
+            Player::White => BOARD_SIZE - 1,
+            Player::Black => self.dimensions.get_top_file(),
+        }
+    }
+
+    // White pawns move towards file 0, black pawns move towards file BOARD_SIZE - 1
+    pub fn get_vertical_dir(piece: Piece) -> isize {
+        if piece.is_white() {
+            -1
+        } else {
+            1
+        }
+    }
+
+    // Check if this move takes a pawn onto the final file for its colour
+    pub fn is_promotion_move(&self, piece_move: PieceMove) -> bool {
+        match self.get_piece(piece_move.from) {
+            Piece::WPawn => piece_move.to.file == self.dimensions.get_top_file(),
+            Piece::BPawn => piece_move.to.file == BOARD_SIZE - 1,
+            _ => false,
+        }
+    }
+
+    // Check if this piece is a pawn which is still on its starting file
+    pub fn double_pawn_move_check(&self, piece: Piece, from: TilePos) -> bool {
+        match piece {
+            Piece::WPawn => from.file == BOARD_SIZE - 2,
+            Piece::BPawn => from.file == self.dimensions.get_top_file() + 1,
+            _ => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::HashSet, hash::DefaultHasher};
+
+    use super::*;
+
+    #[test]
+    fn fen_round_trip() {
+        for fen in [
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+            "rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq e3 0 1",
+            "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+            "8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1",
+            "4k3/8/8/8/8/8/8/4K3 b - - 37 80",
+        ] {
+            assert_eq!(Board::from_fen(fen).unwrap().to_fen(), fen);
+        }
+    }
+
+    #[test]
+    fn fen_after_moves() {
+        let mut board = Board::default();
+        for (from, to) in [("e2", "e4"), ("c7", "c5"), ("g1", "f3")] {
+            board
+                .move_piece(PieceMove::new(
+                    TilePos::from_algebraic(from).unwrap(),
+                    TilePos::from_algebraic(to).unwrap(),
+                ))
+                .unwrap();
+        }
+
+        assert_eq!(
+            board.to_fen(),
+            "rnbqkbnr/pp1ppppp/8/2p5/4P3/5N2/PPPP1PPP/RNBQKB1R b KQkq - 1 2"
+        );
+    }
+
+    #[test]
+    fn fen_en_passant() {
+        for (fen, square) in [
+            ("4k3/8/8/8/4P3/8/8/4K3 b - e3 0 1", "e3"),
+            ("4k3/8/8/6pP/8/8/8/4K3 w - h6 0 1", "h6"),
+        ] {
+            assert_eq!(
+                Board::from_fen(fen).unwrap().get_en_passant_on_last_move(),
+                Some(TilePos::from_algebraic(square).unwrap())
+            );
+        }
+
+        for square in ["e9", "z3"] {
+            let Err(e) = Board::from_fen(format!("4k3/8/8/8/4P3/8/8/4K3 b - {square} 0 1")) else {
+                panic!("{square} was accepted as an en passant square");
+            };
+            assert_eq!(e, FenError::InvalidEnPassant(square.to_string()));
+        }
+    }
+
+    #[test]
+    fn fen_counters() {
+        let board = Board::from_fen("8/8/8/8/8/8/8/8 w - - 13 42").unwrap();
+        assert_eq!((board.half_move_counter, board.full_move_counter), (13, 42));
+
+        let Err(e) = Board::from_fen("4k3/8/8/8/8/8/8/4K3 w - - x 1") else {
+            panic!("x was accepted as a move counter");
+        };
+        assert_eq!(e, FenError::InvalidCounter("x".to_string()));
+    }
+
+    #[test]
+    fn algebraic_squares() {
+        assert_eq!(TilePos::from_algebraic("a1"), Ok(TilePos::new(7, 0)));
+        assert_eq!("h8".parse(), Ok(TilePos::new(0, 7)));
+        assert_eq!(TilePos::new(7, 0).to_algebraic(), "a1");
+
+        for algebraic in ["i2", "a9", "abc", ""] {
+            assert!(TilePos::from_algebraic(algebraic).is_err());
+        }
+    }
+
+    #[test]
+    fn square_indices() {
+        for index in 0..BOARD_SIZE * BOARD_SIZE {
+            assert_eq!(TilePos::from_index(index).to_index(), index);
+        }
+
+        for file in 0..BOARD_SIZE {
+            for rank in 0..BOARD_SIZE {
+                let tile_pos = TilePos::new(file, rank);
+                assert_eq!(TilePos::from_index(tile_pos.to_index()), tile_pos);
+            }
+        }
+    }
+
+    fn get_hash(board: &Board) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        board.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    #[test]
+    fn eq_and_hash_ignore_entities() {
+        let mut board = Board::default();
+        board
+            .move_piece(PieceMove::new(
+                TilePos::from_algebraic("g1").unwrap(),
+                TilePos::from_algebraic("f3").unwrap(),
+            ))
+            .unwrap();
+
+        // The same position without any history, and with a different entity on e1
+        let mut other = Board::from_fen(board.to_fen()).unwrap();
+        other.set_entity(
+            TilePos::from_algebraic("e1").unwrap(),
+            Some(Entity::from_raw(42)),
+        );
+        assert!(board == other);
+        assert_eq!(get_hash(&board), get_hash(&other));
+        assert!(HashSet::from([board.clone()]).contains(&other));
+
+        other.next_player();
+        assert!(board != other);
+    }
+
+    #[test]
+    fn piece_counts() {
+        let board = Board::default();
+        for player in [Player::White, Player::Black] {
+            assert_eq!(board.piece_count(Piece::new(PieceType::Pawn, player)), 8);
+            assert_eq!(board.count_player(player), 16);
+        }
+        assert_eq!(board.piece_count(Piece::WQueen), 1);
+        assert_eq!(board.piece_count(Piece::None), 0);
+
+        let board = Board::from_fen("4k3/8/8/8/8/8/8/R3K2N w - - 0 1").unwrap();
+        assert_eq!(
+            (
+                board.count_player(Player::White),
+                board.count_player(Player::Black)
+            ),
+            (3, 1)
+        );
+    }
+
+    fn sorted(mut squares: Vec<TilePos>) -> Vec<TilePos> {
+        squares.sort_by_key(TilePos::to_index);
+        squares
+    }
+
+    #[test]
+    fn legal_move_cache_matches_uncached() {
+        let mut board =
+            Board::from_fen("r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1")
+                .unwrap();
+
+        // Only the player to move has cached moves
+        for index in 0..BOARD_SIZE * BOARD_SIZE {
+            let from = TilePos::from_index(index);
+            let uncached = if board.get_piece(from).to_player() == Some(board.player) {
+                board.clone().get_legal_moves(from)
+            } else {
+                Vec::new()
+            };
+
+            assert_eq!(
+                sorted(board.legal_moves_from(from).to_vec()),
+                sorted(uncached)
+            );
+        }
+    }
+
+    #[test]
+    fn legal_move_cache_invalidated_by_moves() {
+        let mut board =
+            Board::from_fen("r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1")
+                .unwrap();
+        let (e5, f7, e8) = (
+            TilePos::from_algebraic("e5").unwrap(),
+            TilePos::from_algebraic("f7").unwrap(),
+            TilePos::from_algebraic("e8").unwrap(),
+        );
+        assert!(board.legal_moves_from(e8).is_empty());
+
+        board.move_piece(PieceMove::new(e5, f7)).unwrap();
+        assert!(board.legal_moves_from(f7).is_empty());
+        assert_eq!(
+            sorted(board.legal_moves_from(e8).to_vec()),
+            sorted(board.clone().get_legal_moves(e8))
+        );
+
+        board.undo_move();
+        assert!(board.legal_moves_from(e5).contains(&f7));
+    }
+
+    #[test]
+    fn null_move_round_trip() {
+        let fen = "rnbqkbnr/ppp1pppp/8/8/3pP3/8/PPPP1PPP/RNBQKBNR b KQkq e3 0 3";
+        let mut board = Board::from_fen(fen).unwrap();
+        let hash = board.zobrist_hash();
+
+        // Passing the turn loses the chance to capture en passant
+        let undo = board.make_null_move();
+        assert_eq!(
+            board.to_fen(),
+            "rnbqkbnr/ppp1pppp/8/8/3pP3/8/PPPP1PPP/RNBQKBNR w KQkq - 0 3"
+        );
+        assert_ne!(board.zobrist_hash(), hash);
+
+        board.undo_null_move(undo);
+        assert_eq!(board.to_fen(), fen);
+        assert_eq!(board.zobrist_hash(), hash);
+    }
+
+    #[test]
+    fn fen_without_trailing_sections() {
+        let four_fields =
+            Board::from_fen("rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq e3").unwrap();
+        assert_eq!(
+            four_fields.to_fen(),
+            "rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq e3 0 1"
+        );
+
+        let two_fields = Board::from_fen("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR b").unwrap();
+        assert_eq!(
+            two_fields.to_fen(),
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR b - - 0 1"
+        );
+
+        assert!(
+            Board::from_fen("  rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1\n").is_ok()
+        );
+    }
+
+    #[test]
+    fn fen_missing_player() {
+        const PLACEMENT: &str = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR";
+
+        for (fen, error) in [
+            (
+                PLACEMENT.to_string(),
+                FenError::TooFewSections(PLACEMENT.to_string()),
+            ),
+            (
+                format!("{PLACEMENT} "),
+                FenError::TooFewSections(PLACEMENT.to_string()),
+            ),
+            (
+                format!("{PLACEMENT} wb"),
+                FenError::InvalidPlayer("wb".to_string()),
+            ),
+            (
+                format!("{PLACEMENT} x"),
+                FenError::InvalidPlayer("x".to_string()),
+            ),
+        ] {
+            let Err(e) = Board::from_fen(&fen) else {
+                panic!("\"{fen}\" was accepted");
+            };
+            assert_eq!(e, error);
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn json_round_trip() {
+        let mut board = Board::default();
+        for (from, to) in [("e2", "e4"), ("d7", "d5"), ("e4", "d5"), ("g8", "f6")] {
+            board
+                .move_piece(PieceMove::new(
+                    TilePos::from_algebraic(from).unwrap(),
+                    TilePos::from_algebraic(to).unwrap(),
+                ))
+                .unwrap();
+        }
+        board.undo_move();
+
+        let json = serde_json::to_string(&board).unwrap();
+        let mut loaded: Board = serde_json::from_str(&json).unwrap();
+        assert_eq!(loaded.to_fen(), board.to_fen());
+        assert_eq!(loaded.get_ply(), board.get_ply());
+
+        // The move history comes along too, including the undone move
+        loaded.redo_move();
+        board.redo_move();
+        assert_eq!(loaded.to_fen(), board.to_fen());
+    }
+
+    #[test]
+    fn fen_counters_are_digits() {
+        for counters in ["+5 1", "0 +1", "-1 1", "1 1e2"] {
+            let fen = format!("4k3/8/8/8/8/8/8/4K3 w - - {counters}");
+            let Err(FenError::InvalidCounter(_)) = Board::from_fen(&fen) else {
+                panic!("\"{fen}\" was accepted");
+            };
+        }
+    }
+
+    #[test]
+    fn fen_error_shows_section() {
+        for (fen, section) in [
+            ("4k3/8/8/8/8/8/8/4K2X w - - 0 1", "\"4k3/8/8/8/8/8/8/4K2X\""),
+            ("4k3/8/8/8/8/8/8/4K3 white - - 0 1", "\"white\""),
+            ("4k3/8/8/8/8/8/8/4K3 w KX - 0 1", "\"KX\""),
+            ("4k3/8/8/8/8/8/8/4K3 w - e9 0 1", "\"e9\""),
+            ("4k3/8/8/8/8/8/8/4K3 w - - +5 1", "\"+5\""),
+            (
+                "4k3/8/8/8/8/8/8/4K3/8 w - - 0 1",
+                "\"4k3/8/8/8/8/8/8/4K3/8\"",
+            ),
+            ("4k3/8/8/8/8/8/8/4K3", "\"4k3/8/8/8/8/8/8/4K3\""),
+        ] {
+            let Err(e) = Board::from_fen(fen) else {
+                panic!("\"{fen}\" was accepted");
+            };
+            assert!(e.to_string().contains(section), "{e}");
+        }
+    }
+
+    #[test]
+    fn half_move_counter_resets() {
+        let pos = |algebraic| TilePos::from_algebraic(algebraic).unwrap();
+        let mut board = Board::from_fen("4k3/8/8/3p4/8/8/4P3/R3K3 w - - 98 60").unwrap();
+
+        // A quiet move reaches one hundred half moves
+        board.apply_move(PieceMove::new(pos("a1"), pos("a2")));
+        board.apply_move(PieceMove::new(pos("e8"), pos("d8")));
+        assert_eq!(board.half_move_counter, 100);
+        assert!(board.is_fifty_move_draw());
+
+        // Pawn moves and captures start the count again
+        board.apply_move(PieceMove::new(pos("e2"), pos("e4")));
+        assert_eq!(board.half_move_counter, 0);
+        assert!(!board.is_fifty_move_draw());
+
+        board.apply_move(PieceMove::new(pos("d8"), pos("c8")));
+        assert_eq!(board.half_move_counter, 1);
+
+        board.apply_move(PieceMove::new(pos("a2"), pos("a7")));
+        board.apply_move(PieceMove::new(pos("d5"), pos("e4")));
+        assert_eq!(board.half_move_counter, 0);
+    }
+
+    #[test]
+    fn threefold_repetition() {
+        let pos = |algebraic| TilePos::from_algebraic(algebraic).unwrap();
+        let mut board = Board::default();
+
+        // The starting position comes up again after every four moves
+        let shuffle = [("g1", "f3"), ("g8", "f6"), ("f3", "g1"), ("f6", "g8")];
+        for (i, (from, to)) in shuffle.iter().chain(shuffle.iter()).enumerate() {
+            assert!(!board.is_threefold_repetition(), "Ply {i}");
+
+            board.apply_move(PieceMove::new(pos(from), pos(to)));
+        }
+
+        assert!(board.is_threefold_repetition());
+    }
+
+    #[test]
+    fn insufficient_material() {
+        for (fen, insufficient) in [
+            ("4k3/8/8/8/8/8/8/4K3 w - - 0 1", true),
+            ("4k3/8/8/8/8/8/8/2B1K3 w - - 0 1", true),
+            ("4k3/8/8/8/8/8/8/1N2K3 w - - 0 1", true),
+            // Both bishops are on light squares
+            ("2b1k3/8/8/8/8/8/8/4KB2 w - - 0 1", true),
+            // A bishop on each colour could still mate
+            ("3bk3/8/8/8/8/8/8/4KB2 w - - 0 1", false),
+            ("4k3/8/8/8/8/8/4P3/4K3 w - - 0 1", false),
+        ] {
+            let board = Board::from_fen(fen).unwrap();
+
+            assert_eq!(board.is_insufficient_material(), insufficient, "{fen}");
+        }
+    }
+
+    #[test]
+    fn castling_moves_king_and_rook() {
+        let pos = |algebraic| TilePos::from_algebraic(algebraic).unwrap();
+
+        for (player, king_from, king_to, rook_from, rook_to) in [
+            (Player::White, "e1", "g1", "h1", "f1"),
+            (Player::White, "e1", "c1", "a1", "d1"),
+            (Player::Black, "e8", "g8", "h8", "f8"),
+            (Player::Black, "e8", "c8", "a8", "d8"),
+        ] {
+            let side = if player == Player::White { 'w' } else { 'b' };
+            let mut board =
+                Board::from_fen(format!("r3k2r/8/8/8/8/8/8/R3K2R {side} KQkq - 0 1")).unwrap();
+
+            assert!(board
+                .move_piece(PieceMove::new(pos(king_from), pos(king_to)))
+                .is_ok());
+
+            let king = Piece::new(PieceType::King, player);
+            let rook = Piece::new(PieceType::Rook, player);
+            assert_eq!(board.get_piece(pos(king_to)), king, "{king_to}");
+            assert_eq!(board.get_piece(pos(rook_to)), rook, "{king_to}");
+            assert_eq!(board.get_piece(pos(king_from)), Piece::None, "{king_to}");
+            assert_eq!(board.get_piece(pos(rook_from)), Piece::None, "{king_to}");
+
+            // Castling uses up both of the player's rights, but not the opponent's
+            assert_eq!(board.get_castling_rights(player), (false, false));
+            assert_eq!(board.get_castling_rights(player.opponent()), (true, true));
+        }
+    }
+
+    #[test]
+    fn promotion_to_each_piece() {
+        let pos = |algebraic| TilePos::from_algebraic(algebraic).unwrap();
+
+        for piece_type in PROMOTION_PIECE_TYPES {
+            for (fen, from, to, player) in [
+                ("4k3/P7/8/8/8/8/8/4K3 w - - 0 1", "a7", "a8", Player::White),
+                ("4k3/8/8/8/8/8/p7/4K3 b - - 0 1", "a2", "a1", Player::Black),
+            ] {
+                let mut board = Board::from_fen(fen).unwrap();
+                let promotion = Piece::new(piece_type, player);
+
+                assert!(board
+                    .move_piece(PieceMove::new(pos(from), pos(to)).with_promotion(promotion))
+                    .is_ok());
+                assert_eq!(board.get_piece(pos(to)), promotion);
+                assert_eq!(board.get_piece(pos(from)), Piece::None);
+            }
+        }
+    }
+
+    #[test]
+    fn starting_occupancy() {
+        let board = Board::default();
+        let occupancy = board.occupancy();
+
+        assert_eq!(occupancy.count(), 32);
+        assert_eq!(board.occupancy_of(Player::White).count(), 16);
+        assert_eq!(board.occupancy_of(Player::Black).count(), 16);
+
+        // Nothing is between the two sides
+        for rank in '3'..='6' {
+            for file in 'a'..='h' {
+                let tile_pos = TilePos::from_algebraic(&format!("{file}{rank}")).unwrap();
+                assert!(!occupancy.get_bit_at(tile_pos), "{file}{rank}");
+            }
+        }
+    }
+
+    #[test]
+    fn opponents() {
+        assert_eq!(Player::White.opponent(), Player::Black);
+        assert_eq!(Player::Black.opponent(), Player::White);
+        assert_eq!(Player::White.opponent().opponent(), Player::White);
+    }
+
+    #[test]
+    fn promotion_only_for_promotion_moves() {
+        let pos = |algebraic| TilePos::from_algebraic(algebraic).unwrap();
+        let mut board = Board::from_fen("4k3/P7/8/8/8/8/4P3/4K3 w - - 0 1").unwrap();
+
+        assert_eq!(
+            board.move_piece(PieceMove::new(pos("e2"), pos("e3")).with_promotion(Piece::WQueen)),
+            Err(MoveError::Illegal)
+        );
+
+        // Applying a move doesn't check it, but still doesn't promote a pawn which isn't on the last rank
+        board.apply_move(PieceMove::new(pos("e2"), pos("e3")).with_promotion(Piece::WQueen));
+        assert_eq!(board.get_piece(pos("e3")), Piece::WPawn);
+    }
+
+    #[test]
+    fn try_new_rejects_off_board() {
+        assert_eq!(TilePos::try_new(0, 0), Some(TilePos::new(0, 0)));
+        assert!(TilePos::try_new(BOARD_SIZE - 1, BOARD_SIZE - 1).is_some());
+
+        for (file, rank) in [
+            (BOARD_SIZE, 0),
+            (0, BOARD_SIZE),
+            (BOARD_SIZE, BOARD_SIZE),
+            (usize::MAX, 3),
+        ] {
+            assert!(TilePos::try_new(file, rank).is_none(), "{file}, {rank}");
+            assert!(!TilePos::new(file, rank).is_on_board(), "{file}, {rank}");
+        }
+    }
+
+    #[test]
+    fn offset_stays_on_board() {
+        let pos = |algebraic| TilePos::from_algebraic(algebraic).unwrap();
+
+        assert_eq!(pos("e4").offset(-1, 1), Some(pos("f5")));
+        assert_eq!(pos("e4").offset(2, -1), Some(pos("d2")));
+        assert_eq!(pos("a1").offset(-7, 7), Some(pos("h8")));
+
+        // Past each edge of the board
+        assert_eq!(pos("a1").offset(0, -1), None);
+        assert_eq!(pos("a1").offset(1, 0), None);
+        assert_eq!(pos("h8").offset(0, 1), None);
+        assert_eq!(pos("h8").offset(-1, 0), None);
+        assert_eq!(pos("e4").offset(-5, 0), None);
+    }
 }
@@ -0,0 +1,171 @@
+use bevy::prelude::*;
+use bevy_mod_picking::prelude::*;
+
+use crate::{
+    board::{Board, Player},
+    display::{get_piece_texture_atlas, BoardLayout, BOARD_SIZE},
+    piece::{Piece, PieceCapturedEvent},
+    undo_redo::UndoRedoEvent,
+};
+
+// Captured pieces are drawn smaller than the pieces on the board, overlapping slightly
+const TRAY_PIECE_SCALE: f32 = 0.4;
+const TRAY_PIECE_STEP: f32 = 0.3;
+const TRAY_PIECES_PER_ROW: usize = 6;
+
+// The pieces which have been captured, in the order they were captured
+#[derive(Resource, Default)]
+pub struct CapturedPieces {
+    pub pieces: Vec<Piece>,
+}
+
+impl CapturedPieces {
+    // The pieces captured in the moves which led to this position, for when the board is replaced
+    pub fn from_board(board: &Board) -> Self {
+        let mut board = board.clone();
+        let mut pieces = Vec::new();
+
+        loop {
+            if let Some((piece, _)) = board.get_last_capture() {
+                pieces.push(piece);
+            }
+
+            if board.undo_move().is_none() {
+                break;
+            }
+        }
+        pieces.reverse();
+
+        Self { pieces }
+    }
+}
+
+#[derive(Component)]
+pub struct TrayPiece;
+
+// Where to draw the nth captured piece of this piece's colour, in rows to the right of the board
+pub fn get_tray_position(piece: Piece, index: usize, layout: &BoardLayout) -> (f32, f32) {
+    let square_size = layout.get_square_size();
+    let step = layout.piece_size * TRAY_PIECE_STEP;
+    let (column, row) = (index % TRAY_PIECES_PER_ROW, index / TRAY_PIECES_PER_ROW);
+
+    // Pieces taken by the player at the bottom are shown at the bottom, moving towards the centre as rows fill up
+    let vertical_dir = if piece.to_player() != Some(layout.orientation.get_bottom_player()) {
+        -1.
+    } else {
+        1.
+    };
+
+    (
+        square_size * BOARD_SIZE as f32 / 2. + step * (column as f32 + 1.),
+        vertical_dir * (square_size * (BOARD_SIZE as f32 / 2. - 0.5) - step * row as f32),
+    )
+}
+
+// Keep track of captures, removing them again when they are undone (redone captures are resent as events)
+pub fn captured_pieces_event_reader(
+    mut ev_piece_captured: EventReader<PieceCapturedEvent>,
+    mut ev_undo_redo: EventReader<UndoRedoEvent>,
+    mut captured_pieces: ResMut<CapturedPieces>,
+) {
+    for ev in ev_piece_captured.read() {
+        captured_pieces.pieces.push(ev.piece);
+    }
+
+    for ev in ev_undo_redo.read() {
+        if let (true, Some((piece, _))) = (ev.undone, ev.captured) {
+            if let Some(index) = captured_pieces
+                .pieces
+                .iter()
+                .rposition(|&captured| captured == piece)
+            {
+                captured_pieces.pieces.remove(index);
+            }
+        }
+    }
+}
+
+// Redraw the tray whenever a capture is made or undone, or the board is flipped or resized
+pub fn display_captured_pieces(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mut texture_atlas_layouts: ResMut<Assets<TextureAtlasLayout>>,
+    captured_pieces: Res<CapturedPieces>,
+    layout: Res<BoardLayout>,
+    tray_query: Query<Entity, With<TrayPiece>>,
+) {
+    if !captured_pieces.is_changed() && !layout.is_changed() {
+        return;
+    }
+
+    for entity in tray_query.iter() {
+        commands.entity(entity).despawn();
+    }
+
+    let (texture, texture_atlas_layout) =
+        get_piece_texture_atlas(&asset_server, &mut texture_atlas_layouts);
+
+    for player in [Player::White, Player::Black] {
+        let pieces = captured_pieces
+            .pieces
+            .iter()
+            .filter(|piece| piece.to_player() == Some(player));
+
+        for (index, &piece) in pieces.enumerate() {
+            let (x, y) = get_tray_position(piece, index, &layout);
+
+            commands.spawn((
+                SpriteSheetBundle {
+                    texture: texture.clone(),
+                    atlas: TextureAtlas {
+                        layout: texture_atlas_layout.clone(),
+                        index: piece.into(),
+                    },
+                    transform: Transform::from_scale(Vec3::splat(
+                        layout.get_piece_scale() * TRAY_PIECE_SCALE,
+                    ))
+                    .with_translation(Vec3::new(
+                        x,
+                        y,
+                        1. + index as f32 * 0.01,
+                    )),
+                    ..default()
+                },
+                Pickable::IGNORE,
+                TrayPiece,
+            ));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::display::BoardOrientation;
+
+    #[test]
+    fn tray_layout() {
+        let layout = BoardLayout::default();
+        let board_edge = layout.get_square_size() * BOARD_SIZE as f32 / 2.;
+        let step = layout.piece_size * TRAY_PIECE_STEP;
+
+        // Pieces taken by White, who is at the bottom, are shown below the centre, and White's pieces above it
+        let (x, y) = get_tray_position(Piece::BPawn, 0, &layout);
+        assert!(x > board_edge && y < 0.);
+        assert_eq!(get_tray_position(Piece::WPawn, 0, &layout), (x, -y));
+
+        // Each piece is to the right of the last, until the row is full
+        let (next_x, next_y) = get_tray_position(Piece::BPawn, 1, &layout);
+        assert!((next_x - x - step).abs() < 1e-3 && next_y == y);
+
+        let (row_x, row_y) = get_tray_position(Piece::BPawn, TRAY_PIECES_PER_ROW, &layout);
+        assert!(row_x == x && (row_y - y - step).abs() < 1e-3);
+
+        // Flipping the board swaps the sides
+        let flipped = BoardLayout {
+            orientation: BoardOrientation::BlackAtBottom,
+            ..default()
+        };
+        assert_eq!(get_tray_position(Piece::WPawn, 0, &flipped), (x, y));
+    }
+}
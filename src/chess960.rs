@@ -0,0 +1,125 @@
+use crate::{board::Board, display::BOARD_SIZE};
+
+// Every way of placing the two knights on five empty squares, in the order used by the standard numbering
+const KNIGHT_PLACEMENTS: [(usize, usize); 10] = [
+    (0, 1),
+    (0, 2),
+    (0, 3),
+    (0, 4),
+    (1, 2),
+    (1, 3),
+    (1, 4),
+    (2, 3),
+    (2, 4),
+    (3, 4),
+];
+
+pub const CHESS960_POSITION_AMT: u16 = 960;
+
+impl Board {
+    // One of the 960 starting positions, using the standard numbering (518 is the usual starting position)
+    // Numbers past the last position wrap back around to the first
+    pub fn from_chess960(position_number: u16) -> Board {
+        let back_rank = get_chess960_back_rank((position_number % CHESS960_POSITION_AMT) as usize);
+        let white_back_rank: String = back_rank.iter().collect();
+
+        Board::from_fen(format!(
+            "{}/pppppppp/8/8/8/8/PPPPPPPP/{} w KQkq - 0 1",
+            white_back_rank.to_ascii_lowercase(),
+            white_back_rank
+        ))
+        .unwrap()
+    }
+}
+
+// Place the bishops on opposite colours, then the queen, then the knights, leaving the king between the rooks
+fn get_chess960_back_rank(position_number: usize) -> [char; BOARD_SIZE] {
+    let mut back_rank = [' '; BOARD_SIZE];
+    let mut n = position_number;
+
+    back_rank[2 * (n % 4) + 1] = 'B';
+    n /= 4;
+    back_rank[2 * (n % 4)] = 'B';
+    n /= 4;
+
+    place_on_empty_square(&mut back_rank, n % 6, 'Q');
+    n /= 6;
+
+    // Place the second knight first, so that placing the first knight doesn't shift its index
+    let (first_knight, second_knight) = KNIGHT_PLACEMENTS[n];
+    place_on_empty_square(&mut back_rank, second_knight, 'N');
+    place_on_empty_square(&mut back_rank, first_knight, 'N');
+
+    for chr in ['R', 'K', 'R'] {
+        place_on_empty_square(&mut back_rank, 0, chr);
+    }
+
+    back_rank
+}
+
+fn place_on_empty_square(back_rank: &mut [char; BOARD_SIZE], empty_index: usize, chr: char) {
+    if let Some(square) = back_rank
+        .iter_mut()
+        .filter(|square| **square == ' ')
+        .nth(empty_index)
+    {
+        *square = chr;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::*;
+
+    fn get_back_rank_text(position_number: usize) -> String {
+        get_chess960_back_rank(position_number).iter().collect()
+    }
+
+    #[test]
+    fn standard_start_position() {
+        assert!(Board::from_chess960(518) == Board::default());
+        assert_eq!(get_back_rank_text(518), "RNBQKBNR");
+    }
+
+    #[test]
+    fn reference_positions() {
+        for (position_number, back_rank) in [(0, "BBQNNRKR"), (1, "BQNBNRKR"), (959, "RKRNNQBB")] {
+            assert_eq!(get_back_rank_text(position_number), back_rank);
+        }
+
+        assert!(Board::from_chess960(0)
+            .to_fen()
+            .starts_with("bbqnnrkr/pppppppp/8/8/8/8/PPPPPPPP/BBQNNRKR w KQkq"));
+        assert!(Board::from_chess960(CHESS960_POSITION_AMT) == Board::from_chess960(0));
+    }
+
+    #[test]
+    fn every_position_is_different_and_valid() {
+        let back_ranks = (0..CHESS960_POSITION_AMT as usize)
+            .map(get_back_rank_text)
+            .collect::<HashSet<_>>();
+        assert_eq!(back_ranks.len(), CHESS960_POSITION_AMT as usize);
+
+        for back_rank in back_ranks {
+            let king = back_rank.find('K').unwrap();
+            assert!(back_rank[..king].contains('R') && back_rank[king..].contains('R'));
+
+            let bishops = back_rank.match_indices('B').map(|(i, _)| i % 2);
+            assert_eq!(bishops.collect::<HashSet<_>>().len(), 2);
+        }
+    }
+
+    #[test]
+    fn perft_chess960() {
+        let mut board =
+            Board::from_fen("bqnb1rkr/pp3ppp/3ppn2/2p5/5P2/P2P4/NPP1P1PP/BQ1BNRKR w KQkq - 2 9")
+                .unwrap();
+
+        assert_eq!(
+            (1..=3).map(|depth| board.perft(depth)).collect::<Vec<_>>(),
+            [21, 528, 12189]
+        );
+    }
+}
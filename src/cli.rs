@@ -0,0 +1,97 @@
+use bevy::prelude::*;
+
+use crate::board::Board;
+
+const FEN_ARG: &str = "--fen";
+const SELFPLAY_ARG: &str = "--selfplay";
+
+// The value given to an option, either as the next argument or joined to it with =
+fn get_arg_value<I: IntoIterator<Item = String>>(args: I, name: &str) -> Option<String> {
+    let mut args = args.into_iter();
+
+    while let Some(arg) = args.next() {
+        if arg == name {
+            return args.next();
+        }
+
+        if let Some(value) = arg
+            .strip_prefix(name)
+            .and_then(|rest| rest.strip_prefix('='))
+        {
+            return Some(value.to_string());
+        }
+    }
+
+    None
+}
+
+pub fn get_fen_arg<I: IntoIterator<Item = String>>(args: I) -> Option<String> {
+    get_arg_value(args, FEN_ARG)
+}
+
+// How many games to play with --selfplay, which plays them without opening a window
+pub fn get_selfplay_arg<I: IntoIterator<Item = String>>(args: I) -> Option<usize> {
+    get_arg_value(args, SELFPLAY_ARG).and_then(|games| games.parse().ok())
+}
+
+// The board to start on, which is the standard starting position unless a valid FEN was given
+pub fn get_initial_board<I: IntoIterator<Item = String>>(args: I) -> Board {
+    let Some(fen) = get_fen_arg(args) else {
+        return Board::default();
+    };
+
+    match Board::from_fen_validated(&fen) {
+        Ok(board) => board,
+        Err(e) => {
+            error!("{e}");
+            Board::default()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FEN: &str = "4k3/8/8/8/8/8/8/4K2R w K - 0 1";
+
+    fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(ToString::to_string).collect()
+    }
+
+    #[test]
+    fn fen_arg_forms() {
+        assert_eq!(get_fen_arg(args(&["--fen", FEN])), Some(FEN.to_string()));
+        assert_eq!(
+            get_fen_arg(args(&["--selfplay=2", &format!("--fen={FEN}")])),
+            Some(FEN.to_string())
+        );
+        assert_eq!(get_fen_arg(args(&["--fen"])), None);
+        assert_eq!(get_fen_arg(args(&["--fenx", FEN])), None);
+    }
+
+    #[test]
+    fn initial_board_from_fen() {
+        assert_eq!(get_initial_board(args(&["--fen", FEN])).to_fen(), FEN);
+        assert_eq!(
+            get_initial_board(args(&[])).to_fen(),
+            Board::default().to_fen()
+        );
+    }
+
+    #[test]
+    fn malformed_fen_falls_back_to_start() {
+        for fen in [
+            "nonsense",
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBN- w - - 0 1",
+            // Parses, but has no kings
+            "8/8/8/8/8/8/8/8 w - - 0 1",
+        ] {
+            assert_eq!(
+                get_initial_board(args(&["--fen", fen])).to_fen(),
+                Board::default().to_fen(),
+                "{fen}"
+            );
+        }
+    }
+}
@@ -0,0 +1,280 @@
+use std::time::Duration;
+
+use bevy::prelude::*;
+
+use crate::{
+    board::{Board, Player},
+    display::BoardLayout,
+    game_over::{GameEndReason, GameOverEvent},
+    piece::MoveOutcomeEvent,
+};
+
+pub const DEFAULT_CLOCK_TIME: Duration = Duration::from_secs(10 * 60);
+pub const DEFAULT_CLOCK_INCREMENT: Duration = Duration::from_secs(0);
+
+const CLOCK_FONT_SIZE: f32 = 48.;
+const CLOCK_MARGIN: f32 = 20.;
+
+#[derive(Resource, Clone, PartialEq, Eq, Debug)]
+pub struct ChessClock {
+    pub white: Duration,
+    pub black: Duration,
+    pub increment: Duration,
+    pub running: bool,
+}
+
+impl Default for ChessClock {
+    fn default() -> Self {
+        Self::new(DEFAULT_CLOCK_TIME, DEFAULT_CLOCK_INCREMENT)
+    }
+}
+
+impl ChessClock {
+    pub fn new(time: Duration, increment: Duration) -> Self {
+        Self {
+            white: time,
+            black: time,
+            increment,
+            running: true,
+        }
+    }
+
+    pub fn get_time(&self, player: Player) -> Duration {
+        match player {
+            Player::White => self.white,
+            Player::Black => self.black,
+        }
+    }
+
+    fn get_time_mut(&mut self, player: Player) -> &mut Duration {
+        match player {
+            Player::White => &mut self.white,
+            Player::Black => &mut self.black,
+        }
+    }
+
+    // Run down this player's time, returning true if it has just run out
+    pub fn tick(&mut self, player: Player, delta: Duration) -> bool {
+        if !self.running {
+            return false;
+        }
+
+        let time = self.get_time_mut(player);
+        *time = time.saturating_sub(delta);
+
+        if time.is_zero() {
+            self.running = false;
+            return true;
+        }
+
+        false
+    }
+
+    pub fn add_increment(&mut self, player: Player) {
+        let increment = self.increment;
+        *self.get_time_mut(player) += increment;
+    }
+}
+
+// The result when this player runs out of time, which is a draw if their opponent couldn't checkmate them
+pub fn get_timeout_result(board: &Board, player: Player) -> GameOverEvent {
+    let opponent = player.opponent();
+
+    GameOverEvent {
+        winner: (!board.has_insufficient_material(opponent)).then_some(opponent),
+        reason: GameEndReason::Timeout,
+    }
+}
+
+// Shows the remaining time for one player
+#[derive(Component)]
+pub struct ClockText(pub Player);
+
+pub fn get_clock_text(time: Duration) -> String {
+    let seconds = time.as_secs();
+
+    // Show tenths of a second when time is running low
+    if seconds < 10 {
+        format!("0:{:04.1}", time.as_secs_f32())
+    } else {
+        format!("{}:{:02}", seconds / 60, seconds % 60)
+    }
+}
+
+// Run down the time of the player to move, ending the game if it runs out
+pub fn chess_clock_ticker(
+    time: Res<Time>,
+    board: Res<Board>,
+    mut clock: ResMut<ChessClock>,
+    mut ev_game_over: EventWriter<GameOverEvent>,
+) {
+    if clock.tick(board.player, time.delta()) {
+        ev_game_over.send(get_timeout_result(&board, board.player));
+    }
+}
+
+// Stop the clock once the game has ended
+pub fn chess_clock_game_over_reader(
+    mut ev_game_over: EventReader<GameOverEvent>,
+    mut clock: ResMut<ChessClock>,
+) {
+    if ev_game_over.read().last().is_some() {
+        clock.running = false;
+    }
+}
+
+// Give the player who just moved their increment, which isn't given for undoing or loading a position
+pub fn chess_clock_increment(
+    mut ev_move_outcome: EventReader<MoveOutcomeEvent>,
+    mut clock: ResMut<ChessClock>,
+    board: Res<Board>,
+) {
+    for _ in ev_move_outcome.read() {
+        clock.add_increment(board.get_next_player());
+    }
+}
+
+pub fn spawn_clock_text(mut commands: Commands) {
+    for player in [Player::White, Player::Black] {
+        commands.spawn((
+            TextBundle::from_section(
+                get_clock_text(DEFAULT_CLOCK_TIME),
+                TextStyle {
+                    font_size: CLOCK_FONT_SIZE,
+                    color: Color::WHITE,
+                    ..default()
+                },
+            )
+            .with_style(Style {
+                position_type: PositionType::Absolute,
+                right: Val::Px(CLOCK_MARGIN),
+                ..default()
+            }),
+            ClockText(player),
+        ));
+    }
+}
+
+// Show each player's time on their side of the board
+pub fn update_clock_text(
+    clock: Res<ChessClock>,
+    layout: Res<BoardLayout>,
+    mut text_query: Query<(&mut Text, &mut Style, &ClockText)>,
+) {
+    let bottom_player = layout.orientation.get_bottom_player();
+
+    for (mut text, mut style, ClockText(player)) in text_query.iter_mut() {
+        text.sections[0].value = get_clock_text(clock.get_time(*player));
+
+        (style.top, style.bottom) = if *player == bottom_player {
+            (Val::Auto, Val::Px(CLOCK_MARGIN))
+        } else {
+            (Val::Px(CLOCK_MARGIN), Val::Auto)
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use bevy::time::TimeUpdateStrategy;
+
+    use super::*;
+    use crate::{
+        board::TilePos,
+        game_over::{game_over_checker, GameState},
+        headless::ChessCorePlugin,
+        piece::{piece_move_event_reader, PieceMove, PieceMoveEvent, TurnChangedEvent},
+    };
+
+    const STEP: Duration = Duration::from_millis(100);
+
+    fn clock_app(clock: ChessClock) -> App {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, ChessCorePlugin))
+            .insert_resource(TimeUpdateStrategy::ManualDuration(STEP))
+            .insert_resource(clock)
+            .add_systems(
+                Update,
+                (
+                    chess_clock_increment.after(piece_move_event_reader),
+                    chess_clock_ticker.after(chess_clock_increment),
+                    chess_clock_game_over_reader.after(game_over_checker),
+                ),
+            );
+
+        app
+    }
+
+    fn send_move(app: &mut App, from: &str, to: &str) {
+        app.world.send_event(PieceMoveEvent {
+            piece_move: PieceMove::new(
+                TilePos::from_algebraic(from).unwrap(),
+                TilePos::from_algebraic(to).unwrap(),
+            ),
+            entity: Entity::PLACEHOLDER,
+        });
+    }
+
+    #[test]
+    fn time_runs_down() {
+        let mut app = clock_app(ChessClock::new(Duration::from_secs(60), Duration::ZERO));
+        // The first update only starts the timer
+        app.update();
+        for _ in 0..5 {
+            app.update();
+        }
+
+        let clock = app.world.resource::<ChessClock>();
+        assert_eq!(clock.white, Duration::from_secs(60) - STEP * 5);
+        assert_eq!(clock.black, Duration::from_secs(60));
+    }
+
+    #[test]
+    fn flag_fall_ends_game() {
+        let mut app = clock_app(ChessClock::new(STEP * 3, Duration::ZERO));
+        for _ in 0..4 {
+            app.update();
+        }
+
+        let clock = app.world.resource::<ChessClock>();
+        assert!(clock.white.is_zero());
+        assert!(!clock.running);
+
+        // The move is sent in the same frame that the game over is read
+        send_move(&mut app, "e2", "e4");
+        app.update();
+        assert!(app.world.resource::<GameState>().is_over());
+        assert_eq!(app.world.resource::<Board>().get_ply(), 0);
+    }
+
+    #[test]
+    fn increment_only_for_moves() {
+        let mut clock = ChessClock::new(Duration::from_secs(60), Duration::from_secs(2));
+        clock.running = false;
+        let mut app = clock_app(clock);
+
+        send_move(&mut app, "e2", "e4");
+        app.update();
+        assert_eq!(
+            app.world.resource::<ChessClock>().white,
+            Duration::from_secs(62)
+        );
+
+        // Undoing the move changes the turn without a move being made
+        app.world.resource_mut::<Board>().undo_move();
+        app.world.send_event(TurnChangedEvent {
+            new_player: Player::White,
+        });
+        app.update();
+        assert_eq!(
+            app.world.resource::<ChessClock>().white,
+            Duration::from_secs(62)
+        );
+        assert_eq!(
+            app.world.resource::<ChessClock>().black,
+            Duration::from_secs(60)
+        );
+    }
+}
@@ -0,0 +1,85 @@
+use crate::{board::TilePos, display::BOARD_SIZE};
+
+// The size of the playable part of the board, which is smaller than the full board in some variants
+// The playable squares are in the bottom left corner (for White), so that squares keep their usual algebraic names
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BoardDimensions {
+    // How many files (rows) and ranks (columns) can be played on
+    pub files: usize,
+    pub ranks: usize,
+}
+
+impl Default for BoardDimensions {
+    fn default() -> Self {
+        Self {
+            files: BOARD_SIZE,
+            ranks: BOARD_SIZE,
+        }
+    }
+}
+
+impl BoardDimensions {
+    // Dimensions which fit within the full board
+    pub fn try_new(files: usize, ranks: usize) -> Option<Self> {
+        ((1..=BOARD_SIZE).contains(&files) && (1..=BOARD_SIZE).contains(&ranks))
+            .then_some(Self { files, ranks })
+    }
+
+    // The first file which can be played on, where Black's pieces start
+    pub fn get_top_file(&self) -> usize {
+        BOARD_SIZE - self.files
+    }
+
+    pub fn contains(&self, tile_pos: TilePos) -> bool {
+        tile_pos.is_on_board() && tile_pos.file >= self.get_top_file() && tile_pos.rank < self.ranks
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{board::Board, piece::Piece};
+
+    fn pos(algebraic: &str) -> TilePos {
+        TilePos::from_algebraic(algebraic).unwrap()
+    }
+
+    #[test]
+    fn six_by_six_placement() {
+        let fen = "rnqknr/pppppp/6/6/PPPPPP/RNQKNR w - - 0 1";
+        let board = Board::from_fen(fen).unwrap();
+
+        assert_eq!(board.to_fen(), fen);
+        assert_eq!(
+            board.get_dimensions(),
+            BoardDimensions::try_new(6, 6).unwrap()
+        );
+
+        // The board sits in the bottom left corner, so a1 is in the same place as on a full board
+        assert_eq!(board.get_piece(pos("a1")), Piece::WRook);
+        assert_eq!(board.get_piece(pos("d1")), Piece::WKing);
+        assert_eq!(board.get_piece(pos("c6")), Piece::BQueen);
+        assert_eq!(board.get_piece(pos("f6")), Piece::BRook);
+        assert!(!board.get_dimensions().contains(pos("a7")));
+        assert!(!board.get_dimensions().contains(pos("g1")));
+    }
+
+    #[test]
+    fn six_by_six_rook_moves() {
+        let mut board = Board::from_fen("5k/6/6/6/6/R5 w - - 0 1").unwrap();
+        let moves = board.get_legal_moves(pos("a1"));
+
+        assert_eq!(moves.len(), 10);
+        assert!(!moves.contains(&pos("g1")) && !moves.contains(&pos("a7")));
+    }
+
+    #[test]
+    fn oversized_boards() {
+        assert_eq!(BoardDimensions::try_new(9, 8), None);
+        assert!(Board::from_fen("8/8/8/8/8/8/8/8/8 w - - 0 1").is_err());
+        assert!(
+            Board::from_fen("rnbqkbnr/ppppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1").is_err()
+        );
+    }
+}
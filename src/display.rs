@@ -1,8 +1,13 @@
-use bevy::prelude::*;
+use bevy::{prelude::*, window::WindowResized};
+use bevy_mod_picking::prelude::*;
 
 use crate::{
-    board::{Board, TilePos},
+    animation::MoveAnimation,
+    board::{Board, Player, TilePos},
     piece::{Piece, PieceBundle, COLOUR_AMT, PIECE_AMT},
+    promotion::PendingPromotion,
+    selection::{on_tile_click, SelectedPiece},
+    theme::BoardTheme,
 };
 
 pub const BOARD_SIZE: usize = 8;
@@ -10,76 +15,369 @@ pub const PIECE_SIZE: f32 = 150.;
 pub const PIECE_SIZE_IMG: f32 = 60.;
 pub const BOARD_SPACING: f32 = 4.;
 
-const PIECE_TEXTURE_FILE: &str = "ChessPiecesArray.png";
+// How much of the window's shortest side the board takes up
+const BOARD_WINDOW_FRACTION: f32 = 0.96;
 
-pub fn board_to_pixel_coords(i: usize, j: usize) -> (f32, f32) {
+pub const PIECE_TEXTURE_FILE: &str = "ChessPiecesArray.png";
+
+// Which player's pieces are shown at the bottom of the screen
+#[derive(Default, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum BoardOrientation {
+    #[default]
+    WhiteAtBottom,
+    BlackAtBottom,
+}
+
+impl BoardOrientation {
+    pub fn flipped(self) -> Self {
+        match self {
+            BoardOrientation::WhiteAtBottom => BoardOrientation::BlackAtBottom,
+            BoardOrientation::BlackAtBottom => BoardOrientation::WhiteAtBottom,
+        }
+    }
+
+    pub fn get_bottom_player(self) -> Player {
+        match self {
+            BoardOrientation::WhiteAtBottom => Player::White,
+            BoardOrientation::BlackAtBottom => Player::Black,
+        }
+    }
+
+    // Convert between board coords and screen coords (rows counted up from the bottom), works in both directions
+    fn orient(self, (i, j): (usize, usize)) -> (usize, usize) {
+        match self {
+            BoardOrientation::WhiteAtBottom => (BOARD_SIZE - 1 - i, j),
+            BoardOrientation::BlackAtBottom => (i, BOARD_SIZE - 1 - j),
+        }
+    }
+}
+
+// The size and orientation of the board on screen, which is recomputed when the window is resized
+#[derive(Resource, Clone, Copy, PartialEq, Debug)]
+pub struct BoardLayout {
+    pub piece_size: f32,
+    pub spacing: f32,
+    pub orientation: BoardOrientation,
+}
+
+impl Default for BoardLayout {
+    fn default() -> Self {
+        Self {
+            piece_size: PIECE_SIZE,
+            spacing: BOARD_SPACING,
+            orientation: BoardOrientation::default(),
+        }
+    }
+}
+
+impl BoardLayout {
+    // Scale the default sizes so that the board fits inside the window
+    pub fn from_window_size(width: f32, height: f32, orientation: BoardOrientation) -> Self {
+        let square_size = width.min(height) * BOARD_WINDOW_FRACTION / BOARD_SIZE as f32;
+        let scale = square_size / (PIECE_SIZE + BOARD_SPACING);
+
+        Self {
+            piece_size: PIECE_SIZE * scale,
+            spacing: BOARD_SPACING * scale,
+            orientation,
+        }
+    }
+
+    // The distance between the centres of neighbouring squares
+    pub fn get_square_size(&self) -> f32 {
+        self.piece_size + self.spacing
+    }
+
+    // The sprite scale which draws the piece images at the current piece size
+    pub fn get_piece_scale(&self) -> f32 {
+        self.piece_size / PIECE_SIZE_IMG
+    }
+}
+
+pub fn board_to_pixel_coords(i: usize, j: usize, layout: &BoardLayout) -> (f32, f32) {
+    let (i, j) = layout.orientation.orient((i, j));
+
+    (
+        (j as f32 - BOARD_SIZE as f32 / 2. + 0.5) * layout.get_square_size(),
+        (i as f32 - BOARD_SIZE as f32 / 2. + 0.5) * layout.get_square_size(),
+    )
+}
+
+pub fn pixel_to_board_coords(x: f32, y: f32, layout: &BoardLayout) -> (usize, usize) {
+    let (i, j) = pixel_to_unclamped_board_coords(x, y, layout);
+
+    layout.orientation.orient((
+        (i as usize).clamp(0, BOARD_SIZE - 1),
+        (j as usize).clamp(0, BOARD_SIZE - 1),
+    ))
+}
+
+// Get the board coords of this position, unless it is off the board
+pub fn pixel_to_board_coords_checked(
+    x: f32,
+    y: f32,
+    layout: &BoardLayout,
+) -> Option<(usize, usize)> {
+    let (i, j) = pixel_to_unclamped_board_coords(x, y, layout);
+    let board_range = 0.0..BOARD_SIZE as f32;
+
+    (board_range.contains(&i) && board_range.contains(&j))
+        .then(|| layout.orientation.orient((i as usize, j as usize)))
+}
+
+// Each square covers a whole unit, so the edges of the board are at 0 and BOARD_SIZE
+fn pixel_to_unclamped_board_coords(x: f32, y: f32, layout: &BoardLayout) -> (f32, f32) {
     (
-        (j as f32 - BOARD_SIZE as f32 / 2. + 0.5) * (PIECE_SIZE + BOARD_SPACING),
-        (i as f32 - BOARD_SIZE as f32 / 2. + 0.5) * (PIECE_SIZE + BOARD_SPACING),
+        (y / layout.get_square_size()) + BOARD_SIZE as f32 / 2.,
+        (x / layout.get_square_size()) + BOARD_SIZE as f32 / 2.,
     )
 }
 
-pub fn pixel_to_board_coords(x: f32, y: f32) -> (usize, usize) {
+// Load the piece spritesheet, and create an atlas layout which is indexed using Into::<usize> for Piece
+pub fn get_piece_texture_atlas(
+    asset_server: &AssetServer,
+    texture_atlas_layouts: &mut Assets<TextureAtlasLayout>,
+) -> (Handle<Image>, Handle<TextureAtlasLayout>) {
     (
-        (((y / (PIECE_SIZE + BOARD_SPACING)) - 0.5 + BOARD_SIZE as f32 / 2.) as usize)
-            .clamp(0, BOARD_SIZE - 1),
-        (((x / (PIECE_SIZE + BOARD_SPACING)) - 0.5 + BOARD_SIZE as f32 / 2.) as usize)
-            .clamp(0, BOARD_SIZE - 1),
+        asset_server.load(PIECE_TEXTURE_FILE),
+        texture_atlas_layouts.add(get_piece_atlas_layout()),
     )
 }
 
+pub fn get_piece_atlas_layout() -> TextureAtlasLayout {
+    TextureAtlasLayout::from_grid(
+        Vec2::new(PIECE_SIZE_IMG, PIECE_SIZE_IMG),
+        PIECE_AMT,
+        COLOUR_AMT,
+        None,
+        None,
+    )
+}
+
+// Alternating light and dark squares, starting with a light square on A1 (Bottom Left for White)
+pub fn get_square_colour(tile_pos: TilePos, theme: &BoardTheme) -> Color {
+    if (tile_pos.file + tile_pos.rank).is_multiple_of(2) {
+        theme.light
+    } else {
+        theme.dark
+    }
+}
+
 pub fn display_board(
     mut commands: Commands,
     asset_server: Res<AssetServer>,
     mut texture_atlas_layouts: ResMut<Assets<TextureAtlasLayout>>,
     mut board: ResMut<Board>,
+    layout: Res<BoardLayout>,
+    theme: Res<BoardTheme>,
 ) {
     // Spawn Board Squares
     for i in 0..BOARD_SIZE {
         for j in 0..BOARD_SIZE {
-            let (x, y) = board_to_pixel_coords(i, j);
+            // Smaller boards leave the squares outside them blank
+            if !board.get_dimensions().contains(TilePos::new(i, j)) {
+                continue;
+            }
+
+            let (x, y) = board_to_pixel_coords(i, j, &layout);
 
             // Create a board with alternating light and dark squares
-            // Starting with a light square on A1 (Bottom Left for White)
-            commands.spawn(SpriteBundle {
-                sprite: Sprite {
-                    color: if (i + j) % 2 == 0 {
-                        Color::WHITE
-                    } else {
-                        Color::PURPLE
+            commands.spawn((
+                SpriteBundle {
+                    sprite: Sprite {
+                        color: get_square_colour(TilePos::new(i, j), &theme),
+                        custom_size: Some(Vec2::splat(layout.piece_size)),
+                        ..default()
                     },
-                    custom_size: Some(Vec2::new(PIECE_SIZE, PIECE_SIZE)),
+                    transform: Transform::from_xyz(x, y, 0.),
                     ..default()
                 },
-                transform: Transform::from_xyz(x, y, 0.),
-                ..default()
-            });
+                TilePos::new(i, j),
+                On::<Pointer<Click>>::run(on_tile_click),
+            ));
         }
     }
 
+    // Spawn all the pieces where they are in the board.tiles array
+    spawn_missing_pieces(
+        &mut commands,
+        &asset_server,
+        &mut texture_atlas_layouts,
+        &mut board,
+        &layout,
+    );
+}
+
+// Spawn a sprite for every piece which doesn't have one, linking it to the board
+pub fn spawn_missing_pieces(
+    commands: &mut Commands,
+    asset_server: &AssetServer,
+    texture_atlas_layouts: &mut Assets<TextureAtlasLayout>,
+    board: &mut Board,
+    layout: &BoardLayout,
+) {
     // Texture atlas for all the pieces
-    let texture = asset_server.load(PIECE_TEXTURE_FILE);
-    let texture_atlas_layout = texture_atlas_layouts.add(TextureAtlasLayout::from_grid(
-        Vec2::new(PIECE_SIZE_IMG, PIECE_SIZE_IMG),
-        PIECE_AMT,
-        COLOUR_AMT,
-        None,
-        None,
-    ));
+    let (texture, texture_atlas_layout) =
+        get_piece_texture_atlas(asset_server, texture_atlas_layouts);
 
-    // Spawn all the pieces where they are in the board.tiles array
     for file in 0..BOARD_SIZE {
         for rank in 0..BOARD_SIZE {
-            if board.get_piece(TilePos::new(file, rank)) != Piece::None {
+            let tile_pos = TilePos::new(file, rank);
+            let piece = board.get_piece(tile_pos);
+
+            if piece != Piece::None && board.get_entity(tile_pos).is_none() {
                 let entity = commands.spawn(PieceBundle::new(
                     (file, rank),
-                    board.get_piece(TilePos::new(file, rank)),
+                    piece,
                     texture.clone(),
                     texture_atlas_layout.clone(),
+                    layout,
                 ));
 
-                board.set_entity(TilePos::new(file, rank), Some(entity.id()));
+                board.set_entity(tile_pos, Some(entity.id()));
             }
         }
     }
 }
+
+// Flip the board with F, unless a piece is being held or promoted
+pub fn flip_board_input(
+    keys: Res<ButtonInput<KeyCode>>,
+    selected_piece: Res<SelectedPiece>,
+    pending_promotion: Res<PendingPromotion>,
+    mut layout: ResMut<BoardLayout>,
+) {
+    if keys.just_pressed(KeyCode::KeyF)
+        && selected_piece.dragged.is_none()
+        && pending_promotion.promotion.is_none()
+    {
+        layout.orientation = layout.orientation.flipped();
+    }
+}
+
+// Fit the board to the window whenever it is resized
+pub fn window_resize_event_reader(
+    mut ev_window_resized: EventReader<WindowResized>,
+    mut layout: ResMut<BoardLayout>,
+) {
+    if let Some(ev) = ev_window_resized.read().last() {
+        *layout = BoardLayout::from_window_size(ev.width, ev.height, layout.orientation);
+    }
+}
+
+// Move and resize the squares and pieces after the board is flipped or the window is resized
+pub fn reposition_board(
+    mut commands: Commands,
+    layout: Res<BoardLayout>,
+    board: Res<Board>,
+    pending_promotion: Res<PendingPromotion>,
+    mut square_query: Query<(&TilePos, &mut Transform, &mut Sprite)>,
+    mut piece_query: Query<&mut Transform, Without<TilePos>>,
+) {
+    if !layout.is_changed() {
+        return;
+    }
+
+    for (tile_pos, mut transform, mut sprite) in square_query.iter_mut() {
+        let (x, y) = board_to_pixel_coords(tile_pos.file, tile_pos.rank, &layout);
+        transform.translation = Vec3::new(x, y, transform.translation.z);
+        sprite.custom_size = Some(Vec2::splat(layout.piece_size));
+    }
+
+    for file in 0..BOARD_SIZE {
+        for rank in 0..BOARD_SIZE {
+            let Some(entity) = board.get_entity(TilePos::new(file, rank)) else {
+                continue;
+            };
+
+            // A pawn waiting to be promoted is shown on its destination
+            let tile_pos = match pending_promotion.promotion {
+                Some((piece_move, promoting_entity)) if promoting_entity == entity => piece_move.to,
+                _ => TilePos::new(file, rank),
+            };
+
+            // Pieces which were still moving would finish on their old square
+            commands.entity(entity).remove::<MoveAnimation>();
+
+            if let Ok(mut transform) = piece_query.get_mut(entity) {
+                let (x, y) = board_to_pixel_coords(tile_pos.file, tile_pos.rank, &layout);
+                transform.translation = Vec3::new(x, y, transform.translation.z);
+                transform.scale = Vec3::splat(layout.get_piece_scale());
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pixel_coords_round_trip() {
+        for orientation in [
+            BoardOrientation::WhiteAtBottom,
+            BoardOrientation::BlackAtBottom,
+        ] {
+            let layout = BoardLayout {
+                orientation,
+                ..default()
+            };
+
+            for i in 0..BOARD_SIZE {
+                for j in 0..BOARD_SIZE {
+                    let (x, y) = board_to_pixel_coords(i, j, &layout);
+                    assert_eq!(pixel_to_board_coords(x, y, &layout), (i, j));
+                    assert_eq!(pixel_to_board_coords_checked(x, y, &layout), Some((i, j)));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn flipped_board() {
+        let white = BoardLayout::default();
+        let black = BoardLayout {
+            orientation: white.orientation.flipped(),
+            ..default()
+        };
+        assert_eq!(black.orientation.get_bottom_player(), Player::Black);
+        assert_eq!(black.orientation.flipped(), white.orientation);
+
+        // a1 is at the bottom left for White, and at the top right for Black
+        let (x, y) = board_to_pixel_coords(BOARD_SIZE - 1, 0, &white);
+        assert!(x < 0. && y < 0.);
+        assert_eq!(board_to_pixel_coords(BOARD_SIZE - 1, 0, &black), (-x, -y));
+    }
+
+    #[test]
+    fn window_size_round_trip() {
+        for (width, height) in [(800., 600.), (1920., 1280.)] {
+            let layout =
+                BoardLayout::from_window_size(width, height, BoardOrientation::BlackAtBottom);
+            let board_size = layout.get_square_size() * BOARD_SIZE as f32;
+
+            // The board fills the same share of the smaller side of the window
+            assert!((board_size - height * BOARD_WINDOW_FRACTION).abs() < 1e-3);
+
+            // Anywhere within a square is on that square, not just its centre
+            let near_edge = layout.get_square_size() * 0.45;
+            for i in 0..BOARD_SIZE {
+                for j in 0..BOARD_SIZE {
+                    let (x, y) = board_to_pixel_coords(i, j, &layout);
+
+                    for (dx, dy) in [(0., 0.), (-near_edge, -near_edge), (near_edge, near_edge)] {
+                        assert_eq!(
+                            pixel_to_board_coords_checked(x + dx, y + dy, &layout),
+                            Some((i, j))
+                        );
+                    }
+                }
+            }
+
+            // Just past the edge of the board is off it
+            let edge = board_size / 2. + 1.;
+            assert_eq!(pixel_to_board_coords_checked(edge, 0., &layout), None);
+            assert_eq!(pixel_to_board_coords_checked(0., -edge, &layout), None);
+        }
+    }
+}
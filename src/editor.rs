@@ -0,0 +1,362 @@
+use bevy::{prelude::*, sprite::Anchor};
+use bevy_mod_picking::prelude::*;
+
+use crate::{
+    board::{Board, Player, TilePos},
+    captured_tray::CapturedPieces,
+    display::{get_piece_texture_atlas, spawn_missing_pieces, BoardLayout, BOARD_SIZE},
+    piece::{Piece, PieceType, TurnChangedEvent, COLOUR_AMT, PIECE_AMT},
+    premove::Premove,
+    promotion::PendingPromotion,
+    selection::SelectedPiece,
+};
+
+// The palette is drawn to the right of the board, with its pieces smaller than the board's
+const PALETTE_SCALE: f32 = 0.5;
+const PALETTE_MARGIN: f32 = 20.;
+const PALETTE_COLOUR: Color = Color::rgba(0.8, 0.8, 0.8, 0.9);
+const SELECTED_PALETTE_COLOUR: Color = Color::rgba(0.45, 0.3, 0.55, 0.9);
+const EDITOR_FONT_SIZE: f32 = 30.;
+
+// Lets pieces be placed and removed freely, for composing positions
+#[derive(Resource, Default)]
+pub struct EditorMode {
+    pub enabled: bool,
+    // The piece placed by clicking on a square
+    pub selected_piece: Option<Piece>,
+}
+
+// A piece in the palette, which is selected by clicking on it
+#[derive(Component)]
+pub struct PaletteChoice(pub Piece);
+
+// Every entity which is only shown in the editor, so that it can be cleaned up
+#[derive(Component)]
+pub struct EditorOverlay;
+
+// Where to draw the nth piece of the palette, in a column to the right of the board with the bottom player's pieces at the bottom
+pub fn get_palette_position(index: usize, layout: &BoardLayout) -> (f32, f32) {
+    let square_size = layout.get_square_size();
+    let slot_size = square_size * PALETTE_SCALE;
+
+    let top = if layout.orientation.get_bottom_player() == Player::White {
+        index
+    } else {
+        (index + PIECE_AMT) % (PIECE_AMT * COLOUR_AMT)
+    };
+
+    (
+        square_size * BOARD_SIZE as f32 / 2. + PALETTE_MARGIN + slot_size / 2.,
+        slot_size * (PIECE_AMT as f32 - 0.5 - top as f32),
+    )
+}
+
+// The status text goes under the palette, starting at its left edge
+pub fn get_editor_status_position(layout: &BoardLayout) -> (f32, f32) {
+    let square_size = layout.get_square_size();
+    let slot_size = square_size * PALETTE_SCALE;
+
+    (
+        square_size * BOARD_SIZE as f32 / 2. + PALETTE_MARGIN,
+        -slot_size * PIECE_AMT as f32 - PALETTE_MARGIN,
+    )
+}
+
+// The pieces in the palette, with black's first so that they are at the top
+pub fn get_palette_pieces() -> Vec<Piece> {
+    let piece_types = [
+        PieceType::King,
+        PieceType::Queen,
+        PieceType::Rook,
+        PieceType::Bishop,
+        PieceType::Knight,
+        PieceType::Pawn,
+    ];
+
+    [Player::Black, Player::White]
+        .into_iter()
+        .flat_map(|player| {
+            piece_types
+                .into_iter()
+                .map(move |piece_type| Piece::new(piece_type, player))
+        })
+        .collect()
+}
+
+// The side to move and castling rights, as they are written in FEN
+pub fn get_editor_status(board: &Board) -> String {
+    let fen = board.to_fen();
+    let mut sections = fen.split_whitespace().skip(1);
+
+    let player = match sections.next() {
+        Some("b") => "Black",
+        _ => "White",
+    };
+    let castling = sections.next().unwrap_or("-");
+
+    format!("{player} to move\nCastling: {castling}")
+}
+
+// Open the editor with E, and close it again once the position is valid
+#[allow(clippy::too_many_arguments)]
+pub fn editor_toggle_input(
+    mut commands: Commands,
+    keys: Res<ButtonInput<KeyCode>>,
+    asset_server: Res<AssetServer>,
+    mut texture_atlas_layouts: ResMut<Assets<TextureAtlasLayout>>,
+    mut editor_mode: ResMut<EditorMode>,
+    mut board: ResMut<Board>,
+    mut captured_pieces: ResMut<CapturedPieces>,
+    mut selected_piece: ResMut<SelectedPiece>,
+    mut premove: ResMut<Premove>,
+    pending_promotion: Res<PendingPromotion>,
+    layout: Res<BoardLayout>,
+    mut ev_turn_changed: EventWriter<TurnChangedEvent>,
+) {
+    if !keys.just_pressed(KeyCode::KeyE)
+        || selected_piece.dragged.is_some()
+        || pending_promotion.promotion.is_some()
+    {
+        return;
+    }
+
+    if !editor_mode.enabled {
+        editor_mode.enabled = true;
+        selected_piece.selected = None;
+        premove.cancel();
+
+        // Clicks go through the pieces to the squares beneath them
+        for entity in get_piece_entities(&board) {
+            commands.entity(entity).insert(Pickable::IGNORE);
+        }
+
+        return;
+    }
+
+    if let Err(e) = board.validate_position() {
+        error!("{e}");
+        return;
+    }
+
+    // Start a new game from the composed position, so that its moves can't be undone into the edits
+    let fen = board.to_fen();
+    let mut edited_board = match Board::from_fen(&fen) {
+        Ok(edited_board) => edited_board,
+        Err(e) => {
+            error!("{e}");
+            return;
+        }
+    };
+
+    for entity in get_piece_entities(&board) {
+        commands.entity(entity).despawn();
+    }
+
+    spawn_missing_pieces(
+        &mut commands,
+        &asset_server,
+        &mut texture_atlas_layouts,
+        &mut edited_board,
+        &layout,
+    );
+
+    *captured_pieces = CapturedPieces::default();
+    *board = edited_board;
+    editor_mode.enabled = false;
+
+    ev_turn_changed.send(TurnChangedEvent {
+        new_player: board.player,
+    });
+
+    info!("Composed position: {fen}");
+}
+
+pub fn get_piece_entities(board: &Board) -> Vec<Entity> {
+    (0..BOARD_SIZE)
+        .flat_map(|file| (0..BOARD_SIZE).map(move |rank| TilePos::new(file, rank)))
+        .filter_map(|tile_pos| board.get_entity(tile_pos))
+        .collect()
+}
+
+// Choose the side to move with W and B, and toggle castling rights with 1 to 4 (in the order KQkq)
+pub fn editor_setup_input(
+    keys: Res<ButtonInput<KeyCode>>,
+    editor_mode: Res<EditorMode>,
+    mut board: ResMut<Board>,
+) {
+    if !editor_mode.enabled {
+        return;
+    }
+
+    if keys.just_pressed(KeyCode::KeyW) {
+        board.pass_turn_to(Player::White);
+    } else if keys.just_pressed(KeyCode::KeyB) {
+        board.pass_turn_to(Player::Black);
+    }
+
+    let castling_keys = [
+        (KeyCode::Digit1, Player::White, true),
+        (KeyCode::Digit2, Player::White, false),
+        (KeyCode::Digit3, Player::Black, true),
+        (KeyCode::Digit4, Player::Black, false),
+    ];
+    for (key, player, is_king_side) in castling_keys {
+        if !keys.just_pressed(key) {
+            continue;
+        }
+
+        let (king_side, queen_side) = board.get_castling_rights(player);
+        let castling_rights = if is_king_side {
+            (!king_side, queen_side)
+        } else {
+            (king_side, !queen_side)
+        };
+
+        board.set_castling_rights(player, castling_rights);
+    }
+}
+
+// Select a piece from the palette, then click squares to place it, or right click squares to empty them
+#[allow(clippy::too_many_arguments)]
+pub fn editor_click(
+    mut commands: Commands,
+    mut click_er: EventReader<Pointer<Click>>,
+    palette_query: Query<&PaletteChoice>,
+    tile_query: Query<&TilePos>,
+    asset_server: Res<AssetServer>,
+    mut texture_atlas_layouts: ResMut<Assets<TextureAtlasLayout>>,
+    mut editor_mode: ResMut<EditorMode>,
+    mut board: ResMut<Board>,
+    layout: Res<BoardLayout>,
+) {
+    if !editor_mode.enabled {
+        click_er.clear();
+        return;
+    }
+
+    for click in click_er.read() {
+        if let Ok(PaletteChoice(piece)) = palette_query.get(click.target) {
+            editor_mode.selected_piece = Some(*piece);
+            continue;
+        }
+
+        let Ok(&tile_pos) = tile_query.get(click.target) else {
+            continue;
+        };
+
+        // Clicking a square which already has the selected piece removes it again
+        let piece = match (click.button, editor_mode.selected_piece) {
+            (PointerButton::Primary, Some(piece)) if board.get_piece(tile_pos) != piece => piece,
+            (PointerButton::Primary, Some(_)) | (PointerButton::Secondary, _) => Piece::None,
+            _ => continue,
+        };
+
+        if let Some(entity) = board.get_entity(tile_pos) {
+            commands.entity(entity).despawn();
+            board.set_entity(tile_pos, None);
+        }
+
+        // The pawn which could have been captured en passant may have been moved
+        board.set_piece(tile_pos, piece);
+        board.set_en_passant_on_last_move(None);
+
+        spawn_missing_pieces(
+            &mut commands,
+            &asset_server,
+            &mut texture_atlas_layouts,
+            &mut board,
+            &layout,
+        );
+        if let Some(entity) = board.get_entity(tile_pos) {
+            commands.entity(entity).insert(Pickable::IGNORE);
+        }
+    }
+}
+
+// Show the palette and the side to move and castling rights while the editor is open
+pub fn display_editor(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mut texture_atlas_layouts: ResMut<Assets<TextureAtlasLayout>>,
+    editor_mode: Res<EditorMode>,
+    board: Res<Board>,
+    layout: Res<BoardLayout>,
+    overlay_query: Query<Entity, With<EditorOverlay>>,
+) {
+    if !editor_mode.is_changed() && !layout.is_changed() && !board.is_changed() {
+        return;
+    }
+
+    for entity in overlay_query.iter() {
+        commands.entity(entity).despawn();
+    }
+
+    if !editor_mode.enabled {
+        return;
+    }
+
+    let (texture, texture_atlas_layout) =
+        get_piece_texture_atlas(&asset_server, &mut texture_atlas_layouts);
+    let slot_size = layout.get_square_size() * PALETTE_SCALE;
+
+    for (index, piece) in get_palette_pieces().into_iter().enumerate() {
+        let (x, y) = get_palette_position(index, &layout);
+
+        let colour = if editor_mode.selected_piece == Some(piece) {
+            SELECTED_PALETTE_COLOUR
+        } else {
+            PALETTE_COLOUR
+        };
+
+        // Drawn over the captured pieces, which are in the same place
+        commands.spawn((
+            SpriteBundle {
+                sprite: Sprite {
+                    color: colour,
+                    custom_size: Some(Vec2::splat(slot_size)),
+                    ..default()
+                },
+                transform: Transform::from_xyz(x, y, 20.),
+                ..default()
+            },
+            Pickable::IGNORE,
+            EditorOverlay,
+        ));
+
+        commands.spawn((
+            SpriteSheetBundle {
+                texture: texture.clone(),
+                atlas: TextureAtlas {
+                    layout: texture_atlas_layout.clone(),
+                    index: piece.into(),
+                },
+                transform: Transform::from_scale(Vec3::splat(
+                    layout.get_piece_scale() * PALETTE_SCALE,
+                ))
+                .with_translation(Vec3::new(x, y, 21.)),
+                ..default()
+            },
+            PaletteChoice(piece),
+            EditorOverlay,
+        ));
+    }
+
+    let (x, y) = get_editor_status_position(&layout);
+
+    commands.spawn((
+        Text2dBundle {
+            text: Text::from_section(
+                get_editor_status(&board),
+                TextStyle {
+                    font_size: EDITOR_FONT_SIZE,
+                    color: Color::WHITE,
+                    ..default()
+                },
+            ),
+            text_anchor: Anchor::TopLeft,
+            transform: Transform::from_xyz(x, y, 21.),
+            ..default()
+        },
+        EditorOverlay,
+    ));
+}
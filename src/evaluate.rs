@@ -0,0 +1,232 @@
+use crate::{
+    board::{Board, Player},
+    display::BOARD_SIZE,
+    piece::{Piece, PieceType, COLOUR_AMT, PIECE_AMT},
+};
+
+pub const PAWN_VALUE: i32 = 100;
+pub const KNIGHT_VALUE: i32 = 300;
+pub const BISHOP_VALUE: i32 = 320;
+pub const ROOK_VALUE: i32 = 500;
+pub const QUEEN_VALUE: i32 = 900;
+
+// Score for a side which has been checkmated, far larger than any material difference
+pub const CHECKMATE_SCORE: i32 = 100_000;
+
+// Piece-square tables are from White's point of view, with rank 8 at the top so they line up with bitboard indices
+#[rustfmt::skip]
+const PAWN_TABLE: [i32; BOARD_SIZE * BOARD_SIZE] = [
+     0,  0,  0,  0,  0,  0,  0,  0,
+    50, 50, 50, 50, 50, 50, 50, 50,
+    10, 10, 20, 30, 30, 20, 10, 10,
+     5,  5, 10, 25, 25, 10,  5,  5,
+     0,  0,  0, 20, 20,  0,  0,  0,
+     5, -5,-10,  0,  0,-10, -5,  5,
+     5, 10, 10,-20,-20, 10, 10,  5,
+     0,  0,  0,  0,  0,  0,  0,  0,
+];
+
+#[rustfmt::skip]
+const KNIGHT_TABLE: [i32; BOARD_SIZE * BOARD_SIZE] = [
+    -50,-40,-30,-30,-30,-30,-40,-50,
+    -40,-20,  0,  0,  0,  0,-20,-40,
+    -30,  0, 10, 15, 15, 10,  0,-30,
+    -30,  5, 15, 20, 20, 15,  5,-30,
+    -30,  0, 15, 20, 20, 15,  0,-30,
+    -30,  5, 10, 15, 15, 10,  5,-30,
+    -40,-20,  0,  5,  5,  0,-20,-40,
+    -50,-40,-30,-30,-30,-30,-40,-50,
+];
+
+#[rustfmt::skip]
+const BISHOP_TABLE: [i32; BOARD_SIZE * BOARD_SIZE] = [
+    -20,-10,-10,-10,-10,-10,-10,-20,
+    -10,  0,  0,  0,  0,  0,  0,-10,
+    -10,  0,  5, 10, 10,  5,  0,-10,
+    -10,  5,  5, 10, 10,  5,  5,-10,
+    -10,  0, 10, 10, 10, 10,  0,-10,
+    -10, 10, 10, 10, 10, 10, 10,-10,
+    -10,  5,  0,  0,  0,  0,  5,-10,
+    -20,-10,-10,-10,-10,-10,-10,-20,
+];
+
+#[rustfmt::skip]
+const ROOK_TABLE: [i32; BOARD_SIZE * BOARD_SIZE] = [
+     0,  0,  0,  0,  0,  0,  0,  0,
+     5, 10, 10, 10, 10, 10, 10,  5,
+    -5,  0,  0,  0,  0,  0,  0, -5,
+    -5,  0,  0,  0,  0,  0,  0, -5,
+    -5,  0,  0,  0,  0,  0,  0, -5,
+    -5,  0,  0,  0,  0,  0,  0, -5,
+    -5,  0,  0,  0,  0,  0,  0, -5,
+     0,  0,  0,  5,  5,  0,  0,  0,
+];
+
+#[rustfmt::skip]
+const QUEEN_TABLE: [i32; BOARD_SIZE * BOARD_SIZE] = [
+    -20,-10,-10, -5, -5,-10,-10,-20,
+    -10,  0,  0,  0,  0,  0,  0,-10,
+    -10,  0,  5,  5,  5,  5,  0,-10,
+     -5,  0,  5,  5,  5,  5,  0, -5,
+      0,  0,  5,  5,  5,  5,  0, -5,
+    -10,  5,  5,  5,  5,  5,  0,-10,
+    -10,  0,  5,  0,  0,  0,  0,-10,
+    -20,-10,-10, -5, -5,-10,-10,-20,
+];
+
+#[rustfmt::skip]
+const KING_TABLE: [i32; BOARD_SIZE * BOARD_SIZE] = [
+    -30,-40,-40,-50,-50,-40,-40,-30,
+    -30,-40,-40,-50,-50,-40,-40,-30,
+    -30,-40,-40,-50,-50,-40,-40,-30,
+    -30,-40,-40,-50,-50,-40,-40,-30,
+    -20,-30,-30,-40,-40,-30,-30,-20,
+    -10,-20,-20,-20,-20,-20,-20,-10,
+     20, 20,  0,  0,  0,  0, 20, 20,
+     20, 30, 10,  0,  0, 10, 30, 20,
+];
+
+// Score of the position in centipawns, positive when White is better
+pub fn evaluate(board: &Board) -> i32 {
+    let player = board.player;
+    if board.clone().is_checkmate(player) {
+        return match player {
+            Player::White => -CHECKMATE_SCORE,
+            Player::Black => CHECKMATE_SCORE,
+        };
+    }
+
+    let mut score = 0;
+
+    for piece_index in 0..PIECE_AMT * COLOUR_AMT {
+        let piece = Into::<Piece>::into(piece_index);
+        let (value, table) = get_piece_value_and_table(piece);
+
+        for tile_pos in board.positions[piece].iter() {
+            let index = tile_pos.to_index();
+
+            // Black's tables are White's flipped vertically
+            if piece.is_white() {
+                score += value + table[index];
+            } else {
+                score -= value + table[index ^ (BOARD_SIZE * (BOARD_SIZE - 1))];
+            }
+        }
+    }
+
+    score
+}
+
+fn get_piece_value_and_table(piece: Piece) -> (i32, &'static [i32; BOARD_SIZE * BOARD_SIZE]) {
+    match piece.piece_type() {
+        Some(PieceType::Pawn) => (PAWN_VALUE, &PAWN_TABLE),
+        Some(PieceType::Knight) => (KNIGHT_VALUE, &KNIGHT_TABLE),
+        Some(PieceType::Bishop) => (BISHOP_VALUE, &BISHOP_TABLE),
+        Some(PieceType::Rook) => (ROOK_VALUE, &ROOK_TABLE),
+        Some(PieceType::Queen) => (QUEEN_VALUE, &QUEEN_TABLE),
+        Some(PieceType::King) | None => (0, &KING_TABLE),
+    }
+}
+
+impl Board {
+    // The material difference in pawns using the standard piece values, positive when White is ahead
+    pub fn material_balance(&self) -> i32 {
+        (0..PIECE_AMT * COLOUR_AMT)
+            .map(Into::<Piece>::into)
+            .map(|piece| {
+                let value = piece.piece_type().map_or(0, get_material_value)
+                    * self.piece_count(piece) as i32;
+
+                if piece.is_white() {
+                    value
+                } else {
+                    -value
+                }
+            })
+            .sum()
+    }
+}
+
+// Kings are never captured, so aren't counted
+fn get_material_value(piece_type: PieceType) -> i32 {
+    match piece_type {
+        PieceType::Pawn => 1,
+        PieceType::Knight | PieceType::Bishop => 3,
+        PieceType::Rook => 5,
+        PieceType::Queen => 9,
+        PieceType::King => 0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // The same position with the colours swapped and the board flipped vertically
+    fn mirror_fen(fen: &str) -> String {
+        let sections = fen.split_whitespace().collect::<Vec<_>>();
+        let swap_case = |section: &str| {
+            section
+                .chars()
+                .map(|chr| {
+                    if chr.is_ascii_uppercase() {
+                        chr.to_ascii_lowercase()
+                    } else {
+                        chr.to_ascii_uppercase()
+                    }
+                })
+                .collect::<String>()
+        };
+
+        let placement = sections[0]
+            .split('/')
+            .rev()
+            .map(swap_case)
+            .collect::<Vec<_>>()
+            .join("/");
+        let player = if sections[1] == "w" { "b" } else { "w" };
+
+        format!("{placement} {player} {} - 0 1", swap_case(sections[2]))
+    }
+
+    #[test]
+    fn mirrored_positions_have_opposite_scores() {
+        for fen in [
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+            "r1bqkbnr/pppp1ppp/2n5/4p3/2B1P3/5N2/PPPP1PPP/RNBQK2R b KQkq - 3 3",
+            "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+            "8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1",
+        ] {
+            let board = Board::from_fen(fen).unwrap();
+            let mirrored = Board::from_fen(mirror_fen(fen)).unwrap();
+
+            assert_eq!(evaluate(&board), -evaluate(&mirrored), "{fen}");
+        }
+    }
+
+    #[test]
+    fn queen_up_is_worth_a_queen() {
+        let board =
+            Board::from_fen("rnb1kbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1").unwrap();
+
+        // The piece-square tables only change the score slightly
+        let score = evaluate(&board);
+        assert!(
+            (QUEEN_VALUE - 50..=QUEEN_VALUE + 50).contains(&score),
+            "{score}"
+        );
+    }
+
+    #[test]
+    fn material_balance_in_pawns() {
+        assert_eq!(Board::default().material_balance(), 0);
+
+        // A rook is worth five pawns (500 centipawns), and kings aren't counted
+        let rook_up = Board::from_fen("4k3/8/8/8/8/8/8/R3K3 w - - 0 1").unwrap();
+        assert_eq!(rook_up.material_balance(), 5);
+        assert_eq!(rook_up.material_balance() * PAWN_VALUE, ROOK_VALUE);
+
+        let black_rook_up = Board::from_fen("r3k3/8/8/8/8/8/8/4K3 w - - 0 1").unwrap();
+        assert_eq!(black_rook_up.material_balance(), -5);
+    }
+}
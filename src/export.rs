@@ -0,0 +1,365 @@
+use std::{
+    error::Error,
+    fmt, io,
+    path::{Path, PathBuf},
+    sync::{
+        mpsc::{self, Receiver, Sender},
+        Mutex,
+    },
+};
+
+use bevy::{
+    prelude::*,
+    render::{
+        camera::RenderTarget,
+        extract_component::{ExtractComponent, ExtractComponentPlugin},
+        render_asset::{RenderAssetUsages, RenderAssets},
+        render_graph::{self, NodeRunError, RenderGraph, RenderGraphContext, RenderLabel},
+        render_resource::{
+            Buffer, BufferDescriptor, BufferUsages, Extent3d, ImageCopyBuffer, ImageDataLayout,
+            Maintain, MapMode, TextureDimension, TextureFormat, TextureUsages,
+        },
+        renderer::{RenderContext, RenderDevice},
+        Render, RenderApp, RenderSet,
+    },
+};
+use image::{ImageError, ImageFormat};
+
+use crate::display::{BoardLayout, BOARD_SIZE, PIECE_SIZE_IMG};
+
+const EXPORT_IMAGE_PATH: &str = "board.png";
+
+// Each square is exported at the size of the piece images
+const EXPORT_IMAGE_SIZE: u32 = PIECE_SIZE_IMG as u32 * BOARD_SIZE as u32;
+
+// The image is rendered as 8-bit RGBA
+const BYTES_PER_PIXEL: usize = 4;
+
+// Rendered before the main camera, so that the board is drawn the same way as on screen
+const EXPORT_CAMERA_ORDER: isize = -1;
+
+// Render the board (squares and pieces) to a PNG at this path
+#[derive(Event, Clone, PartialEq, Eq, Debug)]
+pub struct ExportImageEvent {
+    pub path: PathBuf,
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum ExportError {
+    InvalidSize(usize, u32, u32),
+    Io(io::ErrorKind),
+    Encoding(String),
+}
+
+impl fmt::Display for ExportError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Could not export board image:")?;
+
+        match self {
+            ExportError::InvalidSize(len, width, height) => write!(
+                f,
+                "{len} bytes were rendered, which is not a {width}x{height} image"
+            ),
+            ExportError::Io(kind) => write!(f, "{kind}"),
+            ExportError::Encoding(e) => write!(f, "{e}"),
+        }
+    }
+}
+
+impl Error for ExportError {}
+
+impl From<ImageError> for ExportError {
+    fn from(e: ImageError) -> Self {
+        match e {
+            ImageError::IoError(e) => ExportError::Io(e.kind()),
+            e => ExportError::Encoding(e.to_string()),
+        }
+    }
+}
+
+// The camera which renders the board into an image, and the buffer the image is copied into so it can be read back
+#[derive(Component, ExtractComponent, Clone)]
+pub struct BoardExport {
+    image: Handle<Image>,
+    buffer: Buffer,
+    path: PathBuf,
+}
+
+// The rendered image, with rows padded to the alignment which the GPU copies them with
+struct RenderedImage {
+    camera: Entity,
+    path: PathBuf,
+    data: Vec<u8>,
+}
+
+#[derive(Resource)]
+struct RenderedImageSender(Sender<RenderedImage>);
+
+#[derive(Resource)]
+struct RenderedImageReceiver(Mutex<Receiver<RenderedImage>>);
+
+#[derive(RenderLabel, Clone, PartialEq, Eq, Hash, Debug)]
+struct BoardExportLabel;
+
+// Renders the board to an image off screen, then saves the image once it has been read back from the GPU
+pub struct BoardExportPlugin;
+
+impl Plugin for BoardExportPlugin {
+    fn build(&self, app: &mut App) {
+        let (sender, receiver) = mpsc::channel();
+
+        app.add_plugins(ExtractComponentPlugin::<BoardExport>::default())
+            .insert_resource(RenderedImageReceiver(Mutex::new(receiver)))
+            .add_systems(Update, rendered_image_receiver);
+
+        let Ok(render_app) = app.get_sub_app_mut(RenderApp) else {
+            return;
+        };
+
+        render_app
+            .insert_resource(RenderedImageSender(sender))
+            .add_systems(
+                Render,
+                read_rendered_images
+                    .after(RenderSet::Render)
+                    .before(RenderSet::Cleanup),
+            );
+
+        // Copy the image once the cameras have been drawn
+        let mut render_graph = render_app.world.resource_mut::<RenderGraph>();
+        render_graph.add_node(BoardExportLabel, BoardExportNode);
+        render_graph.add_node_edge(bevy::render::graph::CameraDriverLabel, BoardExportLabel);
+    }
+}
+
+// Bytes in each row of the image, and in each row of the buffer it is copied into
+fn get_row_sizes(width: u32) -> (usize, usize) {
+    let row_bytes = width as usize * BYTES_PER_PIXEL;
+
+    (row_bytes, RenderDevice::align_copy_bytes_per_row(row_bytes))
+}
+
+// Remove the padding from the end of each row, and encode the image as a PNG
+pub fn save_rendered_image(
+    data: &[u8],
+    width: u32,
+    height: u32,
+    path: &Path,
+) -> Result<(), ExportError> {
+    let (row_bytes, padded_row_bytes) = get_row_sizes(width);
+    if data.len() != padded_row_bytes * height as usize {
+        return Err(ExportError::InvalidSize(data.len(), width, height));
+    }
+
+    let pixels = data
+        .chunks_exact(padded_row_bytes)
+        .flat_map(|row| &row[..row_bytes])
+        .copied()
+        .collect::<Vec<_>>();
+
+    image::save_buffer_with_format(
+        path,
+        &pixels,
+        width,
+        height,
+        image::ColorType::Rgba8,
+        ImageFormat::Png,
+    )?;
+
+    Ok(())
+}
+
+struct BoardExportNode;
+
+impl render_graph::Node for BoardExportNode {
+    fn run(
+        &self,
+        _graph: &mut RenderGraphContext,
+        render_context: &mut RenderContext,
+        world: &World,
+    ) -> Result<(), NodeRunError> {
+        let gpu_images = world.resource::<RenderAssets<Image>>();
+
+        for export in world.iter_entities().filter_map(|e| e.get::<BoardExport>()) {
+            let Some(gpu_image) = gpu_images.get(&export.image) else {
+                continue;
+            };
+
+            let size = Extent3d {
+                width: gpu_image.size.x as u32,
+                height: gpu_image.size.y as u32,
+                ..default()
+            };
+            let (_, padded_row_bytes) = get_row_sizes(size.width);
+
+            render_context.command_encoder().copy_texture_to_buffer(
+                gpu_image.texture.as_image_copy(),
+                ImageCopyBuffer {
+                    buffer: &export.buffer,
+                    layout: ImageDataLayout {
+                        offset: 0,
+                        bytes_per_row: Some(padded_row_bytes as u32),
+                        rows_per_image: None,
+                    },
+                },
+                size,
+            );
+        }
+
+        Ok(())
+    }
+}
+
+// Wait for the copied images on the GPU, then send them back to the main world
+fn read_rendered_images(
+    exports: Query<(Entity, &BoardExport)>,
+    render_device: Res<RenderDevice>,
+    sender: Res<RenderedImageSender>,
+) {
+    for (camera, export) in exports.iter() {
+        let slice = export.buffer.slice(..);
+        let (map_sender, map_receiver) = mpsc::channel();
+        render_device.map_buffer(&slice, MapMode::Read, move |result| {
+            let _ = map_sender.send(result);
+        });
+        render_device.poll(Maintain::wait());
+
+        if let Ok(Ok(())) = map_receiver.recv() {
+            let _ = sender.0.send(RenderedImage {
+                camera,
+                path: export.path.clone(),
+                data: slice.get_mapped_range().to_vec(),
+            });
+        }
+
+        export.buffer.unmap();
+    }
+}
+
+fn rendered_image_receiver(
+    mut commands: Commands,
+    receiver: Res<RenderedImageReceiver>,
+    exports: Query<&BoardExport>,
+    mut images: ResMut<Assets<Image>>,
+) {
+    let Ok(receiver) = receiver.0.lock() else {
+        return;
+    };
+
+    for rendered in receiver.try_iter() {
+        // The same camera can be copied more than once before it is despawned
+        let Ok(export) = exports.get(rendered.camera) else {
+            continue;
+        };
+
+        match save_rendered_image(
+            &rendered.data,
+            EXPORT_IMAGE_SIZE,
+            EXPORT_IMAGE_SIZE,
+            &rendered.path,
+        ) {
+            Ok(()) => info!("Exported board image to {}", rendered.path.display()),
+            Err(e) => error!("{e}"),
+        }
+
+        images.remove(&export.image);
+        commands.entity(rendered.camera).despawn();
+    }
+}
+
+// Export the board as an image with P
+pub fn export_image_input(
+    keys: Res<ButtonInput<KeyCode>>,
+    mut ev_export_image: EventWriter<ExportImageEvent>,
+) {
+    if keys.just_pressed(KeyCode::KeyP) {
+        ev_export_image.send(ExportImageEvent {
+            path: PathBuf::from(EXPORT_IMAGE_PATH),
+        });
+    }
+}
+
+// Spawn a camera which draws the board into an image, which is saved once it has been read back
+pub fn export_image_event_reader(
+    mut commands: Commands,
+    mut ev_export_image: EventReader<ExportImageEvent>,
+    layout: Res<BoardLayout>,
+    render_device: Res<RenderDevice>,
+    mut images: ResMut<Assets<Image>>,
+) {
+    for ev in ev_export_image.read() {
+        let size = Extent3d {
+            width: EXPORT_IMAGE_SIZE,
+            height: EXPORT_IMAGE_SIZE,
+            ..default()
+        };
+
+        let mut image = Image::new_fill(
+            size,
+            TextureDimension::D2,
+            &[0; 4],
+            TextureFormat::Rgba8UnormSrgb,
+            RenderAssetUsages::default(),
+        );
+        image.texture_descriptor.usage |=
+            TextureUsages::RENDER_ATTACHMENT | TextureUsages::COPY_SRC;
+        let image = images.add(image);
+
+        let (_, padded_row_bytes) = get_row_sizes(size.width);
+        let buffer = render_device.create_buffer(&BufferDescriptor {
+            label: Some("board_export_buffer"),
+            size: (padded_row_bytes * size.height as usize) as u64,
+            usage: BufferUsages::MAP_READ | BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        // Fit the whole board into the image, whatever size it is drawn at on screen
+        let mut camera = Camera2dBundle::default();
+        camera.camera.target = RenderTarget::Image(image.clone());
+        camera.camera.order = EXPORT_CAMERA_ORDER;
+        camera.projection.scale =
+            layout.get_square_size() * BOARD_SIZE as f32 / EXPORT_IMAGE_SIZE as f32;
+
+        commands.spawn((
+            camera,
+            BoardExport {
+                image,
+                buffer,
+                path: ev.path.clone(),
+            },
+        ));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn saves_rendered_image() {
+        let path = std::env::temp_dir().join("chess_bevy_refactor_export_test.png");
+        let (width, height) = (3, 2);
+
+        // Rows are padded, so the padding has to be removed before the image is saved
+        let (_, padded_row_bytes) = get_row_sizes(width);
+        let data = vec![255; padded_row_bytes * height as usize];
+        save_rendered_image(&data, width, height, &path).unwrap();
+
+        let saved = image::open(&path).unwrap();
+        assert!(std::fs::metadata(&path).unwrap().len() > 0);
+        assert_eq!((saved.width(), saved.height()), (width, height));
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn rejects_wrongly_sized_image() {
+        let path = std::env::temp_dir().join("chess_bevy_refactor_export_size_test.png");
+
+        assert_eq!(
+            save_rendered_image(&[0; 12], 3, 2, &path),
+            Err(ExportError::InvalidSize(12, 3, 2))
+        );
+        assert!(!path.exists());
+    }
+}
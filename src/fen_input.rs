@@ -0,0 +1,334 @@
+use bevy::prelude::*;
+
+use crate::{
+    board::Board,
+    captured_tray::CapturedPieces,
+    clock::{ChessClock, DEFAULT_CLOCK_TIME},
+    display::{spawn_missing_pieces, BoardLayout},
+    editor::{get_piece_entities, EditorMode},
+    piece::TurnChangedEvent,
+    premove::Premove,
+    promotion::PendingPromotion,
+    resignation::AgreementState,
+    selection::SelectedPiece,
+};
+
+const FEN_INPUT_FONT_SIZE: f32 = 28.;
+const FEN_INPUT_TOP: f32 = 20.;
+const FEN_INPUT_COLOUR: Color = Color::rgba(0.15, 0.15, 0.15, 0.9);
+const FEN_ERROR_COLOUR: Color = Color::rgb(0.9, 0.3, 0.3);
+
+// A text box for typing in a FEN, opened with F2
+#[derive(Resource, Default)]
+pub struct FenInput {
+    pub open: bool,
+    pub text: String,
+    // Why the last FEN entered couldn't be loaded
+    pub error: Option<String>,
+}
+
+#[derive(Component)]
+pub struct FenInputBox;
+
+#[derive(Component)]
+pub struct FenInputText;
+
+// Keyboard shortcuts are ignored while a FEN is being typed
+pub fn fen_input_closed(fen_input: Res<FenInput>) -> bool {
+    !fen_input.open
+}
+
+// The board for a FEN typed by the user, which also has to be a position that can be played from
+pub fn parse_fen_input(text: &str) -> Result<Board, String> {
+    Board::from_fen_validated(text.trim()).map_err(|e| e.to_string())
+}
+
+pub fn spawn_fen_input(mut commands: Commands) {
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    top: Val::Px(FEN_INPUT_TOP),
+                    width: Val::Percent(100.),
+                    justify_content: JustifyContent::Center,
+                    display: Display::None,
+                    ..default()
+                },
+                ..default()
+            },
+            FenInputBox,
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                TextBundle::from_sections([
+                    TextSection::new(
+                        "",
+                        TextStyle {
+                            font_size: FEN_INPUT_FONT_SIZE,
+                            color: Color::WHITE,
+                            ..default()
+                        },
+                    ),
+                    TextSection::new(
+                        "",
+                        TextStyle {
+                            font_size: FEN_INPUT_FONT_SIZE,
+                            color: FEN_ERROR_COLOUR,
+                            ..default()
+                        },
+                    ),
+                ])
+                .with_background_color(FEN_INPUT_COLOUR),
+                FenInputText,
+            ));
+        });
+}
+
+// Open the text box with F2, and close it again with F2 or Escape
+pub fn fen_input_toggle(
+    keys: Res<ButtonInput<KeyCode>>,
+    mut fen_input: ResMut<FenInput>,
+    editor_mode: Res<EditorMode>,
+    selected_piece: Res<SelectedPiece>,
+    pending_promotion: Res<PendingPromotion>,
+) {
+    if fen_input.open {
+        if keys.any_just_pressed([KeyCode::F2, KeyCode::Escape]) {
+            fen_input.open = false;
+        }
+    } else if keys.just_pressed(KeyCode::F2)
+        && !editor_mode.enabled
+        && selected_piece.dragged.is_none()
+        && pending_promotion.promotion.is_none()
+    {
+        *fen_input = FenInput {
+            open: true,
+            ..default()
+        };
+    }
+}
+
+pub fn fen_input_typing(
+    mut ev_character: EventReader<ReceivedCharacter>,
+    keys: Res<ButtonInput<KeyCode>>,
+    mut fen_input: ResMut<FenInput>,
+) {
+    if !fen_input.open {
+        ev_character.clear();
+        return;
+    }
+
+    let mut text = fen_input.text.clone();
+    for ev in ev_character.read() {
+        text.extend(ev.char.chars().filter(|chr| !chr.is_control()));
+    }
+
+    if keys.just_pressed(KeyCode::Backspace) {
+        text.pop();
+    }
+
+    // The error is about the FEN as it was, so it goes once the FEN is edited
+    if text != fen_input.text {
+        fen_input.text = text;
+        fen_input.error = None;
+    }
+}
+
+// Replace the game with the typed position when Enter is pressed, starting the move history and clocks again
+#[allow(clippy::too_many_arguments)]
+pub fn fen_input_submit(
+    mut commands: Commands,
+    keys: Res<ButtonInput<KeyCode>>,
+    asset_server: Res<AssetServer>,
+    mut texture_atlas_layouts: ResMut<Assets<TextureAtlasLayout>>,
+    mut fen_input: ResMut<FenInput>,
+    mut board: ResMut<Board>,
+    mut captured_pieces: ResMut<CapturedPieces>,
+    mut selected_piece: ResMut<SelectedPiece>,
+    mut premove: ResMut<Premove>,
+    mut clock: ResMut<ChessClock>,
+    mut agreement_state: ResMut<AgreementState>,
+    layout: Res<BoardLayout>,
+    mut ev_turn_changed: EventWriter<TurnChangedEvent>,
+) {
+    if !fen_input.open || !keys.just_pressed(KeyCode::Enter) {
+        return;
+    }
+
+    let mut new_board = match parse_fen_input(&fen_input.text) {
+        Ok(new_board) => new_board,
+        Err(e) => {
+            fen_input.error = Some(e);
+            return;
+        }
+    };
+
+    for entity in get_piece_entities(&board) {
+        commands.entity(entity).despawn();
+    }
+
+    spawn_missing_pieces(
+        &mut commands,
+        &asset_server,
+        &mut texture_atlas_layouts,
+        &mut new_board,
+        &layout,
+    );
+
+    *captured_pieces = CapturedPieces::default();
+    selected_piece.selected = None;
+    premove.cancel();
+    *clock = ChessClock::new(DEFAULT_CLOCK_TIME, clock.increment);
+    *agreement_state = AgreementState::default();
+    *board = new_board;
+
+    ev_turn_changed.send(TurnChangedEvent {
+        new_player: board.player,
+    });
+
+    info!("Loaded position: {}", board.to_fen());
+
+    *fen_input = FenInput::default();
+}
+
+pub fn update_fen_input_text(
+    fen_input: Res<FenInput>,
+    mut box_query: Query<&mut Style, With<FenInputBox>>,
+    mut text_query: Query<&mut Text, With<FenInputText>>,
+) {
+    if !fen_input.is_changed() {
+        return;
+    }
+
+    for mut style in box_query.iter_mut() {
+        style.display = if fen_input.open {
+            Display::Flex
+        } else {
+            Display::None
+        };
+    }
+
+    for mut text in text_query.iter_mut() {
+        text.sections[0].value = format!("FEN: {}_", fen_input.text);
+        text.sections[1].value = fen_input
+            .error
+            .as_ref()
+            .map(|e| format!("\n{e}"))
+            .unwrap_or_default();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        board::{Player, TilePos},
+        display::BOARD_SIZE,
+        piece::Piece,
+    };
+
+    const LOADED_FEN: &str = "r3k2r/8/8/8/8/8/8/R3K2R b KQkq - 3 20";
+
+    fn fen_input_app() -> App {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, AssetPlugin::default()))
+            .init_asset::<Image>()
+            .init_asset::<TextureAtlasLayout>()
+            .init_resource::<Board>()
+            .init_resource::<BoardLayout>()
+            .init_resource::<CapturedPieces>()
+            .init_resource::<SelectedPiece>()
+            .init_resource::<Premove>()
+            .init_resource::<ChessClock>()
+            .init_resource::<AgreementState>()
+            .init_resource::<FenInput>()
+            .init_resource::<ButtonInput<KeyCode>>()
+            .add_event::<TurnChangedEvent>()
+            .add_systems(Update, fen_input_submit);
+
+        app
+    }
+
+    fn submit(app: &mut App, text: &str) {
+        *app.world.resource_mut::<FenInput>() = FenInput {
+            open: true,
+            text: text.to_string(),
+            error: None,
+        };
+
+        let mut keys = app.world.resource_mut::<ButtonInput<KeyCode>>();
+        keys.reset_all();
+        keys.press(KeyCode::Enter);
+
+        app.update();
+    }
+
+    #[test]
+    fn parse_round_trips() {
+        let board = parse_fen_input(&format!(" {LOADED_FEN}\n")).unwrap();
+
+        assert_eq!(board.to_fen(), LOADED_FEN);
+        assert_eq!(board.count_player(Player::White), 3);
+        assert_eq!(board.count_player(Player::Black), 3);
+    }
+
+    #[test]
+    fn parse_rejects_invalid_fens() {
+        for fen in [
+            "rubbish",
+            // No kings
+            "8/8/8/8/8/8/8/8 w - - 0 1",
+            // '-' is not a piece, and empty squares are written as digits
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBN- w - - 0 1",
+        ] {
+            assert!(parse_fen_input(fen).is_err(), "{fen}");
+        }
+    }
+
+    #[test]
+    fn submit_shows_error_inline() {
+        let mut app = fen_input_app();
+        submit(
+            &mut app,
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBN- w - - 0 1",
+        );
+
+        let fen_input = app.world.resource::<FenInput>();
+        assert!(fen_input.open);
+        assert!(fen_input.error.as_ref().is_some_and(|e| e.contains("'-'")));
+        assert_eq!(
+            app.world.resource::<Board>().to_fen(),
+            Board::default().to_fen()
+        );
+    }
+
+    #[test]
+    fn submit_replaces_board() {
+        let mut app = fen_input_app();
+
+        // Every piece on the old board has a sprite, which should be despawned
+        let mut board = Board::default();
+        for file in 0..BOARD_SIZE {
+            for rank in 0..BOARD_SIZE {
+                let tile_pos = TilePos::new(file, rank);
+                if board.get_piece(tile_pos) != Piece::None {
+                    board.set_entity(tile_pos, Some(app.world.spawn_empty().id()));
+                }
+            }
+        }
+        let old_entities = get_piece_entities(&board);
+        app.insert_resource(board);
+
+        submit(&mut app, LOADED_FEN);
+
+        let board = app.world.resource::<Board>();
+        assert_eq!(board.to_fen(), LOADED_FEN);
+        assert_eq!(board.get_ply(), 0);
+        assert_eq!(get_piece_entities(board).len(), 6);
+        assert!(old_entities
+            .iter()
+            .all(|&entity| app.world.get_entity(entity).is_none()));
+        assert!(!app.world.resource::<FenInput>().open);
+    }
+}
@@ -0,0 +1,282 @@
+use bevy::prelude::*;
+
+use crate::{
+    board::{Board, Player},
+    piece::{MoveOutcomeEvent, TurnChangedEvent},
+};
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum GameEndReason {
+    Checkmate,
+    Stalemate,
+    FiftyMove,
+    Repetition,
+    InsufficientMaterial,
+    Timeout,
+    Resignation,
+    Agreement,
+    // Only used when the computer plays itself, so that a game can't go on forever
+    MoveLimit,
+}
+
+#[derive(Event)]
+pub struct GameOverEvent {
+    pub winner: Option<Player>,
+    pub reason: GameEndReason,
+}
+
+// Moves can only be made while the game is being played
+#[derive(Resource, Default, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum GameState {
+    #[default]
+    Playing,
+    Over,
+}
+
+impl GameState {
+    pub fn is_over(self) -> bool {
+        self == GameState::Over
+    }
+}
+
+impl Board {
+    // The draw which could be claimed in this position, if any, since these don't end the game on their own
+    pub fn get_claimable_draw(&self) -> Option<GameEndReason> {
+        if self.is_threefold_repetition() {
+            Some(GameEndReason::Repetition)
+        } else if self.is_fifty_move_draw() {
+            Some(GameEndReason::FiftyMove)
+        } else {
+            None
+        }
+    }
+}
+
+// Check whether the game has ended for the player who is now to move
+pub fn game_over_checker(
+    mut ev_move_outcome: EventReader<MoveOutcomeEvent>,
+    mut board: ResMut<Board>,
+    mut ev_game_over: EventWriter<GameOverEvent>,
+) {
+    // Only check once a move has been made, not when a move was rejected
+    if ev_move_outcome.read().last().is_none() {
+        return;
+    }
+
+    let player = board.player;
+
+    if board.is_checkmate(player) {
+        ev_game_over.send(GameOverEvent {
+            winner: Some(board.get_next_player()),
+            reason: GameEndReason::Checkmate,
+        });
+    } else if board.is_stalemate(player) {
+        ev_game_over.send(GameOverEvent {
+            winner: None,
+            reason: GameEndReason::Stalemate,
+        });
+    } else if board.is_seventy_five_move_draw() {
+        ev_game_over.send(GameOverEvent {
+            winner: None,
+            reason: GameEndReason::FiftyMove,
+        });
+    } else if board.is_fivefold_repetition() {
+        ev_game_over.send(GameOverEvent {
+            winner: None,
+            reason: GameEndReason::Repetition,
+        });
+    } else if board.is_insufficient_material() {
+        ev_game_over.send(GameOverEvent {
+            winner: None,
+            reason: GameEndReason::InsufficientMaterial,
+        });
+    }
+}
+
+pub fn game_over_event_reader(mut ev_game_over: EventReader<GameOverEvent>) {
+    for ev in ev_game_over.read() {
+        match ev.winner {
+            Some(winner) => info!("Game over, {winner:?} wins by {:?}", ev.reason),
+            None => info!("Game over, drawn by {:?}", ev.reason),
+        }
+    }
+}
+
+// However the game ended, no more moves can be made until the position changes some other way (such as by undoing a move)
+pub fn game_state_updater(
+    mut ev_turn_changed: EventReader<TurnChangedEvent>,
+    mut ev_game_over: EventReader<GameOverEvent>,
+    mut game_state: ResMut<GameState>,
+) {
+    // The move which ended the game also changed the turn, so the game over is read last
+    if ev_turn_changed.read().last().is_some() {
+        *game_state = GameState::Playing;
+    }
+
+    if ev_game_over.read().last().is_some() {
+        *game_state = GameState::Over;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        board::TilePos,
+        headless::ChessCorePlugin,
+        piece::{MoveRejectedEvent, PieceMove, PieceMoveEvent},
+    };
+
+    fn core_app() -> App {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, ChessCorePlugin));
+
+        app
+    }
+
+    fn fen_app(fen: &str) -> App {
+        let mut app = core_app();
+        app.insert_resource(Board::from_fen(fen).unwrap());
+
+        app
+    }
+
+    fn game_over_results(app: &mut App) -> Vec<(Option<Player>, GameEndReason)> {
+        app.world
+            .resource_mut::<Events<GameOverEvent>>()
+            .drain()
+            .map(|ev| (ev.winner, ev.reason))
+            .collect()
+    }
+
+    fn send_move(app: &mut App, from: &str, to: &str) {
+        app.world.send_event(PieceMoveEvent {
+            piece_move: PieceMove::new(
+                TilePos::from_algebraic(from).unwrap(),
+                TilePos::from_algebraic(to).unwrap(),
+            ),
+            entity: Entity::PLACEHOLDER,
+        });
+        app.update();
+    }
+
+    #[test]
+    fn no_moves_after_game_over() {
+        let mut app = core_app();
+        app.world.send_event(GameOverEvent {
+            winner: Some(Player::Black),
+            reason: GameEndReason::Resignation,
+        });
+        app.update();
+        assert_eq!(*app.world.resource::<GameState>(), GameState::Over);
+
+        send_move(&mut app, "e2", "e4");
+
+        assert_eq!(app.world.resource::<Board>().get_ply(), 0);
+        assert_eq!(
+            app.world
+                .resource_mut::<Events<MoveRejectedEvent>>()
+                .drain()
+                .count(),
+            1
+        );
+    }
+
+    #[test]
+    fn undo_carries_on_playing() {
+        let mut app = core_app();
+        send_move(&mut app, "e2", "e4");
+        app.world.send_event(GameOverEvent {
+            winner: Some(Player::White),
+            reason: GameEndReason::Resignation,
+        });
+        app.update();
+        assert!(app.world.resource::<GameState>().is_over());
+
+        app.world.resource_mut::<Board>().undo_move();
+        app.world.send_event(TurnChangedEvent {
+            new_player: Player::White,
+        });
+        app.update();
+        assert_eq!(*app.world.resource::<GameState>(), GameState::Playing);
+
+        send_move(&mut app, "d2", "d4");
+        assert_eq!(app.world.resource::<Board>().get_ply(), 1);
+    }
+
+    #[test]
+    fn checkmate_ends_game() {
+        let mut app = core_app();
+        for (from, to) in [("f2", "f3"), ("e7", "e5"), ("g2", "g4"), ("d8", "h4")] {
+            send_move(&mut app, from, to);
+        }
+        app.update();
+
+        assert!(app.world.resource::<GameState>().is_over());
+        send_move(&mut app, "e1", "f2");
+        assert_eq!(app.world.resource::<Board>().get_ply(), 4);
+    }
+
+    #[test]
+    fn fools_mate() {
+        let mut app = core_app();
+        for (from, to) in [("f2", "f3"), ("e7", "e5"), ("g2", "g4")] {
+            send_move(&mut app, from, to);
+        }
+        assert!(game_over_results(&mut app).is_empty());
+
+        send_move(&mut app, "d8", "h4");
+        assert_eq!(
+            game_over_results(&mut app),
+            [(Some(Player::Black), GameEndReason::Checkmate)]
+        );
+    }
+
+    #[test]
+    fn back_rank_mate() {
+        let mut app = fen_app("6k1/5ppp/8/8/8/8/8/R5K1 w - - 0 1");
+        send_move(&mut app, "a1", "a8");
+
+        assert_eq!(
+            game_over_results(&mut app),
+            [(Some(Player::White), GameEndReason::Checkmate)]
+        );
+    }
+
+    #[test]
+    fn no_check_for_rejected_moves() {
+        // Black has already been mated, so the game over was sent when the mating move was made
+        let mut app = fen_app("R5k1/5ppp/8/8/8/8/8/6K1 b - - 1 1");
+        send_move(&mut app, "g8", "h8");
+        send_move(&mut app, "g7", "g7");
+
+        assert!(game_over_results(&mut app).is_empty());
+    }
+
+    #[test]
+    fn fifty_move_draw_is_claimable() {
+        let mut app = fen_app("4k3/8/8/8/8/8/8/R3K3 w - - 99 80");
+        assert_eq!(app.world.resource::<Board>().get_claimable_draw(), None);
+
+        send_move(&mut app, "a1", "a2");
+
+        // The draw can be claimed, but the game carries on until it is
+        assert_eq!(
+            app.world.resource::<Board>().get_claimable_draw(),
+            Some(GameEndReason::FiftyMove)
+        );
+        assert!(game_over_results(&mut app).is_empty());
+        assert_eq!(*app.world.resource::<GameState>(), GameState::Playing);
+    }
+
+    #[test]
+    fn seventy_five_moves_ends_game() {
+        let mut app = fen_app("4k3/8/8/8/8/8/8/R3K3 w - - 149 80");
+        send_move(&mut app, "a1", "a2");
+
+        assert_eq!(
+            game_over_results(&mut app),
+            [(None, GameEndReason::FiftyMove)]
+        );
+    }
+}
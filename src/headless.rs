@@ -0,0 +1,203 @@
+use std::path::Path;
+
+use bevy::prelude::*;
+
+use crate::{
+    board::{Board, Player},
+    game_over::{
+        game_over_checker, game_over_event_reader, game_state_updater, GameEndReason,
+        GameOverEvent, GameState,
+    },
+    opening_book::{OpeningBook, OPENING_BOOK_PATH},
+    piece::{
+        piece_move_event_reader, MoveOutcomeEvent, MoveRejectedEvent, PieceCapturedEvent,
+        PieceMoveEvent, TurnChangedEvent,
+    },
+};
+
+// The board and the rules of the game, without anything which needs a window
+pub struct ChessCorePlugin;
+
+impl Plugin for ChessCorePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Board>()
+            .init_resource::<GameState>()
+            .add_event::<PieceMoveEvent>()
+            .add_event::<PieceCapturedEvent>()
+            .add_event::<TurnChangedEvent>()
+            .add_event::<MoveOutcomeEvent>()
+            .add_event::<MoveRejectedEvent>()
+            .add_event::<GameOverEvent>()
+            .add_systems(
+                Update,
+                (
+                    piece_move_event_reader,
+                    game_over_checker.after(piece_move_event_reader),
+                    game_over_event_reader.after(game_over_checker),
+                    // Any move sent after the game ended is rejected, whichever system ended it
+                    game_state_updater.before(piece_move_event_reader),
+                ),
+            );
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct GameResult {
+    pub winner: Option<Player>,
+    pub reason: GameEndReason,
+    pub ply_count: usize,
+}
+
+// A game where the computer plays both sides
+#[derive(Resource)]
+struct SelfPlay {
+    depth: usize,
+    book: Option<OpeningBook>,
+    result: Option<GameResult>,
+}
+
+// Each frame plays at most one move, so this also stops a game where moves keep being rejected
+pub const MAX_SELFPLAY_PLY: usize = 500;
+
+fn selfplay_move(
+    mut selfplay: ResMut<SelfPlay>,
+    board: Res<Board>,
+    mut ev_piece_move: EventWriter<PieceMoveEvent>,
+) {
+    if selfplay.result.is_some() {
+        return;
+    }
+
+    let mut board = board.clone();
+    let Some(piece_move) = board.best_move(selfplay.depth, selfplay.book.as_ref()) else {
+        // No move can be made, so the game is over even if no move ended it (such as when starting from a finished position)
+        let player = board.player;
+        selfplay.result = Some(if board.is_checkmate(player) {
+            GameResult {
+                winner: Some(board.get_next_player()),
+                reason: GameEndReason::Checkmate,
+                ply_count: board.get_ply(),
+            }
+        } else {
+            GameResult {
+                winner: None,
+                reason: GameEndReason::Stalemate,
+                ply_count: board.get_ply(),
+            }
+        });
+        return;
+    };
+
+    ev_piece_move.send(PieceMoveEvent {
+        piece_move,
+        entity: Entity::PLACEHOLDER,
+    });
+}
+
+fn selfplay_game_over_reader(
+    mut ev_game_over: EventReader<GameOverEvent>,
+    board: Res<Board>,
+    mut selfplay: ResMut<SelfPlay>,
+) {
+    if let Some(ev) = ev_game_over.read().last() {
+        selfplay.result = Some(GameResult {
+            winner: ev.winner,
+            reason: ev.reason,
+            ply_count: board.get_ply(),
+        });
+    }
+}
+
+// Play games of the computer against itself without a window, with the opening book varying the games
+pub fn run_selfplay(depth: usize, games: usize) -> Vec<GameResult> {
+    let book = OpeningBook::load(Path::new(OPENING_BOOK_PATH)).ok();
+
+    (0..games)
+        .map(|_| play_selfplay_game(Board::default(), depth, book.clone(), MAX_SELFPLAY_PLY))
+        .collect()
+}
+
+fn play_selfplay_game(
+    board: Board,
+    depth: usize,
+    book: Option<OpeningBook>,
+    max_ply: usize,
+) -> GameResult {
+    let mut app = App::new();
+    app.add_plugins((MinimalPlugins, ChessCorePlugin))
+        .insert_resource(board)
+        .insert_resource(SelfPlay {
+            depth,
+            book,
+            result: None,
+        })
+        .add_systems(
+            Update,
+            (
+                selfplay_move.before(piece_move_event_reader),
+                selfplay_game_over_reader.after(game_over_checker),
+            ),
+        );
+
+    for _ in 0..max_ply {
+        app.update();
+
+        if let Some(result) = app.world.resource::<SelfPlay>().result {
+            return result;
+        }
+    }
+
+    GameResult {
+        winner: None,
+        reason: GameEndReason::MoveLimit,
+        ply_count: app.world.resource::<Board>().get_ply(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn selfplay_games_finish() {
+        let results = run_selfplay(1, 2);
+
+        assert_eq!(results.len(), 2);
+        for result in results {
+            assert!(result.ply_count <= MAX_SELFPLAY_PLY);
+        }
+    }
+
+    #[test]
+    fn selfplay_stops_at_move_limit() {
+        let result = play_selfplay_game(Board::default(), 1, None, 6);
+
+        assert_eq!(
+            result,
+            GameResult {
+                winner: None,
+                reason: GameEndReason::MoveLimit,
+                ply_count: 6,
+            }
+        );
+    }
+
+    #[test]
+    fn selfplay_ends_without_legal_moves() {
+        // Black is to move with no legal moves, before any move has been made
+        let stalemate = Board::from_fen("7k/5Q2/6K1/8/8/8/8/8 b - - 0 1").unwrap();
+        let checkmate = Board::from_fen("7k/6Q1/6K1/8/8/8/8/8 b - - 0 1").unwrap();
+
+        let result = play_selfplay_game(stalemate, 1, None, MAX_SELFPLAY_PLY);
+        assert_eq!(
+            (result.winner, result.reason),
+            (None, GameEndReason::Stalemate)
+        );
+
+        let result = play_selfplay_game(checkmate, 1, None, MAX_SELFPLAY_PLY);
+        assert_eq!(
+            (result.winner, result.reason),
+            (Some(Player::White), GameEndReason::Checkmate)
+        );
+    }
+}
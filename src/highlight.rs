@@ -0,0 +1,112 @@
+use bevy::prelude::*;
+
+use crate::{
+    board::{Board, TilePos},
+    display::get_square_colour,
+    piece::PieceMove,
+    theme::BoardTheme,
+};
+
+// Marks a board square which is tinted to show the last move
+#[derive(Component)]
+pub struct LastMoveHighlight;
+
+// Marks the board square of a king which is in check
+#[derive(Component)]
+pub struct CheckHighlight;
+
+// The squares which show the last move
+pub fn get_last_move_squares(piece_move: PieceMove) -> [TilePos; 2] {
+    [piece_move.from, piece_move.to]
+}
+
+// The square of the king belonging to the player to move, if it is in check
+pub fn get_check_square(board: &mut Board) -> Option<TilePos> {
+    let player = board.player;
+
+    if board.is_in_check(player) {
+        board.get_king_pos(player)
+    } else {
+        None
+    }
+}
+
+// Tint the squares of the last move, and the king's square when in check, so they stay correct after undo and redo
+pub fn highlight_squares(
+    mut commands: Commands,
+    board: Res<Board>,
+    theme: Res<BoardTheme>,
+    mut square_query: Query<(Entity, &TilePos, &mut Sprite)>,
+) {
+    if !board.is_changed() && !theme.is_changed() {
+        return;
+    }
+
+    let last_move_squares = board.get_last_move().map(get_last_move_squares);
+    let check_square = get_check_square(&mut board.clone());
+
+    for (entity, tile_pos, mut sprite) in square_query.iter_mut() {
+        let is_last_move = last_move_squares.is_some_and(|squares| squares.contains(tile_pos));
+        let is_check = check_square == Some(*tile_pos);
+
+        // Check takes priority, since the king may have just moved into it
+        sprite.color = if is_check {
+            theme.check
+        } else if is_last_move {
+            theme.highlight
+        } else {
+            get_square_colour(*tile_pos, &theme)
+        };
+
+        let mut square = commands.entity(entity);
+        if is_last_move {
+            square.insert(LastMoveHighlight);
+        } else {
+            square.remove::<LastMoveHighlight>();
+        }
+        if is_check {
+            square.insert(CheckHighlight);
+        } else {
+            square.remove::<CheckHighlight>();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pos(algebraic: &str) -> TilePos {
+        TilePos::from_algebraic(algebraic).unwrap()
+    }
+
+    #[test]
+    fn last_move_squares() {
+        let mut board = Board::default();
+        assert_eq!(board.get_last_move().map(get_last_move_squares), None);
+
+        board
+            .move_piece(PieceMove::new(pos("e2"), pos("e4")))
+            .unwrap();
+        assert_eq!(
+            board.get_last_move().map(get_last_move_squares),
+            Some([pos("e2"), pos("e4")])
+        );
+
+        // Undoing the move also removes its highlight
+        board.undo_move();
+        assert_eq!(board.get_last_move().map(get_last_move_squares), None);
+    }
+
+    #[test]
+    fn check_square() {
+        assert_eq!(get_check_square(&mut Board::default()), None);
+
+        // Only the king of the player to move is highlighted
+        let mut board = Board::from_fen("4k3/8/8/8/8/8/8/4K2r w - - 0 1").unwrap();
+        assert_eq!(get_check_square(&mut board), Some(pos("e1")));
+
+        let mut board = Board::from_fen("4k3/8/8/8/8/8/8/4K2r b - - 0 1").unwrap();
+        assert_eq!(get_check_square(&mut board), None);
+    }
+}
@@ -0,0 +1,203 @@
+use bevy::{
+    prelude::*,
+    sprite::{MaterialMesh2dBundle, Mesh2dHandle},
+    tasks::{block_on, futures_lite::future, AsyncComputeTaskPool, Task},
+};
+use bevy_mod_picking::prelude::*;
+
+use crate::{
+    ai::{AiPlayer, AiSettings},
+    board::Board,
+    display::{board_to_pixel_coords, BoardLayout},
+    opening_book::OpeningBook,
+    piece::{PieceMove, TurnChangedEvent},
+};
+
+pub const DEFAULT_HINT_DEPTH: usize = 3;
+
+const HINT_ARROW_COLOUR: Color = Color::rgba(0.1, 0.6, 0.2, 0.7);
+
+#[derive(Resource)]
+pub struct HintSettings {
+    pub depth: usize,
+}
+
+impl Default for HintSettings {
+    fn default() -> Self {
+        Self {
+            depth: DEFAULT_HINT_DEPTH,
+        }
+    }
+}
+
+// The suggested move, along with the search for it and the position that search started from
+#[derive(Resource, Default)]
+pub struct Hint {
+    pub piece_move: Option<PieceMove>,
+    task: Option<(u64, Task<Option<PieceMove>>)>,
+}
+
+// Part of the arrow showing the suggested move
+#[derive(Component)]
+pub struct HintArrow;
+
+// The arrow runs between the centres of the squares which the piece moves from and to
+pub fn get_arrow_endpoints(piece_move: PieceMove, layout: &BoardLayout) -> (Vec2, Vec2) {
+    let (start_x, start_y) =
+        board_to_pixel_coords(piece_move.from.file, piece_move.from.rank, layout);
+    let (end_x, end_y) = board_to_pixel_coords(piece_move.to.file, piece_move.to.rank, layout);
+
+    (Vec2::new(start_x, start_y), Vec2::new(end_x, end_y))
+}
+
+// Search for a move for the human player with H
+pub fn hint_input(
+    keys: Res<ButtonInput<KeyCode>>,
+    board: Res<Board>,
+    ai_player: Res<AiPlayer>,
+    ai_settings: Res<AiSettings>,
+    settings: Res<HintSettings>,
+    book: Res<OpeningBook>,
+    mut hint: ResMut<Hint>,
+) {
+    if !keys.just_pressed(KeyCode::KeyH) || hint.task.is_some() {
+        return;
+    }
+
+    // The computer is already thinking about its own move
+    if ai_settings.enabled && board.player == ai_player.0 {
+        return;
+    }
+
+    let mut search_board = board.clone();
+    let depth = settings.depth;
+    let book = book.clone();
+    let task = AsyncComputeTaskPool::get()
+        .spawn(async move { search_board.best_move(depth, Some(&book)) });
+
+    hint.task = Some((board.zobrist_hash(), task));
+}
+
+// Keep the suggested move once the search finishes, so long as the position hasn't changed since
+pub fn poll_hint_search(board: Res<Board>, mut hint: ResMut<Hint>) {
+    let Some((hash, task)) = &mut hint.task else {
+        return;
+    };
+
+    let Some(best_move) = block_on(future::poll_once(task)) else {
+        return;
+    };
+
+    if *hash == board.zobrist_hash() {
+        hint.piece_move = best_move;
+    }
+
+    hint.task = None;
+}
+
+// The hint only applies to the position it was asked for, so it is removed after a move, undo or redo
+pub fn hint_turn_changed_reader(
+    mut ev_turn_changed: EventReader<TurnChangedEvent>,
+    mut hint: ResMut<Hint>,
+) {
+    if ev_turn_changed.read().last().is_none() {
+        return;
+    }
+
+    if hint.piece_move.is_some() || hint.task.is_some() {
+        hint.piece_move = None;
+        hint.task = None;
+    }
+}
+
+pub fn display_hint_arrow(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    hint: Res<Hint>,
+    layout: Res<BoardLayout>,
+    arrow_query: Query<Entity, With<HintArrow>>,
+) {
+    if !hint.is_changed() && !layout.is_changed() {
+        return;
+    }
+
+    for arrow in arrow_query.iter() {
+        commands.entity(arrow).despawn();
+    }
+
+    let Some(piece_move) = hint.piece_move else {
+        return;
+    };
+
+    let (start, end) = get_arrow_endpoints(piece_move, &layout);
+    let direction = (end - start).normalize_or_zero();
+    let rotation = Quat::from_rotation_z(direction.y.atan2(direction.x));
+
+    let head_length = layout.piece_size * 0.4;
+    let head_width = layout.piece_size * 0.5;
+    let shaft_length = (start.distance(end) - head_length).max(0.);
+    let shaft_width = layout.piece_size * 0.15;
+
+    // Both shapes point along the x-axis, then are rotated to point along the move
+    let shaft = Rectangle::new(shaft_length, shaft_width);
+    let shaft_centre = start + direction * shaft_length / 2.;
+
+    let head = Triangle2d::new(
+        Vec2::new(head_length / 2., 0.),
+        Vec2::new(-head_length / 2., head_width / 2.),
+        Vec2::new(-head_length / 2., -head_width / 2.),
+    );
+    let head_centre = end - direction * head_length / 2.;
+
+    let material = materials.add(HINT_ARROW_COLOUR);
+
+    for (mesh, centre) in [
+        (meshes.add(shaft), shaft_centre),
+        (meshes.add(head), head_centre),
+    ] {
+        commands.spawn((
+            MaterialMesh2dBundle {
+                mesh: Mesh2dHandle(mesh),
+                material: material.clone(),
+                transform: Transform::from_xyz(centre.x, centre.y, 6.).with_rotation(rotation),
+                ..default()
+            },
+            Pickable::IGNORE,
+            HintArrow,
+        ));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{board::TilePos, display::BoardOrientation};
+
+    #[test]
+    fn arrow_endpoints() {
+        let piece_move = PieceMove::new(
+            TilePos::from_algebraic("e2").unwrap(),
+            TilePos::from_algebraic("e4").unwrap(),
+        );
+
+        for (orientation, dir) in [
+            (BoardOrientation::WhiteAtBottom, 1.),
+            (BoardOrientation::BlackAtBottom, -1.),
+        ] {
+            let layout = BoardLayout {
+                orientation,
+                ..default()
+            };
+            let (start, end) = get_arrow_endpoints(piece_move, &layout);
+
+            // The arrow starts on the pawn's square, and goes up the board two squares from White's side
+            let (x, y) = board_to_pixel_coords(piece_move.from.file, piece_move.from.rank, &layout);
+            assert_eq!(start, Vec2::new(x, y));
+            assert_eq!(
+                end - start,
+                Vec2::new(0., dir * 2. * layout.get_square_size())
+            );
+        }
+    }
+}
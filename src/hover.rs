@@ -0,0 +1,160 @@
+use bevy::{
+    prelude::*,
+    sprite::{MaterialMesh2dBundle, Mesh2dHandle},
+    window::PrimaryWindow,
+};
+use bevy_mod_picking::prelude::*;
+
+use crate::{
+    board::{Board, TilePos},
+    display::{board_to_pixel_coords, pixel_to_board_coords_checked, BoardLayout},
+};
+
+const HOVER_COLOUR: Color = Color::rgba(1., 1., 1., 0.2);
+
+// The board square under the cursor
+#[derive(Resource, Default)]
+pub struct HoveredSquare {
+    pub tile_pos: Option<TilePos>,
+}
+
+// A tint drawn over the hovered square, so the square's own colour is left for the last move and check highlights
+#[derive(Component)]
+pub struct HoverHighlight;
+
+// The square at this position in world coordinates, unless it is off the board
+pub fn get_hovered_square(cursor: Vec2, layout: &BoardLayout) -> Option<TilePos> {
+    pixel_to_board_coords_checked(cursor.x, cursor.y, layout)
+        .map(|(file, rank)| TilePos::new(file, rank))
+}
+
+pub fn track_hovered_square(
+    window_query: Query<&Window, With<PrimaryWindow>>,
+    camera_query: Query<(&Camera, &GlobalTransform)>,
+    board: Res<Board>,
+    layout: Res<BoardLayout>,
+    mut hovered_square: ResMut<HoveredSquare>,
+) {
+    let cursor = window_query
+        .get_single()
+        .ok()
+        .and_then(|window| window.cursor_position());
+    let Ok((camera, camera_transform)) = camera_query.get_single() else {
+        return;
+    };
+
+    let tile_pos = cursor
+        .and_then(|cursor| camera.viewport_to_world_2d(camera_transform, cursor))
+        .and_then(|cursor| get_hovered_square(cursor, &layout))
+        .filter(|&tile_pos| board.get_dimensions().contains(tile_pos));
+
+    // Only change the resource when the square changes, so the highlight isn't redrawn every frame
+    if hovered_square.tile_pos != tile_pos {
+        hovered_square.tile_pos = tile_pos;
+    }
+}
+
+pub fn display_hover_highlight(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    hovered_square: Res<HoveredSquare>,
+    layout: Res<BoardLayout>,
+    highlight_query: Query<Entity, With<HoverHighlight>>,
+) {
+    if !hovered_square.is_changed() && !layout.is_changed() {
+        return;
+    }
+
+    for highlight in highlight_query.iter() {
+        commands.entity(highlight).despawn();
+    }
+
+    let Some(tile_pos) = hovered_square.tile_pos else {
+        return;
+    };
+
+    let (x, y) = board_to_pixel_coords(tile_pos.file, tile_pos.rank, &layout);
+
+    // Drawn above the squares and the premove tint, but below the pieces
+    commands.spawn((
+        MaterialMesh2dBundle {
+            mesh: Mesh2dHandle(meshes.add(Rectangle::new(layout.piece_size, layout.piece_size))),
+            material: materials.add(HOVER_COLOUR),
+            transform: Transform::from_xyz(x, y, 0.3),
+            ..default()
+        },
+        Pickable::IGNORE,
+        HoverHighlight,
+    ));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::display::{BoardOrientation, BOARD_SIZE};
+
+    fn square(algebraic: &str) -> Option<TilePos> {
+        Some(TilePos::from_algebraic(algebraic).unwrap())
+    }
+
+    #[test]
+    fn square_centres() {
+        for orientation in [
+            BoardOrientation::WhiteAtBottom,
+            BoardOrientation::BlackAtBottom,
+        ] {
+            let layout = BoardLayout {
+                orientation,
+                ..default()
+            };
+
+            for file in 0..BOARD_SIZE {
+                for rank in 0..BOARD_SIZE {
+                    let (x, y) = board_to_pixel_coords(file, rank, &layout);
+
+                    assert_eq!(
+                        get_hovered_square(Vec2::new(x, y), &layout),
+                        Some(TilePos::new(file, rank))
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn board_edges() {
+        let layout = BoardLayout::default();
+        let inside = BOARD_SIZE as f32 / 2. * layout.get_square_size() - 0.01;
+
+        assert_eq!(
+            get_hovered_square(Vec2::new(inside, inside), &layout),
+            square("h8")
+        );
+        assert_eq!(
+            get_hovered_square(Vec2::new(-inside, -inside), &layout),
+            square("a1")
+        );
+        assert_eq!(
+            get_hovered_square(Vec2::new(-inside, inside), &layout),
+            square("a8")
+        );
+        assert_eq!(
+            get_hovered_square(Vec2::new(inside, -inside), &layout),
+            square("h1")
+        );
+    }
+
+    #[test]
+    fn off_board() {
+        let layout = BoardLayout::default();
+        let outside = BOARD_SIZE as f32 / 2. * layout.get_square_size() + 0.01;
+
+        assert_eq!(get_hovered_square(Vec2::new(outside, 0.), &layout), None);
+        assert_eq!(get_hovered_square(Vec2::new(0., -outside), &layout), None);
+        assert_eq!(
+            get_hovered_square(Vec2::new(-outside, outside), &layout),
+            None
+        );
+    }
+}
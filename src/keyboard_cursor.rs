@@ -0,0 +1,250 @@
+use bevy::{
+    prelude::*,
+    sprite::{MaterialMesh2dBundle, Mesh2dHandle},
+};
+use bevy_mod_picking::prelude::*;
+
+use crate::{
+    board::{Board, TilePos},
+    dimensions::BoardDimensions,
+    display::{board_to_pixel_coords, BoardLayout, BoardOrientation},
+    editor::EditorMode,
+    piece::PieceMoveEvent,
+    premove::Premove,
+    promotion::PendingPromotion,
+    selection::{click_tile, SelectedPiece},
+};
+
+const CURSOR_COLOUR: Color = Color::rgba(0.2, 0.5, 1., 0.45);
+// How much of the square is left uncovered in the middle of the cursor's outline
+const CURSOR_INNER_SIZE: f32 = 0.85;
+
+// The square chosen with the arrow keys, which is hidden until an arrow key is first pressed
+#[derive(Resource, Default)]
+pub struct KeyboardCursor {
+    pub tile_pos: Option<TilePos>,
+}
+
+impl KeyboardCursor {
+    // Move one square in this direction on screen, with (0, 1) being up, staying put at the edge of the board
+    pub fn step(
+        &mut self,
+        (right, up): (isize, isize),
+        orientation: BoardOrientation,
+        dimensions: BoardDimensions,
+        start: TilePos,
+    ) {
+        let Some(tile_pos) = self.tile_pos else {
+            // The first press only shows the cursor
+            self.tile_pos = Some(start);
+            return;
+        };
+
+        // Files are counted down from the top of the board when White is at the bottom, and ranks from the left
+        let (d_file, d_rank) = match orientation {
+            BoardOrientation::WhiteAtBottom => (-up, right),
+            BoardOrientation::BlackAtBottom => (up, -right),
+        };
+
+        if let Some(next) = tile_pos
+            .offset(d_file, d_rank)
+            .filter(|&next| dimensions.contains(next))
+        {
+            self.tile_pos = Some(next);
+        }
+    }
+}
+
+// Arrow keys move the cursor, Enter picks up or puts down a piece like clicking, and Escape puts the piece back down
+#[allow(clippy::too_many_arguments)]
+pub fn keyboard_cursor_input(
+    keys: Res<ButtonInput<KeyCode>>,
+    board: Res<Board>,
+    layout: Res<BoardLayout>,
+    editor_mode: Res<EditorMode>,
+    mut cursor: ResMut<KeyboardCursor>,
+    mut selected_piece: ResMut<SelectedPiece>,
+    mut pending_promotion: ResMut<PendingPromotion>,
+    mut premove: ResMut<Premove>,
+    mut transform_query: Query<&mut Transform>,
+    mut ev_piece_move: EventWriter<PieceMoveEvent>,
+) {
+    if editor_mode.enabled || pending_promotion.promotion.is_some() {
+        return;
+    }
+
+    // The cursor first appears on the king of the player to move
+    let start = board
+        .get_king_pos(board.player)
+        .unwrap_or(TilePos::new(0, 0));
+
+    for (key, direction) in [
+        (KeyCode::ArrowUp, (0, 1)),
+        (KeyCode::ArrowDown, (0, -1)),
+        (KeyCode::ArrowLeft, (-1, 0)),
+        (KeyCode::ArrowRight, (1, 0)),
+    ] {
+        if keys.just_pressed(key) {
+            cursor.step(direction, layout.orientation, board.get_dimensions(), start);
+        }
+    }
+
+    if keys.just_pressed(KeyCode::Escape) && selected_piece.selected.is_some() {
+        selected_piece.selected = None;
+    }
+
+    let Some(tile_pos) = cursor.tile_pos else {
+        return;
+    };
+
+    if keys.just_pressed(KeyCode::Enter) && selected_piece.dragged.is_none() {
+        click_tile(
+            tile_pos,
+            &board,
+            &mut selected_piece,
+            &mut pending_promotion,
+            &mut premove,
+            &mut transform_query,
+            &mut ev_piece_move,
+            &layout,
+        );
+    }
+}
+
+// An outline around the cursor's square, leaving the square and any highlight on it visible
+#[derive(Component)]
+pub struct CursorHighlight;
+
+pub fn display_keyboard_cursor(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    cursor: Res<KeyboardCursor>,
+    layout: Res<BoardLayout>,
+    highlight_query: Query<Entity, With<CursorHighlight>>,
+) {
+    if !cursor.is_changed() && !layout.is_changed() {
+        return;
+    }
+
+    for highlight in highlight_query.iter() {
+        commands.entity(highlight).despawn();
+    }
+
+    let Some(tile_pos) = cursor.tile_pos else {
+        return;
+    };
+
+    let (x, y) = board_to_pixel_coords(tile_pos.file, tile_pos.rank, &layout);
+    let size = layout.piece_size;
+    let border = size * (1. - CURSOR_INNER_SIZE) / 2.;
+    let material = materials.add(CURSOR_COLOUR);
+
+    // Drawn as four bars above the hover tint, but below the pieces
+    for (width, height, offset) in [
+        (size, border, Vec2::new(0., (size - border) / 2.)),
+        (size, border, Vec2::new(0., -(size - border) / 2.)),
+        (
+            border,
+            size - 2. * border,
+            Vec2::new((size - border) / 2., 0.),
+        ),
+        (
+            border,
+            size - 2. * border,
+            Vec2::new(-(size - border) / 2., 0.),
+        ),
+    ] {
+        commands.spawn((
+            MaterialMesh2dBundle {
+                mesh: Mesh2dHandle(meshes.add(Rectangle::new(width, height))),
+                material: material.clone(),
+                transform: Transform::from_xyz(x + offset.x, y + offset.y, 0.4),
+                ..default()
+            },
+            Pickable::IGNORE,
+            CursorHighlight,
+        ));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const UP: (isize, isize) = (0, 1);
+    const RIGHT: (isize, isize) = (1, 0);
+
+    fn square(algebraic: &str) -> TilePos {
+        TilePos::from_algebraic(algebraic).unwrap()
+    }
+
+    fn step_from(
+        start: &str,
+        direction: (isize, isize),
+        orientation: BoardOrientation,
+    ) -> Option<TilePos> {
+        let mut cursor = KeyboardCursor {
+            tile_pos: Some(square(start)),
+        };
+        cursor.step(
+            direction,
+            orientation,
+            BoardDimensions::default(),
+            square("a1"),
+        );
+
+        cursor.tile_pos
+    }
+
+    #[test]
+    fn first_step_shows_cursor() {
+        let mut cursor = KeyboardCursor::default();
+        cursor.step(
+            UP,
+            BoardOrientation::WhiteAtBottom,
+            BoardDimensions::default(),
+            square("e2"),
+        );
+
+        assert_eq!(cursor.tile_pos, Some(square("e2")));
+    }
+
+    #[test]
+    fn steps_follow_the_screen() {
+        let white = BoardOrientation::WhiteAtBottom;
+        let black = BoardOrientation::BlackAtBottom;
+
+        assert_eq!(step_from("e4", UP, white), Some(square("e5")));
+        assert_eq!(step_from("e4", RIGHT, white), Some(square("f4")));
+        assert_eq!(step_from("e4", (-1, -1), white), Some(square("d3")));
+
+        // The board is upside down when Black is at the bottom
+        assert_eq!(step_from("e4", UP, black), Some(square("e3")));
+        assert_eq!(step_from("e4", RIGHT, black), Some(square("d4")));
+    }
+
+    #[test]
+    fn stays_on_board() {
+        let white = BoardOrientation::WhiteAtBottom;
+        let black = BoardOrientation::BlackAtBottom;
+
+        assert_eq!(step_from("h8", UP, white), Some(square("h8")));
+        assert_eq!(step_from("h8", RIGHT, white), Some(square("h8")));
+        assert_eq!(step_from("a1", (-1, -1), white), Some(square("a1")));
+        assert_eq!(step_from("a1", UP, black), Some(square("a1")));
+        assert_eq!(step_from("a1", RIGHT, black), Some(square("a1")));
+
+        // A smaller board has its edges further in
+        let mut cursor = KeyboardCursor {
+            tile_pos: Some(TilePos::new(0, 4)),
+        };
+        cursor.step(
+            RIGHT,
+            white,
+            BoardDimensions::try_new(5, 5).unwrap(),
+            square("a1"),
+        );
+        assert_eq!(cursor.tile_pos, Some(TilePos::new(0, 4)));
+    }
+}
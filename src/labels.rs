@@ -0,0 +1,135 @@
+use bevy::prelude::*;
+
+use crate::{
+    board::TilePos,
+    display::{board_to_pixel_coords, BoardLayout, BoardOrientation, BOARD_SIZE, PIECE_SIZE},
+};
+
+const LABEL_FONT_SIZE: f32 = 30.;
+const LABEL_MARGIN: f32 = 15.;
+const LABEL_COLOUR: Color = Color::rgb(0.3, 0.3, 0.3);
+
+#[derive(Resource)]
+pub struct LabelSettings {
+    pub enabled: bool,
+}
+
+impl Default for LabelSettings {
+    fn default() -> Self {
+        Self { enabled: true }
+    }
+}
+
+// A file or rank label drawn on the edge of the board
+#[derive(Component)]
+pub struct CoordinateLabel;
+
+// The file letters along the bottom edge and the rank numbers along the left edge, drawn in the corners of the edge squares
+pub fn get_label_positions(layout: &BoardLayout) -> Vec<(char, (f32, f32))> {
+    // The row of the board which is at the bottom, and the column which is on the left
+    let (bottom_file, left_rank) = match layout.orientation {
+        BoardOrientation::WhiteAtBottom => (BOARD_SIZE - 1, 0),
+        BoardOrientation::BlackAtBottom => (0, BOARD_SIZE - 1),
+    };
+    let offset = (layout.piece_size / 2.) - LABEL_MARGIN * get_label_scale(layout);
+
+    let file_labels = (0..BOARD_SIZE).map(|rank| {
+        let (x, y) = board_to_pixel_coords(bottom_file, rank, layout);
+        let algebraic = TilePos::new(bottom_file, rank).to_algebraic();
+
+        (algebraic.chars().next().unwrap(), (x + offset, y - offset))
+    });
+
+    let rank_labels = (0..BOARD_SIZE).map(|file| {
+        let (x, y) = board_to_pixel_coords(file, left_rank, layout);
+        let algebraic = TilePos::new(file, left_rank).to_algebraic();
+
+        (algebraic.chars().nth(1).unwrap(), (x - offset, y + offset))
+    });
+
+    file_labels.chain(rank_labels).collect()
+}
+
+// Labels shrink and grow with the board
+fn get_label_scale(layout: &BoardLayout) -> f32 {
+    layout.piece_size / PIECE_SIZE
+}
+
+// Show or hide the coordinate labels with L
+pub fn label_toggle_input(keys: Res<ButtonInput<KeyCode>>, mut settings: ResMut<LabelSettings>) {
+    if keys.just_pressed(KeyCode::KeyL) {
+        settings.enabled = !settings.enabled;
+    }
+}
+
+// Respawn the labels whenever they are toggled or the board is flipped
+pub fn display_labels(
+    mut commands: Commands,
+    settings: Res<LabelSettings>,
+    layout: Res<BoardLayout>,
+    label_query: Query<Entity, With<CoordinateLabel>>,
+) {
+    if !settings.is_changed() && !layout.is_changed() {
+        return;
+    }
+
+    for entity in label_query.iter() {
+        commands.entity(entity).despawn();
+    }
+
+    if !settings.enabled {
+        return;
+    }
+
+    for (label, (x, y)) in get_label_positions(&layout) {
+        commands.spawn((
+            Text2dBundle {
+                text: Text::from_section(
+                    label,
+                    TextStyle {
+                        font_size: LABEL_FONT_SIZE * get_label_scale(&layout),
+                        color: LABEL_COLOUR,
+                        ..default()
+                    },
+                ),
+                transform: Transform::from_xyz(x, y, 0.5),
+                ..default()
+            },
+            CoordinateLabel,
+        ));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn corner_labels() {
+        for (orientation, files, ranks) in [
+            (BoardOrientation::WhiteAtBottom, "abcdefgh", "12345678"),
+            (BoardOrientation::BlackAtBottom, "hgfedcba", "87654321"),
+        ] {
+            let layout = BoardLayout {
+                orientation,
+                ..default()
+            };
+            let (mut file_labels, mut rank_labels) = get_label_positions(&layout)
+                .into_iter()
+                .partition::<Vec<_>, _>(|(label, _)| label.is_ascii_alphabetic());
+
+            // File letters go left to right along the bottom, and rank numbers go up the left
+            file_labels.sort_by(|(_, (x1, _)), (_, (x2, _))| x1.total_cmp(x2));
+            rank_labels.sort_by(|(_, (_, y1)), (_, (_, y2))| y1.total_cmp(y2));
+
+            let labels = |labels: &[(char, (f32, f32))]| {
+                labels.iter().map(|(label, _)| label).collect::<String>()
+            };
+            assert_eq!(labels(&file_labels), files, "{orientation:?}");
+            assert_eq!(labels(&rank_labels), ranks, "{orientation:?}");
+
+            assert!(file_labels.iter().all(|(_, (_, y))| *y < 0.));
+            assert!(rank_labels.iter().all(|(_, (x, _))| *x < 0.));
+        }
+    }
+}
@@ -0,0 +1,47 @@
+pub mod ai;
+pub mod analysis;
+pub mod animation;
+pub mod ascii;
+pub mod attack_tables;
+pub mod bitboard;
+pub mod board;
+pub mod captured_tray;
+pub mod chess960;
+pub mod cli;
+pub mod clock;
+pub mod dimensions;
+pub mod display;
+pub mod editor;
+pub mod evaluate;
+pub mod export;
+pub mod fen_input;
+pub mod game_over;
+pub mod headless;
+pub mod highlight;
+pub mod hint;
+pub mod hover;
+pub mod keyboard_cursor;
+pub mod labels;
+pub mod material;
+pub mod move_history;
+pub mod move_list;
+pub mod move_markers;
+pub mod opening_book;
+pub mod perft;
+pub mod pgn;
+pub mod piece;
+pub mod possible_moves;
+pub mod premove;
+pub mod promotion;
+pub mod resignation;
+pub mod san;
+pub mod save;
+pub mod search;
+pub mod selection;
+pub mod sound;
+pub mod theme;
+pub mod transposition;
+pub mod uci;
+pub mod undo_redo;
+pub mod validate;
+pub mod zobrist;
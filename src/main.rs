@@ -1,15 +1,82 @@
 use bevy::prelude::*;
 use bevy_mod_picking::prelude::*;
-use piece::{piece_move_event_reader, PieceMoveEvent};
 
-pub mod bitboard;
-pub mod board;
-pub mod display;
-pub mod piece;
-
-use crate::{board::Board, display::display_board};
+use chess_bevy_refactor::{
+    ai::{ChessAiPlugin, DEFAULT_AI_DEPTH},
+    analysis::AnalysisPlugin,
+    animation::{animate_moves, AnimationSettings},
+    captured_tray::{captured_pieces_event_reader, display_captured_pieces, CapturedPieces},
+    cli::{get_initial_board, get_selfplay_arg},
+    clock::{
+        chess_clock_game_over_reader, chess_clock_increment, chess_clock_ticker, spawn_clock_text,
+        update_clock_text, ChessClock,
+    },
+    display::{
+        display_board, flip_board_input, reposition_board, window_resize_event_reader, BoardLayout,
+    },
+    editor::{display_editor, editor_click, editor_setup_input, editor_toggle_input, EditorMode},
+    export::{export_image_event_reader, export_image_input, BoardExportPlugin, ExportImageEvent},
+    fen_input::{
+        fen_input_closed, fen_input_submit, fen_input_toggle, fen_input_typing, spawn_fen_input,
+        update_fen_input_text, FenInput,
+    },
+    game_over::game_over_checker,
+    headless::{run_selfplay, ChessCorePlugin},
+    highlight::highlight_squares,
+    hint::{
+        display_hint_arrow, hint_input, hint_turn_changed_reader, poll_hint_search, Hint,
+        HintSettings,
+    },
+    hover::{display_hover_highlight, track_hovered_square, HoveredSquare},
+    keyboard_cursor::{display_keyboard_cursor, keyboard_cursor_input, KeyboardCursor},
+    labels::{display_labels, label_toggle_input, LabelSettings},
+    material::{spawn_material_text, update_material_text},
+    move_list::{ply_button_click, scroll_move_list, spawn_move_list, update_move_list},
+    move_markers::display_selected_moves,
+    piece::{
+        analysis_mode_input, move_piece_sprites, piece_captured_event_reader,
+        piece_move_event_reader, AnalysisMode,
+    },
+    premove::{
+        cancel_premove_input, display_premove, play_premove, update_premove_player, Premove,
+    },
+    promotion::{display_promotion_choices, promotion_choice_click, PendingPromotion},
+    resignation::{
+        agreement_button_click, agreement_game_over_reader, agreement_turn_changed_reader,
+        draw_claim_event_reader, draw_offer_event_reader, draw_response_event_reader,
+        resign_event_reader, spawn_agreement_buttons, update_agreement_buttons, AgreementState,
+        DrawClaimEvent, DrawOfferEvent, DrawResponseEvent, ResignEvent,
+    },
+    save::{load_game_input, save_game_input},
+    selection::SelectedPiece,
+    sound::{mute_input, play_move_sounds, setup_sounds, SoundSettings},
+    theme::{board_theme_event_reader, theme_cycle_input, BoardTheme, BoardThemeEvent},
+    uci::UciEnginePlugin,
+    undo_redo::{undo_redo_event_reader, undo_redo_input, UndoRedoEvent},
+};
 
 fn main() {
+    if let Some(games) = get_selfplay_arg(std::env::args().skip(1)) {
+        for (i, result) in run_selfplay(DEFAULT_AI_DEPTH, games).iter().enumerate() {
+            match result.winner {
+                Some(winner) => println!(
+                    "Game {}: {winner:?} wins by {:?} after {} plies",
+                    i + 1,
+                    result.reason,
+                    result.ply_count
+                ),
+                None => println!(
+                    "Game {}: drawn by {:?} after {} plies",
+                    i + 1,
+                    result.reason,
+                    result.ply_count
+                ),
+            }
+        }
+
+        return;
+    }
+
     App::new()
         .add_plugins((
             DefaultPlugins
@@ -18,19 +85,201 @@ fn main() {
                     primary_window: Some(Window {
                         title: "Chez.cum".into(),
                         resolution: (1920., 1280.).into(),
-                        resizable: false,
                         ..default()
                     }),
                     ..default()
                 })
                 .build(),
             DefaultPickingPlugins,
+            ChessCorePlugin,
+            ChessAiPlugin,
+            UciEnginePlugin,
+            AnalysisPlugin,
+            BoardExportPlugin,
         ))
         // .insert_resource(bevy_mod_picking::debug::DebugPickingMode::Normal)
-        .init_resource::<Board>()
-        .add_systems(Startup, (setup, display_board))
-        .add_systems(Update, piece_move_event_reader)
-        .add_event::<PieceMoveEvent>()
+        .insert_resource(get_initial_board(std::env::args().skip(1)))
+        .init_resource::<PendingPromotion>()
+        .init_resource::<SelectedPiece>()
+        .init_resource::<BoardLayout>()
+        .init_resource::<LabelSettings>()
+        .init_resource::<BoardTheme>()
+        .init_resource::<CapturedPieces>()
+        .init_resource::<ChessClock>()
+        .init_resource::<HintSettings>()
+        .init_resource::<Hint>()
+        .init_resource::<Premove>()
+        .init_resource::<HoveredSquare>()
+        .init_resource::<AnalysisMode>()
+        .init_resource::<EditorMode>()
+        .init_resource::<SoundSettings>()
+        .init_resource::<AnimationSettings>()
+        .init_resource::<AgreementState>()
+        .init_resource::<FenInput>()
+        .init_resource::<KeyboardCursor>()
+        .add_systems(
+            Startup,
+            (
+                setup,
+                display_board,
+                spawn_material_text,
+                spawn_move_list,
+                spawn_clock_text,
+                setup_sounds,
+                spawn_agreement_buttons,
+                spawn_fen_input,
+            ),
+        )
+        .add_systems(
+            Update,
+            (
+                move_piece_sprites.after(piece_move_event_reader),
+                piece_captured_event_reader.after(piece_move_event_reader),
+                promotion_choice_click,
+                display_selected_moves,
+                highlight_squares
+                    .after(piece_move_event_reader)
+                    .after(board_theme_event_reader),
+                display_promotion_choices,
+                undo_redo_input.run_if(fen_input_closed),
+                undo_redo_event_reader.after(undo_redo_input),
+                flip_board_input.run_if(fen_input_closed),
+                window_resize_event_reader,
+                reposition_board
+                    .after(flip_board_input)
+                    .after(window_resize_event_reader),
+                label_toggle_input.run_if(fen_input_closed),
+                display_labels
+                    .after(flip_board_input)
+                    .after(window_resize_event_reader)
+                    .after(label_toggle_input),
+                theme_cycle_input.run_if(fen_input_closed),
+                board_theme_event_reader.after(theme_cycle_input),
+            ),
+        )
+        .add_systems(
+            Update,
+            (
+                captured_pieces_event_reader
+                    .after(piece_move_event_reader)
+                    .after(undo_redo_input),
+                display_captured_pieces
+                    .after(captured_pieces_event_reader)
+                    .after(window_resize_event_reader)
+                    .after(flip_board_input),
+                update_material_text
+                    .after(piece_move_event_reader)
+                    .after(undo_redo_input),
+                ply_button_click,
+                update_move_list
+                    .after(piece_move_event_reader)
+                    .after(undo_redo_input)
+                    .after(ply_button_click),
+                scroll_move_list,
+                chess_clock_increment.after(piece_move_event_reader),
+                chess_clock_ticker.after(chess_clock_increment),
+                chess_clock_game_over_reader.after(game_over_checker),
+                update_clock_text.after(chess_clock_ticker),
+                save_game_input.run_if(fen_input_closed),
+                load_game_input.run_if(fen_input_closed),
+            ),
+        )
+        .add_systems(
+            Update,
+            (
+                hint_input.run_if(fen_input_closed),
+                poll_hint_search.after(hint_input),
+                hint_turn_changed_reader
+                    .after(piece_move_event_reader)
+                    .after(undo_redo_event_reader)
+                    .after(load_game_input)
+                    .after(poll_hint_search),
+                display_hint_arrow
+                    .after(hint_turn_changed_reader)
+                    .after(window_resize_event_reader)
+                    .after(flip_board_input),
+                update_premove_player,
+                play_premove
+                    .after(update_premove_player)
+                    .after(piece_move_event_reader)
+                    .after(undo_redo_input)
+                    .after(ply_button_click),
+                cancel_premove_input,
+                display_premove
+                    .after(play_premove)
+                    .after(cancel_premove_input)
+                    .after(window_resize_event_reader)
+                    .after(flip_board_input),
+                track_hovered_square
+                    .after(window_resize_event_reader)
+                    .after(flip_board_input),
+                display_hover_highlight.after(track_hovered_square),
+                analysis_mode_input.run_if(fen_input_closed),
+                editor_toggle_input.run_if(fen_input_closed),
+                editor_setup_input
+                    .run_if(fen_input_closed)
+                    .after(editor_toggle_input),
+                editor_click.after(editor_toggle_input),
+                display_editor
+                    .after(editor_setup_input)
+                    .after(editor_click)
+                    .after(window_resize_event_reader)
+                    .after(flip_board_input),
+                mute_input.run_if(fen_input_closed),
+                play_move_sounds
+                    .after(piece_move_event_reader)
+                    .after(game_over_checker)
+                    .after(mute_input),
+                animate_moves
+                    .after(piece_move_event_reader)
+                    .after(undo_redo_event_reader)
+                    .after(reposition_board)
+                    .after(move_piece_sprites),
+            ),
+        )
+        .add_systems(
+            Update,
+            (
+                agreement_button_click,
+                resign_event_reader.after(agreement_button_click),
+                draw_offer_event_reader.after(agreement_button_click),
+                draw_response_event_reader
+                    .after(agreement_button_click)
+                    .after(draw_offer_event_reader),
+                draw_claim_event_reader.after(agreement_button_click),
+                agreement_game_over_reader
+                    .after(game_over_checker)
+                    .after(draw_claim_event_reader)
+                    .after(chess_clock_ticker)
+                    .after(resign_event_reader)
+                    .after(draw_response_event_reader),
+                agreement_turn_changed_reader
+                    .after(piece_move_event_reader)
+                    .after(undo_redo_event_reader)
+                    .after(draw_offer_event_reader),
+                update_agreement_buttons
+                    .after(agreement_game_over_reader)
+                    .after(agreement_turn_changed_reader),
+                fen_input_toggle,
+                fen_input_typing.after(fen_input_toggle),
+                fen_input_submit.after(fen_input_typing),
+                update_fen_input_text.after(fen_input_submit),
+                keyboard_cursor_input.run_if(fen_input_closed),
+                display_keyboard_cursor
+                    .after(keyboard_cursor_input)
+                    .after(window_resize_event_reader)
+                    .after(flip_board_input),
+                export_image_input.run_if(fen_input_closed),
+                export_image_event_reader.after(export_image_input),
+            ),
+        )
+        .add_event::<ResignEvent>()
+        .add_event::<DrawOfferEvent>()
+        .add_event::<DrawResponseEvent>()
+        .add_event::<DrawClaimEvent>()
+        .add_event::<UndoRedoEvent>()
+        .add_event::<BoardThemeEvent>()
+        .add_event::<ExportImageEvent>()
         .run();
 }
 
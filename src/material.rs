@@ -0,0 +1,53 @@
+use bevy::prelude::*;
+
+use crate::{board::Board, piece::TurnChangedEvent};
+
+const MATERIAL_FONT_SIZE: f32 = 40.;
+const MATERIAL_MARGIN: f32 = 20.;
+
+// The text showing which side is ahead on material
+#[derive(Component)]
+pub struct MaterialText;
+
+pub fn get_material_text(balance: i32) -> String {
+    match balance {
+        0 => "Even".to_string(),
+        1.. => format!("White +{balance}"),
+        _ => format!("Black +{}", -balance),
+    }
+}
+
+pub fn spawn_material_text(mut commands: Commands, board: Res<Board>) {
+    commands.spawn((
+        TextBundle::from_section(
+            get_material_text(board.material_balance()),
+            TextStyle {
+                font_size: MATERIAL_FONT_SIZE,
+                color: Color::WHITE,
+                ..default()
+            },
+        )
+        .with_style(Style {
+            position_type: PositionType::Absolute,
+            top: Val::Px(MATERIAL_MARGIN),
+            left: Val::Px(MATERIAL_MARGIN),
+            ..default()
+        }),
+        MaterialText,
+    ));
+}
+
+// Recount the material after every move, undo and redo
+pub fn update_material_text(
+    mut ev_turn_changed: EventReader<TurnChangedEvent>,
+    board: Res<Board>,
+    mut text_query: Query<&mut Text, With<MaterialText>>,
+) {
+    if ev_turn_changed.read().last().is_none() {
+        return;
+    }
+
+    for mut text in text_query.iter_mut() {
+        text.sections[0].value = get_material_text(board.material_balance());
+    }
+}
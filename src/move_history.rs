@@ -0,0 +1,236 @@
+use crate::{
+    board::TilePos,
+    piece::{Piece, PieceMove},
+};
+
+// Everything needed to take back a move, alongside the hash of the position it led to
+#[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PieceMoveRecord {
+    pub piece_move: PieceMove,
+    pub piece: Piece,
+    pub captured: Option<(Piece, TilePos)>,
+    // Where the king and rook went, if this move was castling
+    pub castling: Option<(PieceMove, PieceMove)>,
+    pub castling_rights: [(bool, bool); 2],
+    pub en_passant_on_last_move: Option<TilePos>,
+    pub half_move_counter: usize,
+    pub full_move_counter: usize,
+    pub hash: u64,
+}
+
+// A move which has been played, without the state needed to take it back
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct PlayedMove {
+    pub piece_move: PieceMove,
+    pub piece: Piece,
+    pub captured: Option<(Piece, TilePos)>,
+}
+
+impl From<PieceMoveRecord> for PlayedMove {
+    fn from(record: PieceMoveRecord) -> Self {
+        Self {
+            piece_move: record.piece_move,
+            piece: record.piece,
+            captured: record.captured,
+        }
+    }
+}
+
+// The moves which have been played, alongside the hash of every position reached
+// Moves after the cursor have been undone, and can be redone
+#[derive(Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PieceMoveHistory {
+    starting_hash: u64,
+    records: Vec<PieceMoveRecord>,
+    cursor: usize,
+}
+
+impl PieceMoveHistory {
+    pub fn new(starting_hash: u64) -> Self {
+        Self {
+            starting_hash,
+            records: Vec::new(),
+            cursor: 0,
+        }
+    }
+
+    pub fn push(&mut self, record: PieceMoveRecord) {
+        // Replaying the next undone move keeps the rest of the redo history, any other move discards it
+        if self.records.get(self.cursor).map(|next| next.piece_move) == Some(record.piece_move) {
+            self.records[self.cursor] = record;
+        } else {
+            self.records.truncate(self.cursor);
+            self.records.push(record);
+        }
+
+        self.cursor += 1;
+    }
+
+    // How many moves have been played, not counting those which were undone
+    pub fn get_ply(&self) -> usize {
+        self.cursor
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cursor == 0
+    }
+
+    // The moves which have been played, from the first to the most recent, leaving out those which were undone
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = PlayedMove> + '_ {
+        self.records[..self.cursor]
+            .iter()
+            .map(|&record| PlayedMove::from(record))
+    }
+
+    // The move played at this ply, counting from 0 for the first move
+    pub fn get(&self, ply: usize) -> Option<PlayedMove> {
+        self.records[..self.cursor]
+            .get(ply)
+            .map(|&record| PlayedMove::from(record))
+    }
+
+    pub fn last(&self) -> Option<PlayedMove> {
+        self.get_last_record().map(PlayedMove::from)
+    }
+
+    pub fn undo(&mut self) -> Option<PieceMoveRecord> {
+        self.cursor = self.cursor.checked_sub(1)?;
+
+        Some(self.records[self.cursor])
+    }
+
+    // The most recent move which hasn't been undone
+    pub fn get_last_move(&self) -> Option<PieceMove> {
+        self.get_last_record().map(|record| record.piece_move)
+    }
+
+    pub fn get_last_record(&self) -> Option<PieceMoveRecord> {
+        self.cursor.checked_sub(1).map(|index| self.records[index])
+    }
+
+    // The move which would be replayed by a redo
+    pub fn get_redo_move(&self) -> Option<PieceMove> {
+        self.get_redo_record().map(|record| record.piece_move)
+    }
+
+    pub fn get_redo_record(&self) -> Option<PieceMoveRecord> {
+        self.records.get(self.cursor).copied()
+    }
+
+    // How many times this position has been reached
+    pub fn count_position(&self, hash: u64) -> usize {
+        (self.starting_hash == hash) as usize
+            + self.records[..self.cursor]
+                .iter()
+                .filter(|record| record.hash == hash)
+                .count()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::Board;
+
+    fn piece_move(from: &str, to: &str) -> PieceMove {
+        PieceMove::new(
+            TilePos::from_algebraic(from).unwrap(),
+            TilePos::from_algebraic(to).unwrap(),
+        )
+    }
+
+    #[test]
+    fn three_move_history() {
+        let mut board = Board::default();
+        assert!(board.get_move_history().is_empty());
+
+        let moves = [
+            piece_move("e2", "e4"),
+            piece_move("d7", "d5"),
+            piece_move("e4", "d5"),
+        ];
+        for piece_move in moves {
+            board.move_piece(piece_move).unwrap();
+        }
+
+        let history = board.get_move_history();
+        assert_eq!(history.get_ply(), 3);
+        assert_eq!(
+            history
+                .iter()
+                .map(|played| played.piece_move)
+                .collect::<Vec<_>>(),
+            moves
+        );
+        assert_eq!(
+            history.get(1).map(|played| played.piece),
+            Some(Piece::BPawn)
+        );
+        assert_eq!(history.get(3), None);
+
+        let last = history.last().unwrap();
+        assert_eq!(last.piece_move, moves[2]);
+        assert_eq!(last.captured, Some((Piece::BPawn, moves[2].to)));
+    }
+
+    #[test]
+    fn undone_moves_are_left_out() {
+        let mut board = Board::default();
+        board.move_piece(piece_move("e2", "e4")).unwrap();
+        board.move_piece(piece_move("d7", "d5")).unwrap();
+        board.undo_move();
+
+        let history = board.get_move_history();
+        assert_eq!(history.get_ply(), 1);
+        assert_eq!(history.iter().count(), 1);
+        assert_eq!(history.get(1), None);
+        assert_eq!(
+            history.last().map(|played| played.piece_move),
+            Some(piece_move("e2", "e4"))
+        );
+    }
+
+    #[test]
+    fn undo_undo_redo() {
+        let mut board = Board::default();
+        let moves = [piece_move("e2", "e4"), piece_move("e7", "e5")];
+        for piece_move in moves {
+            board.move_piece(piece_move).unwrap();
+        }
+        let after_first_move = {
+            let mut board = Board::default();
+            board.move_piece(moves[0]).unwrap();
+            board.to_fen()
+        };
+
+        assert_eq!(board.undo_move(), Some(moves[1]));
+        assert_eq!(board.undo_move(), Some(moves[0]));
+        assert_eq!(board.undo_move(), None);
+        assert_eq!(board.get_ply(), 0);
+
+        // Redoing replays the first move, leaving the second to be redone
+        assert_eq!(board.redo_move(), Some(moves[0]));
+        assert_eq!(board.get_ply(), 1);
+        assert_eq!(board.to_fen(), after_first_move);
+        assert_eq!(board.get_redo_move(), Some(moves[1]));
+    }
+
+    #[test]
+    fn new_move_discards_redo() {
+        let mut board = Board::default();
+        board.move_piece(piece_move("e2", "e4")).unwrap();
+        board.move_piece(piece_move("e7", "e5")).unwrap();
+        board.undo_move();
+
+        // A different move from the one undone starts a new line
+        board.move_piece(piece_move("c7", "c5")).unwrap();
+        assert_eq!(board.get_redo_move(), None);
+        assert_eq!(board.redo_move(), None);
+
+        board.undo_move();
+        assert_eq!(board.get_redo_move(), Some(piece_move("c7", "c5")));
+        assert_eq!(board.get_move_history().get_ply(), 1);
+    }
+}
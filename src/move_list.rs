@@ -0,0 +1,295 @@
+use bevy::{
+    input::mouse::{MouseScrollUnit, MouseWheel},
+    prelude::*,
+    window::PrimaryWindow,
+};
+
+use crate::{
+    board::{Board, Player},
+    piece::TurnChangedEvent,
+    promotion::PendingPromotion,
+    selection::SelectedPiece,
+    undo_redo::UndoRedoWriters,
+};
+
+const MOVE_LIST_FONT_SIZE: f32 = 28.;
+const MOVE_LIST_WIDTH: f32 = 300.;
+const MOVE_LIST_TOP: f32 = 80.;
+const MOVE_LIST_MARGIN: f32 = 20.;
+const MOVE_LIST_LINE_HEIGHT: f32 = 20.;
+const PANEL_COLOUR: Color = Color::rgba(0.15, 0.15, 0.15, 0.9);
+const CURRENT_PLY_COLOUR: Color = Color::rgb(0.45, 0.3, 0.55);
+
+// One line of the move list, with White's and Black's moves (either can be missing at the start or end of the game)
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct MoveListRow {
+    pub move_number: usize,
+    pub white: Option<(usize, String)>,
+    pub black: Option<(usize, String)>,
+}
+
+// The list of moves which scrolls inside the panel
+#[derive(Component, Default)]
+pub struct MoveList {
+    scroll_position: f32,
+}
+
+// A move in the list, which jumps to the position after it when clicked
+#[derive(Component)]
+pub struct PlyButton(pub usize);
+
+// The move number of a ply (counting from 0), and who played it, given the first move of the game
+pub fn get_ply_move_number(
+    ply: usize,
+    first_player: Player,
+    first_move_number: usize,
+) -> (usize, Player) {
+    let plies_since_white = ply + (first_player == Player::Black) as usize;
+    let player = if plies_since_white.is_multiple_of(2) {
+        Player::White
+    } else {
+        Player::Black
+    };
+
+    (first_move_number + plies_since_white / 2, player)
+}
+
+// Every move in the history, including any which have been undone, grouped into numbered rows
+pub fn get_move_list_rows(board: &Board) -> Vec<MoveListRow> {
+    let mut replay_board = board.clone();
+    while replay_board.undo_move().is_some() {}
+
+    let first_player = replay_board.player;
+    let first_move_number = replay_board.full_move_counter;
+
+    let mut rows: Vec<MoveListRow> = Vec::new();
+    let mut ply = 0;
+    while let Some(piece_move) = replay_board.get_redo_move() {
+        let san = replay_board.to_san(piece_move);
+        replay_board.redo_move();
+
+        let (move_number, player) = get_ply_move_number(ply, first_player, first_move_number);
+        if rows.last().is_none_or(|row| row.move_number != move_number) {
+            rows.push(MoveListRow {
+                move_number,
+                white: None,
+                black: None,
+            });
+        }
+
+        let row = rows.last_mut().unwrap();
+        match player {
+            Player::White => row.white = Some((ply, san)),
+            Player::Black => row.black = Some((ply, san)),
+        }
+
+        ply += 1;
+    }
+
+    rows
+}
+
+pub fn spawn_move_list(mut commands: Commands) {
+    commands
+        .spawn(NodeBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                top: Val::Px(MOVE_LIST_TOP),
+                bottom: Val::Px(MOVE_LIST_MARGIN),
+                left: Val::Px(MOVE_LIST_MARGIN),
+                width: Val::Px(MOVE_LIST_WIDTH),
+                overflow: Overflow::clip_y(),
+                ..default()
+            },
+            background_color: PANEL_COLOUR.into(),
+            ..default()
+        })
+        .with_children(|parent| {
+            parent.spawn((
+                NodeBundle {
+                    style: Style {
+                        flex_direction: FlexDirection::Column,
+                        align_self: AlignSelf::Stretch,
+                        height: Val::Auto,
+                        ..default()
+                    },
+                    ..default()
+                },
+                MoveList::default(),
+            ));
+        });
+}
+
+// Rebuild the list whenever the side to move changes, highlighting the last move played
+pub fn update_move_list(
+    mut commands: Commands,
+    mut ev_turn_changed: EventReader<TurnChangedEvent>,
+    board: Res<Board>,
+    list_query: Query<Entity, With<MoveList>>,
+) {
+    if ev_turn_changed.read().last().is_none() {
+        return;
+    }
+
+    let Ok(list) = list_query.get_single() else {
+        return;
+    };
+
+    let current_ply = board.get_ply().checked_sub(1);
+    let text_style = TextStyle {
+        font_size: MOVE_LIST_FONT_SIZE,
+        color: Color::WHITE,
+        ..default()
+    };
+
+    commands.entity(list).despawn_descendants();
+    commands.entity(list).with_children(|parent| {
+        for row in get_move_list_rows(&board) {
+            parent
+                .spawn(NodeBundle {
+                    style: Style {
+                        flex_direction: FlexDirection::Row,
+                        ..default()
+                    },
+                    ..default()
+                })
+                .with_children(|parent| {
+                    parent.spawn(
+                        TextBundle::from_section(
+                            format!("{}.", row.move_number),
+                            text_style.clone(),
+                        )
+                        .with_style(Style {
+                            width: Val::Px(MOVE_LIST_WIDTH / 5.),
+                            ..default()
+                        }),
+                    );
+
+                    for ply_san in [row.white, row.black] {
+                        let is_current_ply = ply_san
+                            .as_ref()
+                            .is_some_and(|(ply, _)| Some(*ply) == current_ply);
+                        let background_color = if is_current_ply {
+                            CURRENT_PLY_COLOUR
+                        } else {
+                            Color::NONE
+                        };
+
+                        let mut button = parent.spawn(ButtonBundle {
+                            style: Style {
+                                width: Val::Px(MOVE_LIST_WIDTH * 2. / 5.),
+                                ..default()
+                            },
+                            background_color: background_color.into(),
+                            ..default()
+                        });
+
+                        if let Some((ply, san)) = ply_san {
+                            button.insert(PlyButton(ply)).with_children(|parent| {
+                                parent.spawn(TextBundle::from_section(san, text_style.clone()));
+                            });
+                        }
+                    }
+                });
+        }
+    });
+}
+
+// Undo or redo moves until the board shows the position after the clicked move
+pub fn ply_button_click(
+    interaction_query: Query<(&Interaction, &PlyButton), Changed<Interaction>>,
+    mut board: ResMut<Board>,
+    mut undo_redo_writers: UndoRedoWriters,
+    selected_piece: Res<SelectedPiece>,
+    pending_promotion: Res<PendingPromotion>,
+) {
+    if selected_piece.dragged.is_some() || pending_promotion.promotion.is_some() {
+        return;
+    }
+
+    for (interaction, PlyButton(ply)) in interaction_query.iter() {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+
+        let target_ply = ply + 1;
+        while board.get_ply() > target_ply && undo_redo_writers.undo(&mut board) {}
+        while board.get_ply() < target_ply && undo_redo_writers.redo(&mut board) {}
+    }
+}
+
+// Scroll the list with the mouse wheel while the cursor is over the panel
+pub fn scroll_move_list(
+    mut ev_mouse_wheel: EventReader<MouseWheel>,
+    window_query: Query<&Window, With<PrimaryWindow>>,
+    mut list_query: Query<(&mut MoveList, &mut Style, &Parent, &Node)>,
+    panel_query: Query<(&Node, &GlobalTransform)>,
+) {
+    let cursor_position = window_query
+        .get_single()
+        .ok()
+        .and_then(|window| window.cursor_position());
+
+    for ev in ev_mouse_wheel.read() {
+        for (mut list, mut style, parent, list_node) in list_query.iter_mut() {
+            let Ok((panel_node, panel_transform)) = panel_query.get(parent.get()) else {
+                continue;
+            };
+
+            let is_hovered = cursor_position.is_some_and(|cursor| {
+                (cursor - panel_transform.translation().xy())
+                    .abs()
+                    .cmple(panel_node.size() / 2.)
+                    .all()
+            });
+            if !is_hovered {
+                continue;
+            }
+
+            let max_scroll = (list_node.size().y - panel_node.size().y).max(0.);
+            let dy = match ev.unit {
+                MouseScrollUnit::Line => ev.y * MOVE_LIST_LINE_HEIGHT,
+                MouseScrollUnit::Pixel => ev.y,
+            };
+
+            list.scroll_position = (list.scroll_position + dy).clamp(-max_scroll, 0.);
+            style.top = Val::Px(list.scroll_position);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ply_move_numbers() {
+        // A game from the start
+        for (ply, expected) in [
+            (0, (1, Player::White)),
+            (1, (1, Player::Black)),
+            (2, (2, Player::White)),
+            (5, (3, Player::Black)),
+        ] {
+            assert_eq!(
+                get_ply_move_number(ply, Player::White, 1),
+                expected,
+                "{ply}"
+            );
+        }
+
+        // A game from a position where Black moves first
+        for (ply, expected) in [
+            (0, (12, Player::Black)),
+            (1, (13, Player::White)),
+            (2, (13, Player::Black)),
+            (3, (14, Player::White)),
+        ] {
+            assert_eq!(
+                get_ply_move_number(ply, Player::Black, 12),
+                expected,
+                "{ply}"
+            );
+        }
+    }
+}
@@ -0,0 +1,165 @@
+use bevy::{
+    prelude::*,
+    sprite::{MaterialMesh2dBundle, Mesh2dHandle},
+};
+use bevy_mod_picking::prelude::*;
+
+use crate::{
+    board::{Board, TilePos},
+    display::{board_to_pixel_coords, BoardLayout},
+    game_over::GameState,
+    piece::{can_drag_piece, AnalysisMode, MoveFlag},
+    premove::Premove,
+    selection::SelectedPiece,
+};
+
+// A circle showing a square which the held or selected piece can move to
+#[derive(Component)]
+pub struct PossibleMoveMarker;
+
+// Show where a piece can move to as soon as it is picked up
+#[allow(clippy::too_many_arguments)]
+pub fn draw_possible_moves(
+    drag_start: Listener<Pointer<DragStart>>,
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    board: Res<Board>,
+    mut selected_piece: ResMut<SelectedPiece>,
+    marker_query: Query<Entity, With<PossibleMoveMarker>>,
+    premove: Res<Premove>,
+    analysis_mode: Res<AnalysisMode>,
+    game_state: Res<GameState>,
+    layout: Res<BoardLayout>,
+) {
+    // Dragging replaces any selection made by clicking
+    selected_piece.selected = None;
+    selected_piece.dragged = Some(drag_start.target);
+
+    clear_possible_moves(&mut commands, &marker_query);
+
+    let from = board
+        .get_entity_pos(drag_start.target)
+        .filter(|&from| can_drag_piece(&board, from, &premove, &analysis_mode, *game_state));
+
+    if let Some(from) = from {
+        spawn_possible_moves(
+            &mut commands,
+            &mut meshes,
+            &mut materials,
+            &board,
+            from,
+            &layout,
+        );
+    }
+}
+
+// Show where the piece selected by clicking can move to, and remove the markers once it is deselected
+pub fn display_selected_moves(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    board: Res<Board>,
+    selected_piece: Res<SelectedPiece>,
+    marker_query: Query<Entity, With<PossibleMoveMarker>>,
+    layout: Res<BoardLayout>,
+) {
+    // The markers for a dragged piece are handled by the drag listeners instead
+    let changed = selected_piece.is_changed() || layout.is_changed();
+    if !changed || selected_piece.dragged.is_some() {
+        return;
+    }
+
+    clear_possible_moves(&mut commands, &marker_query);
+
+    if let Some(from) = selected_piece.selected {
+        spawn_possible_moves(
+            &mut commands,
+            &mut meshes,
+            &mut materials,
+            &board,
+            from,
+            &layout,
+        );
+    }
+}
+
+pub fn clear_possible_moves(
+    commands: &mut Commands,
+    marker_query: &Query<Entity, With<PossibleMoveMarker>>,
+) {
+    for marker in marker_query.iter() {
+        commands.entity(marker).despawn();
+    }
+}
+
+fn spawn_possible_moves(
+    commands: &mut Commands,
+    meshes: &mut Assets<Mesh>,
+    materials: &mut Assets<ColorMaterial>,
+    board: &Board,
+    from: TilePos,
+    layout: &BoardLayout,
+) {
+    let mut moves = board.clone().moves_from(from);
+    // Each promotion is a separate move onto the same square, but only needs one marker
+    moves.dedup_by_key(|piece_move| piece_move.to);
+
+    for piece_move in moves {
+        let (x, y) = board_to_pixel_coords(piece_move.to.file, piece_move.to.rank, layout);
+
+        // Captures get a larger, fainter circle so that the captured piece can still be seen
+        let is_promotion_capture =
+            matches!(piece_move.flag, MoveFlag::Promotion(_)) && !board.is_empty(piece_move.to);
+        let (radius, colour) = if piece_move.is_capture() || is_promotion_capture {
+            (layout.piece_size * 0.45, Color::rgba(0.8, 0.1, 0.1, 0.35))
+        } else {
+            (layout.piece_size * 0.15, Color::rgba(0.1, 0.1, 0.1, 0.5))
+        };
+
+        commands.spawn((
+            MaterialMesh2dBundle {
+                mesh: Mesh2dHandle(meshes.add(Circle::new(radius))),
+                material: materials.add(colour),
+                transform: Transform::from_xyz(x, y, 5.),
+                ..default()
+            },
+            Pickable::IGNORE,
+            PossibleMoveMarker,
+        ));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn marker_count(app: &mut App) -> usize {
+        app.world
+            .query_filtered::<(), With<PossibleMoveMarker>>()
+            .iter(&app.world)
+            .count()
+    }
+
+    #[test]
+    fn central_knight_markers() {
+        let mut app = App::new();
+        app.init_resource::<Assets<Mesh>>()
+            .init_resource::<Assets<ColorMaterial>>()
+            .init_resource::<BoardLayout>()
+            .insert_resource(Board::from_fen("4k3/8/8/8/3N4/8/8/4K3 w - - 0 1").unwrap())
+            .insert_resource(SelectedPiece {
+                selected: Some(TilePos::from_algebraic("d4").unwrap()),
+                dragged: None,
+            })
+            .add_systems(Update, display_selected_moves);
+
+        app.update();
+        assert_eq!(marker_count(&mut app), 8);
+
+        // Deselecting removes the markers
+        app.world.resource_mut::<SelectedPiece>().selected = None;
+        app.update();
+        assert_eq!(marker_count(&mut app), 0);
+    }
+}
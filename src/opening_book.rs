@@ -0,0 +1,222 @@
+use std::{collections::HashMap, error::Error, fmt, fs, io, path::Path};
+
+use bevy::prelude::*;
+
+use crate::{
+    board::{Board, FenError},
+    piece::PieceMove,
+};
+
+pub const OPENING_BOOK_PATH: &str = "assets/openings.txt";
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum OpeningBookError {
+    Io(io::ErrorKind),
+    InvalidLine(usize),
+    InvalidFen(usize, FenError),
+    InvalidMove(usize, String),
+    InvalidWeight(usize, String),
+}
+
+impl fmt::Display for OpeningBookError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Could not load opening book:")?;
+
+        match self {
+            OpeningBookError::Io(kind) => write!(f, "{kind}"),
+            OpeningBookError::InvalidLine(line) => {
+                write!(
+                    f,
+                    "Line {line} should be of the form \"FEN -> move [weight]\""
+                )
+            }
+            OpeningBookError::InvalidFen(line, fen_error) => {
+                write!(f, "Line {line}: {fen_error}")
+            }
+            OpeningBookError::InvalidMove(line, uci) => {
+                write!(f, "Line {line} has \"{uci}\" which is not a legal move")
+            }
+            OpeningBookError::InvalidWeight(line, weight) => {
+                write!(
+                    f,
+                    "Line {line} has \"{weight}\" which is not a valid weight"
+                )
+            }
+        }
+    }
+}
+
+impl Error for OpeningBookError {}
+
+// Moves to play in known positions, each with a weight for how often it should be picked
+#[derive(Resource, Clone, Default, Debug)]
+pub struct OpeningBook {
+    moves: HashMap<u64, Vec<(PieceMove, u32)>>,
+}
+
+impl OpeningBook {
+    // One entry per line as "FEN -> move [weight]", where the move is in UCI notation and the weight defaults to 1
+    pub fn parse(contents: &str) -> Result<Self, OpeningBookError> {
+        let mut book = OpeningBook::default();
+
+        for (index, line) in contents.lines().enumerate() {
+            let line_number = index + 1;
+
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let (fen, entry) = line
+                .split_once("->")
+                .ok_or(OpeningBookError::InvalidLine(line_number))?;
+
+            let mut board = Board::from_fen(fen.trim())
+                .map_err(|e| OpeningBookError::InvalidFen(line_number, e))?;
+
+            let mut entry = entry.split_whitespace();
+            let uci = entry
+                .next()
+                .ok_or(OpeningBookError::InvalidLine(line_number))?;
+
+            let piece_move = board
+                .parse_uci_move(uci)
+                .filter(|&piece_move| board.get_all_legal_moves().contains(&piece_move))
+                .ok_or_else(|| OpeningBookError::InvalidMove(line_number, uci.to_string()))?;
+
+            let weight = match entry.next() {
+                Some(weight) => weight
+                    .parse::<u32>()
+                    .ok()
+                    .filter(|&weight| weight > 0)
+                    .ok_or_else(|| {
+                        OpeningBookError::InvalidWeight(line_number, weight.to_string())
+                    })?,
+                None => 1,
+            };
+
+            if entry.next().is_some() {
+                return Err(OpeningBookError::InvalidLine(line_number));
+            }
+
+            book.insert(&board, piece_move, weight);
+        }
+
+        Ok(book)
+    }
+
+    pub fn load(path: &Path) -> Result<Self, OpeningBookError> {
+        let contents = fs::read_to_string(path).map_err(|e| OpeningBookError::Io(e.kind()))?;
+
+        Self::parse(&contents)
+    }
+
+    pub fn insert(&mut self, board: &Board, piece_move: PieceMove, weight: u32) {
+        self.moves
+            .entry(board.zobrist_hash())
+            .or_default()
+            .push((piece_move, weight));
+    }
+
+    pub fn get_moves(&self, board: &Board) -> &[(PieceMove, u32)] {
+        self.moves
+            .get(&board.zobrist_hash())
+            .map_or(&[], Vec::as_slice)
+    }
+
+    // Pick one of the book moves for this position, with more heavily weighted moves being picked more often
+    pub fn get_move(&self, board: &Board) -> Option<PieceMove> {
+        let moves = self.get_moves(board);
+
+        let total_weight = moves.iter().map(|&(_, weight)| weight).sum::<u32>();
+        if total_weight == 0 {
+            return None;
+        }
+
+        let mut pick = fastrand::u32(0..total_weight);
+        for &(piece_move, weight) in moves {
+            if pick < weight {
+                return Some(piece_move);
+            }
+            pick -= weight;
+        }
+
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::TilePos;
+
+    const START_FEN: &str = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1";
+
+    fn piece_move(from: &str, to: &str) -> PieceMove {
+        PieceMove::new(
+            TilePos::from_algebraic(from).unwrap(),
+            TilePos::from_algebraic(to).unwrap(),
+        )
+    }
+
+    #[test]
+    fn book_move_without_searching() {
+        let book = OpeningBook::parse(format!("{START_FEN} -> e2e4").as_str()).unwrap();
+        let mut board = Board::default();
+
+        // A search this deep would never finish, so the move must come from the book
+        assert_eq!(
+            board.best_move(100, Some(&book)),
+            Some(piece_move("e2", "e4"))
+        );
+        assert_eq!(book.get_moves(&board).len(), 1);
+
+        board.move_piece(piece_move("e2", "e4")).unwrap();
+        assert_eq!(book.get_move(&board), None);
+    }
+
+    #[test]
+    fn weighted_moves() {
+        let book = OpeningBook::parse(
+            format!("{START_FEN} -> e2e4 3\n# Comment\n\n{START_FEN} -> d2d4").as_str(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            book.get_moves(&Board::default()),
+            [(piece_move("e2", "e4"), 3), (piece_move("d2", "d4"), 1)]
+        );
+    }
+
+    #[test]
+    fn invalid_lines() {
+        for (contents, error) in [
+            ("x".to_string(), OpeningBookError::InvalidLine(1)),
+            (
+                format!("\n{START_FEN} e2e4"),
+                OpeningBookError::InvalidLine(2),
+            ),
+            (
+                format!("{START_FEN} -> e2e5"),
+                OpeningBookError::InvalidMove(1, "e2e5".to_string()),
+            ),
+            (
+                format!("{START_FEN} -> e2e4 0"),
+                OpeningBookError::InvalidWeight(1, "0".to_string()),
+            ),
+            (
+                format!("{START_FEN} -> e2e4 1 2"),
+                OpeningBookError::InvalidLine(1),
+            ),
+        ] {
+            assert_eq!(OpeningBook::parse(&contents).map(|_| ()), Err(error));
+        }
+    }
+
+    #[test]
+    fn bundled_book_loads() {
+        let book = OpeningBook::load(Path::new(OPENING_BOOK_PATH)).unwrap();
+
+        assert!(!book.get_moves(&Board::default()).is_empty());
+    }
+}
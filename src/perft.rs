@@ -0,0 +1,37 @@
+use crate::{board::Board, piece::PieceMove};
+
+impl Board {
+    // Count the positions reachable after this many moves, for checking move generation
+    pub fn perft(&mut self, depth: usize) -> u64 {
+        if depth == 0 {
+            return 1;
+        }
+
+        self.get_all_legal_moves()
+            .into_iter()
+            .map(|piece_move| {
+                let mut board = self.clone();
+                board.apply_move(piece_move);
+
+                board.perft(depth - 1)
+            })
+            .sum()
+    }
+
+    // Perft split by the first move, to narrow down which move leads to a wrong count
+    pub fn perft_divide(&mut self, depth: usize) -> Vec<(PieceMove, u64)> {
+        if depth == 0 {
+            return Vec::new();
+        }
+
+        self.get_all_legal_moves()
+            .into_iter()
+            .map(|piece_move| {
+                let mut board = self.clone();
+                board.apply_move(piece_move);
+
+                (piece_move, board.perft(depth - 1))
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,374 @@
+use std::{error::Error, fmt};
+
+use crate::{
+    board::{Board, FenError, Player},
+    game_over::GameOverEvent,
+    san::SanError,
+};
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum PgnError {
+    InvalidFen(FenError),
+    InvalidSan(SanError),
+}
+
+impl fmt::Display for PgnError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Could not create board using PGN:")?;
+
+        match self {
+            PgnError::InvalidFen(fen_error) => write!(f, "{fen_error}"),
+            PgnError::InvalidSan(san_error) => write!(f, "{san_error}"),
+        }
+    }
+}
+
+impl Error for PgnError {}
+
+// The Seven Tag Roster which starts every PGN, followed by any other tags
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct PgnHeaders {
+    pub event: String,
+    pub site: String,
+    pub date: String,
+    pub round: String,
+    pub white: String,
+    pub black: String,
+    pub result: String,
+    pub extra: Vec<(String, String)>,
+}
+
+// Unknown values are written as question marks, and the result is left open until the game ends
+impl Default for PgnHeaders {
+    fn default() -> Self {
+        Self {
+            event: "Casual game".to_string(),
+            site: "?".to_string(),
+            date: "????.??.??".to_string(),
+            round: "-".to_string(),
+            white: "?".to_string(),
+            black: "?".to_string(),
+            result: "*".to_string(),
+            extra: Vec::new(),
+        }
+    }
+}
+
+impl PgnHeaders {
+    pub fn with_result(self, game_over: &GameOverEvent) -> Self {
+        Self {
+            result: get_pgn_result_tag(game_over.winner).to_string(),
+            ..self
+        }
+    }
+
+    pub fn get_extra(&self, tag: &str) -> Option<&str> {
+        self.extra
+            .iter()
+            .find(|(name, _)| name == tag)
+            .map(|(_, value)| value.as_str())
+    }
+
+    // Read the tag pairs at the start of a PGN, keeping the defaults for any missing tags
+    pub fn from_pgn(pgn: &str) -> Self {
+        let mut headers = PgnHeaders::default();
+
+        for line in pgn.lines().map(str::trim) {
+            let Some((tag, value)) = line
+                .strip_prefix('[')
+                .and_then(|line| line.strip_suffix(']'))
+                .and_then(|line| line.split_once(' '))
+            else {
+                continue;
+            };
+
+            let Some(value) = value
+                .trim()
+                .strip_prefix('"')
+                .and_then(|value| value.strip_suffix('"'))
+            else {
+                continue;
+            };
+            let value = value.replace("\\\"", "\"").replace("\\\\", "\\");
+
+            match tag {
+                "Event" => headers.event = value,
+                "Site" => headers.site = value,
+                "Date" => headers.date = value,
+                "Round" => headers.round = value,
+                "White" => headers.white = value,
+                "Black" => headers.black = value,
+                "Result" => headers.result = value,
+                _ => headers.extra.push((tag.to_string(), value)),
+            }
+        }
+
+        headers
+    }
+
+    // Each tag on its own line, in the order given by the PGN standard
+    pub fn to_tag_pairs(&self) -> String {
+        [
+            ("Event", self.event.as_str()),
+            ("Site", self.site.as_str()),
+            ("Date", self.date.as_str()),
+            ("Round", self.round.as_str()),
+            ("White", self.white.as_str()),
+            ("Black", self.black.as_str()),
+            ("Result", self.result.as_str()),
+        ]
+        .into_iter()
+        .chain(
+            self.extra
+                .iter()
+                .map(|(tag, value)| (tag.as_str(), value.as_str())),
+        )
+        .map(|(tag, value)| {
+            let value = value.replace('\\', "\\\\").replace('"', "\\\"");
+            format!("[{tag} \"{value}\"]\n")
+        })
+        .collect()
+    }
+}
+
+pub fn get_pgn_result_tag(winner: Option<Player>) -> &'static str {
+    match winner {
+        Some(Player::White) => "1-0",
+        Some(Player::Black) => "0-1",
+        None => "1/2-1/2",
+    }
+}
+
+impl Board {
+    pub fn from_pgn(pgn: &str) -> Result<Board, PgnError> {
+        let mut board = Board::default();
+        let mut movetext = String::new();
+
+        // Tag pairs are only needed when the game starts from a custom position
+        for line in pgn.lines() {
+            let line = line.trim();
+
+            if line.starts_with('[') {
+                if let Some(fen) = line
+                    .strip_prefix("[FEN \"")
+                    .and_then(|fen| fen.strip_suffix("\"]"))
+                {
+                    board = Board::from_fen(fen).map_err(PgnError::InvalidFen)?;
+                }
+            } else {
+                // Semicolons start a comment which lasts until the end of the line
+                movetext += line.split(';').next().unwrap_or_default();
+                movetext.push(' ');
+            }
+        }
+
+        // Remove comments and variations
+        let mut stripped = String::new();
+        let mut comment_depth = 0;
+        for chr in movetext.chars() {
+            match chr {
+                '{' | '(' => comment_depth += 1,
+                '}' | ')' => comment_depth -= 1,
+                _ if comment_depth > 0 => {}
+                _ => stripped.push(chr),
+            }
+        }
+
+        for token in stripped.split_whitespace() {
+            // Skip move numbers, which may be attached to the move itself (e.g. "1.e4" or "3...Nf6"),
+            // without touching castling written with zeros
+            let san = match token.split_once('.') {
+                Some((number, san)) if number.chars().all(|chr| chr.is_ascii_digit()) => {
+                    san.trim_start_matches('.')
+                }
+                _ => token,
+            };
+
+            if san.is_empty()
+                || san.starts_with('$')
+                || matches!(token, "1-0" | "0-1" | "1/2-1/2" | "*")
+            {
+                continue;
+            }
+
+            let piece_move = board.parse_san(san).map_err(PgnError::InvalidSan)?;
+            board.apply_move(piece_move);
+        }
+
+        Ok(board)
+    }
+
+    pub fn to_pgn(&self) -> String {
+        self.to_pgn_with_headers(PgnHeaders::default())
+    }
+
+    // The result is worked out from the board if the headers leave it open
+    pub fn to_pgn_with_headers(&self, mut headers: PgnHeaders) -> String {
+        // Take back every move to find the starting position, then replay them to write the SAN
+        let mut board = self.clone();
+        let mut moves = Vec::new();
+        while let Some(piece_move) = board.undo_move() {
+            moves.push(piece_move);
+        }
+        moves.reverse();
+
+        if headers.result == "*" {
+            headers.result = self.clone().get_pgn_result().to_string();
+        }
+
+        // Games which don't start from the standard position need it written out to be read back
+        let fen = board.to_fen();
+        if fen != Board::default().to_fen() && headers.get_extra("FEN").is_none() {
+            headers.extra.push(("SetUp".to_string(), "1".to_string()));
+            headers.extra.push(("FEN".to_string(), fen));
+        }
+
+        let mut pgn = headers.to_tag_pairs();
+        pgn.push('\n');
+
+        for (i, piece_move) in moves.into_iter().enumerate() {
+            if board.player == Player::White {
+                pgn += format!("{}. ", board.full_move_counter).as_str();
+            } else if i == 0 {
+                pgn += format!("{}... ", board.full_move_counter).as_str();
+            }
+
+            pgn += board.to_san(piece_move).as_str();
+            pgn.push(' ');
+
+            board.apply_move(piece_move);
+        }
+
+        pgn += headers.result.as_str();
+
+        pgn
+    }
+
+    fn get_pgn_result(&mut self) -> &'static str {
+        let player = self.player;
+
+        if self.is_checkmate(player) {
+            get_pgn_result_tag(Some(player.opponent()))
+        } else if self.is_stalemate(player)
+            || self.is_fifty_move_draw()
+            || self.is_threefold_repetition()
+            || self.is_insufficient_material()
+        {
+            "1/2-1/2"
+        } else {
+            "*"
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{game_over::GameEndReason, san::SanError};
+
+    #[test]
+    fn import_game() {
+        let board = Board::from_pgn(
+            "[Event \"Test\"]\n\n1. e4 e5 2. Nf3 {A comment} Nc6 3. Bb5 a6 $1 (3... d6) 4. Bxc6 dxc6 \
+             5. O-O f6 6. d4 exd4 7. Nxd4 c5 8. Nb3?! Qxd1 9. Rxd1 *",
+        )
+        .unwrap();
+
+        assert_eq!(
+            board.to_fen(),
+            "r1b1kbnr/1pp3pp/p4p2/2p5/4P3/1N6/PPP2PPP/RNBR2K1 b kq - 0 9"
+        );
+    }
+
+    #[test]
+    fn import_attached_move_numbers() {
+        let board = Board::from_pgn("1.e4 d5 2.exd5 c6 3.dxc6 Nf6 4.cxb7 Nbd7 5.bxa8=Q").unwrap();
+        assert!(board.to_fen().starts_with("Q1bqkb1r/p2npppp/5n2/"));
+
+        let board = Board::from_pgn("1. e4 Nf6 2. e5 d5 3. exd6 3...e6").unwrap();
+        assert!(board
+            .to_fen()
+            .starts_with("rnbqkb1r/ppp2ppp/3Ppn2/8/8/8/PPPP1PPP/RNBQKBNR w"));
+    }
+
+    #[test]
+    fn import_zero_castling() {
+        let zeros =
+            Board::from_pgn("1. e4 e5 2. Nf3 Nc6 3. Bc4 Bc5 4. 0-0 Nf6 5. d3 0-0 *").unwrap();
+        let letters =
+            Board::from_pgn("1. e4 e5 2. Nf3 Nc6 3. Bc4 Bc5 4. O-O Nf6 5. d3 O-O *").unwrap();
+
+        assert_eq!(zeros.to_fen(), letters.to_fen());
+        assert_eq!(
+            zeros.to_fen(),
+            "r1bq1rk1/pppp1ppp/2n2n2/2b1p3/2B1P3/3P1N2/PPP2PPP/RNBQ1RK1 w - - 1 6"
+        );
+
+        let board =
+            Board::from_pgn("1. d4 d5 2. Nc3 Nc6 3. Bf4 Bf5 4. Qd2 Qd7 5. 0-0-0 0-0-0").unwrap();
+        assert!(board
+            .to_fen()
+            .starts_with("2kr1bnr/pppqpppp/2n5/3p1b2/3P1B2/2N5/PPPQPPPP/2KR1BNR w"));
+    }
+
+    #[test]
+    fn import_illegal_move() {
+        assert!(matches!(
+            Board::from_pgn("1. e5"),
+            Err(PgnError::InvalidSan(SanError::IllegalMove(_)))
+        ));
+    }
+
+    #[test]
+    fn header_block_round_trip() {
+        let headers = PgnHeaders {
+            event: "Club \"Open\"".to_string(),
+            white: "Alice".to_string(),
+            black: "Bob".to_string(),
+            extra: vec![("Annotator".to_string(), "C:\\Games".to_string())],
+            ..PgnHeaders::default()
+        };
+
+        let pgn = Board::default().to_pgn_with_headers(headers.clone());
+        assert!(pgn.starts_with("[Event \"Club \\\"Open\\\"\"]\n[Site \"?\"]\n"));
+        assert_eq!(PgnHeaders::from_pgn(&pgn), headers);
+    }
+
+    #[test]
+    fn checkmate_result() {
+        let mut board = Board::default();
+        for san in ["f3", "e5", "g4", "Qh4#"] {
+            let piece_move = board.parse_san(san).unwrap();
+            board.move_piece(piece_move).unwrap();
+        }
+
+        let pgn = board.to_pgn();
+        assert_eq!(PgnHeaders::from_pgn(&pgn).result, "0-1");
+        assert!(pgn.ends_with("1. f3 e5 2. g4 Qh4# 0-1"));
+
+        let game_over = GameOverEvent {
+            winner: Some(Player::Black),
+            reason: GameEndReason::Checkmate,
+        };
+        assert_eq!(
+            PgnHeaders::default().with_result(&game_over).result,
+            PgnHeaders::from_pgn(&pgn).result
+        );
+
+        // Reading the game back gives the same position
+        assert_eq!(Board::from_pgn(&pgn).unwrap().to_fen(), board.to_fen());
+    }
+
+    #[test]
+    fn scholars_mate_export() {
+        let mut board = Board::default();
+        for san in ["e4", "e5", "Bc4", "Nc6", "Qh5", "Nf6", "Qxf7#"] {
+            let piece_move = board.parse_san(san).unwrap();
+            board.move_piece(piece_move).unwrap();
+        }
+
+        let pgn = board.to_pgn();
+        assert!(pgn.contains("[Result \"1-0\"]\n"));
+        assert!(pgn.ends_with("\n1. e4 e5 2. Bc4 Nc6 3. Qh5 Nf6 4. Qxf7# 1-0"));
+    }
+}
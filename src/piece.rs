@@ -1,9 +1,15 @@
-use bevy::{prelude::*, sprite::Mesh2dHandle};
+use bevy::prelude::*;
 use bevy_mod_picking::prelude::*;
 
 use crate::{
-    board::TilePos,
-    display::{board_to_pixel_coords, pixel_to_board_coords, PIECE_SIZE, PIECE_SIZE_IMG},
+    animation::{animate_to, AnimationSettings, MoveAnimation},
+    board::{Board, MoveOutcome, Player, TilePos},
+    display::{board_to_pixel_coords, pixel_to_board_coords_checked, BoardLayout},
+    game_over::GameState,
+    move_markers::{clear_possible_moves, draw_possible_moves, PossibleMoveMarker},
+    premove::Premove,
+    promotion::PendingPromotion,
+    selection::on_tile_click,
 };
 
 pub const PIECE_AMT: usize = 6;
@@ -15,14 +21,130 @@ pub struct PieceMoveEvent {
     pub entity: Entity,
 }
 
+// Sent when a piece is captured, so that its sprite can be removed, with where it was captured (the pawn's square for en passant)
+#[derive(Event)]
+pub struct PieceCapturedEvent {
+    pub piece: Piece,
+    pub at: TilePos,
+    pub by: Player,
+    pub entity: Option<Entity>,
+}
+
+// Sent after a move is made, with what happened as a result
+#[derive(Event)]
+pub struct MoveOutcomeEvent {
+    pub outcome: MoveOutcome,
+    pub entity: Entity,
+}
+
+// Sent when a move isn't allowed, so that the piece can go back to where it came from
+#[derive(Event)]
+pub struct MoveRejectedEvent {
+    pub piece_move: PieceMove,
+    pub entity: Entity,
+}
+
+// Sent whenever the side to move changes, after a move or an undo or redo
+#[derive(Event)]
+pub struct TurnChangedEvent {
+    pub new_player: Player,
+}
+
 #[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum CastleSide {
+    KingSide,
+    QueenSide,
+}
+
+// What kind of move this is, as worked out from the board it is made on
+// Promotions which capture are flagged as promotions, with the capture found from the board
+#[derive(Default, Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum MoveFlag {
+    #[default]
+    Quiet,
+    Capture,
+    DoublePawnPush,
+    EnPassant,
+    Castle(CastleSide),
+    Promotion(Piece),
+}
+
+#[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct PieceMove {
     pub from: TilePos,
     pub to: TilePos,
+    // Moves made without looking at the board (such as from clicks or notation) are quiet until flagged by Board::flag_move,
+    // apart from the piece chosen for a promotion, which is only kept in the flag
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub flag: MoveFlag,
+}
+
+impl PieceMove {
+    pub fn new(from: TilePos, to: TilePos) -> Self {
+        Self {
+            from,
+            to,
+            flag: MoveFlag::Quiet,
+        }
+    }
+
+    pub fn with_promotion(self, promotion: Piece) -> Self {
+        self.with_flag(MoveFlag::Promotion(promotion))
+    }
+
+    // The piece a pawn is promoted to, if it has been chosen
+    pub fn get_promotion(&self) -> Option<Piece> {
+        match self.flag {
+            MoveFlag::Promotion(promotion) => Some(promotion),
+            _ => None,
+        }
+    }
+
+    pub fn with_flag(self, flag: MoveFlag) -> Self {
+        Self { flag, ..self }
+    }
+
+    pub fn is_capture(&self) -> bool {
+        matches!(self.flag, MoveFlag::Capture | MoveFlag::EnPassant)
+    }
+}
+
+// Apart from the promotion, the flag follows from the board, so moves are the same if they go between the same squares with the same promotion
+impl PartialEq for PieceMove {
+    fn eq(&self, other: &Self) -> bool {
+        self.from == other.from
+            && self.to == other.to
+            && self.get_promotion() == other.get_promotion()
+    }
+}
+
+impl Eq for PieceMove {}
+
+// Uses the same numbering as the white pieces
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum PieceType {
+    Queen = 1,
+    King = 2,
+    Rook = 3,
+    Knight = 4,
+    Bishop = 5,
+    Pawn = 6,
 }
 
+// The pieces a pawn can be promoted to, in the order they are offered
+pub const PROMOTION_PIECE_TYPES: [PieceType; 4] = [
+    PieceType::Queen,
+    PieceType::Rook,
+    PieceType::Bishop,
+    PieceType::Knight,
+];
+
 #[allow(dead_code)]
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Piece {
     None = 0,
     WQueen = 1,
@@ -66,6 +188,35 @@ impl From<usize> for Piece {
 }
 
 impl Piece {
+    pub fn new(piece_type: PieceType, player: Player) -> Self {
+        match (player, piece_type) {
+            (Player::White, PieceType::Queen) => Piece::WQueen,
+            (Player::White, PieceType::King) => Piece::WKing,
+            (Player::White, PieceType::Rook) => Piece::WRook,
+            (Player::White, PieceType::Knight) => Piece::WKnight,
+            (Player::White, PieceType::Bishop) => Piece::WBishop,
+            (Player::White, PieceType::Pawn) => Piece::WPawn,
+            (Player::Black, PieceType::Queen) => Piece::BQueen,
+            (Player::Black, PieceType::King) => Piece::BKing,
+            (Player::Black, PieceType::Rook) => Piece::BRook,
+            (Player::Black, PieceType::Knight) => Piece::BKnight,
+            (Player::Black, PieceType::Bishop) => Piece::BBishop,
+            (Player::Black, PieceType::Pawn) => Piece::BPawn,
+        }
+    }
+
+    pub fn piece_type(self) -> Option<PieceType> {
+        match self {
+            Piece::None => None,
+            Piece::WQueen | Piece::BQueen => Some(PieceType::Queen),
+            Piece::WKing | Piece::BKing => Some(PieceType::King),
+            Piece::WRook | Piece::BRook => Some(PieceType::Rook),
+            Piece::WKnight | Piece::BKnight => Some(PieceType::Knight),
+            Piece::WBishop | Piece::BBishop => Some(PieceType::Bishop),
+            Piece::WPawn | Piece::BPawn => Some(PieceType::Pawn),
+        }
+    }
+
     pub fn is_white(self) -> bool {
         ((self as u8 >> 3) & 1) == 0 && self != Piece::None
     }
@@ -74,6 +225,17 @@ impl Piece {
         ((self as u8 >> 3) & 1) == 1
     }
 
+    // The player this piece belongs to, where an empty square belongs to neither
+    pub fn to_player(self) -> Option<Player> {
+        if self.is_white() {
+            Some(Player::White)
+        } else if self.is_black() {
+            Some(Player::Black)
+        } else {
+            None
+        }
+    }
+
     pub fn to_algebraic(&self) -> char {
         match self {
             Piece::None => '-',
@@ -92,6 +254,24 @@ impl Piece {
         }
     }
 
+    pub fn to_unicode(&self) -> char {
+        match self {
+            Piece::None => '-',
+            Piece::WPawn => '♙',
+            Piece::WKnight => '♘',
+            Piece::WBishop => '♗',
+            Piece::WRook => '♖',
+            Piece::WQueen => '♕',
+            Piece::WKing => '♔',
+            Piece::BPawn => '♟',
+            Piece::BKnight => '♞',
+            Piece::BBishop => '♝',
+            Piece::BRook => '♜',
+            Piece::BQueen => '♛',
+            Piece::BKing => '♚',
+        }
+    }
+
     pub fn from_algebraic(chr: char) -> Option<Self> {
         match chr {
             '-' => Some(Piece::None),
@@ -112,12 +292,51 @@ impl Piece {
     }
 }
 
+// Lets either player's pieces be dragged on any turn, for setting up and analysing positions
+#[derive(Resource, Default)]
+pub struct AnalysisMode {
+    pub enabled: bool,
+}
+
+// Pieces can be dragged on their own turn, by a player queuing a premove, or at any time in analysis mode,
+// but not at all once the game is over
+pub fn can_drag_piece(
+    board: &Board,
+    from: TilePos,
+    premove: &Premove,
+    analysis_mode: &AnalysisMode,
+    game_state: GameState,
+) -> bool {
+    let Some(player) = board.get_piece(from).to_player() else {
+        return false;
+    };
+
+    if game_state.is_over() {
+        return false;
+    }
+
+    analysis_mode.enabled
+        || player == board.player
+        || (premove.is_waiting(board) && premove.player == Some(player))
+}
+
+// Turn analysis mode on and off with M
+pub fn analysis_mode_input(
+    keys: Res<ButtonInput<KeyCode>>,
+    mut analysis_mode: ResMut<AnalysisMode>,
+) {
+    if keys.just_pressed(KeyCode::KeyM) {
+        analysis_mode.enabled = !analysis_mode.enabled;
+    }
+}
+
 #[derive(Bundle)]
 pub struct PieceBundle {
     pub sprite: SpriteSheetBundle,
-    // on_drag_start_listener: On<Pointer<DragStart>>,
+    on_drag_start_listener: On<Pointer<DragStart>>,
     on_drag_listener: On<Pointer<Drag>>,
     on_drag_end_listener: On<Pointer<DragEnd>>,
+    on_click_listener: On<Pointer<Click>>,
 }
 
 impl PieceBundle {
@@ -126,8 +345,9 @@ impl PieceBundle {
         key: Piece,
         texture: Handle<Image>,
         texture_atlas_layout: Handle<TextureAtlasLayout>,
+        layout: &BoardLayout,
     ) -> Self {
-        let (x, y) = board_to_pixel_coords(i, j);
+        let (x, y) = board_to_pixel_coords(i, j, layout);
 
         // Create a bundle with this piece's spritesheet and some listeners for picking up the pieces
         Self {
@@ -137,23 +357,39 @@ impl PieceBundle {
                     layout: texture_atlas_layout,
                     index: Into::<usize>::into(key),
                 },
-                transform: Transform::from_scale(Vec3::splat(PIECE_SIZE / PIECE_SIZE_IMG))
+                transform: Transform::from_scale(Vec3::splat(layout.get_piece_scale()))
                     .with_translation(Vec3::new(x, y, 1.)),
                 ..default()
             },
-            // on_drag_start_listener: On::<Pointer<DragStart>>::run(draw_possible_moves),
+            on_drag_start_listener: On::<Pointer<DragStart>>::run(draw_possible_moves),
             on_drag_listener: On::<Pointer<Drag>>::run(on_piece_drag),
             on_drag_end_listener: On::<Pointer<DragEnd>>::run(on_piece_drag_end),
+            on_click_listener: On::<Pointer<Click>>::run(on_tile_click),
         }
     }
 }
 
-// Move the piece when it is dragged by a mouse
+// Move the piece when it is dragged by a mouse, so long as it can be moved
 fn on_piece_drag(
+    mut commands: Commands,
     mut drag_er: EventReader<Pointer<Drag>>,
     mut transform_query: Query<&mut Transform>,
+    board: Res<Board>,
+    premove: Res<Premove>,
+    analysis_mode: Res<AnalysisMode>,
+    game_state: Res<GameState>,
 ) {
     for drag_data in drag_er.read() {
+        let can_drag = board.get_entity_pos(drag_data.target).is_some_and(|from| {
+            can_drag_piece(&board, from, &premove, &analysis_mode, *game_state)
+        });
+        if !can_drag {
+            continue;
+        }
+
+        // Picking up a piece which is still sliding into place stops it where it is
+        commands.entity(drag_data.target).remove::<MoveAnimation>();
+
         let mut transform = transform_query.get_mut(drag_data.target).unwrap();
         transform.translation += Vec3::new(drag_data.delta.x, -drag_data.delta.y, 0.);
         transform.translation.z = 10.;
@@ -161,52 +397,437 @@ fn on_piece_drag(
 }
 
 // Finalise the movement of a piece, either snapping it to the grid, or by moving it back
+#[allow(clippy::too_many_arguments)]
 fn on_piece_drag_end(
     mut commands: Commands,
     mut drag_er: EventReader<Pointer<DragEnd>>,
     mut transform_query: Query<&mut Transform>,
-    possible_move_meshes: Query<Entity, With<Mesh2dHandle>>,
+    marker_query: Query<Entity, With<PossibleMoveMarker>>,
     mut ev_piece_move: EventWriter<PieceMoveEvent>,
+    board: Res<Board>,
+    mut pending_promotion: ResMut<PendingPromotion>,
+    mut premove: ResMut<Premove>,
+    analysis_mode: Res<AnalysisMode>,
+    game_state: Res<GameState>,
+    layout: Res<BoardLayout>,
 ) {
     for drag_data in drag_er.read() {
-        let transform = transform_query.get_mut(drag_data.target).unwrap();
+        clear_possible_moves(&mut commands, &marker_query);
 
-        // Find where the piece was moved from in board coordinates
-        let original_pos = transform.translation.xy()
-            - Vec2::new(drag_data.distance.x, -drag_data.distance.y)
-            + Vec2::new(PIECE_SIZE, PIECE_SIZE) / 2.;
-        let (ori_file, ori_rank) = pixel_to_board_coords(original_pos.x, original_pos.y);
+        // Pieces which couldn't be dragged stayed where they were
+        let Some(from) = board
+            .get_entity_pos(drag_data.target)
+            .filter(|&from| can_drag_piece(&board, from, &premove, &analysis_mode, *game_state))
+        else {
+            continue;
+        };
 
-        // Find the new position, snapped to board coords, and move the sprite there
-        let (file, rank) = pixel_to_board_coords(
-            transform.translation.x + PIECE_SIZE / 2.,
-            transform.translation.y + PIECE_SIZE / 2.,
-        );
+        let transform = transform_query.get_mut(drag_data.target).unwrap();
 
-        ev_piece_move.send(PieceMoveEvent {
-            piece_move: PieceMove {
-                from: TilePos::new(ori_file, ori_rank),
-                to: TilePos::new(file, rank),
-            },
-            entity: drag_data.target,
-        });
+        // Find the new position, snapped to board coords, and move the sprite there (or back, if dropped off the board)
+        let to = pixel_to_board_coords_checked(
+            transform.translation.x,
+            transform.translation.y,
+            &layout,
+        )
+        .map_or(from, |(file, rank)| TilePos::new(file, rank));
 
-        // Clean up the possible move markers
-        for mesh in possible_move_meshes.iter() {
-            commands.entity(mesh).despawn();
+        let piece_move = PieceMove::new(from, to);
+
+        // Analysis mode moves the piece straight away rather than queuing it
+        if !analysis_mode.enabled && premove.queue(&board, piece_move) {
+            // Moves made while waiting for the computer are queued, and the piece goes back until then
+            ev_piece_move.send(PieceMoveEvent {
+                piece_move: PieceMove::new(piece_move.from, piece_move.from),
+                entity: drag_data.target,
+            });
+        } else if board.is_promotion_move(piece_move)
+            && board
+                .get_possible_moves(piece_move.from)
+                .contains(&piece_move.to)
+        {
+            // Wait for the promotion piece to be chosen before sending the move
+            pending_promotion.promotion = Some((piece_move, drag_data.target));
+        } else {
+            ev_piece_move.send(PieceMoveEvent {
+                piece_move,
+                entity: drag_data.target,
+            });
         }
     }
 }
 
+// Make the move on the board, so long as it is legal and the game hasn't ended
+#[allow(clippy::too_many_arguments)]
 pub fn piece_move_event_reader(
     mut ev_piece_move: EventReader<PieceMoveEvent>,
-    mut transform_query: Query<&mut Transform>,
+    mut ev_piece_captured: EventWriter<PieceCapturedEvent>,
+    mut ev_turn_changed: EventWriter<TurnChangedEvent>,
+    mut ev_move_outcome: EventWriter<MoveOutcomeEvent>,
+    mut ev_move_rejected: EventWriter<MoveRejectedEvent>,
+    mut board: ResMut<Board>,
+    game_state: Res<GameState>,
+    analysis_mode: Option<Res<AnalysisMode>>,
 ) {
     for ev in ev_piece_move.read() {
-        let mut transform = transform_query.get_mut(ev.entity).unwrap();
+        if ev.piece_move.from == ev.piece_move.to || game_state.is_over() {
+            ev_move_rejected.send(MoveRejectedEvent {
+                piece_move: ev.piece_move,
+                entity: ev.entity,
+            });
+            continue;
+        }
+
+        // Either player can move in analysis mode, so the turn goes to whoever makes a legal move
+        if analysis_mode.as_ref().is_some_and(|mode| mode.enabled)
+            && board
+                .get_legal_moves(ev.piece_move.from)
+                .contains(&ev.piece_move.to)
+        {
+            if let Some(player) = board.get_piece(ev.piece_move.from).to_player() {
+                board.pass_turn_to(player);
+            }
+        }
+
+        let Ok(outcome) = board.move_piece(ev.piece_move) else {
+            ev_move_rejected.send(MoveRejectedEvent {
+                piece_move: ev.piece_move,
+                entity: ev.entity,
+            });
+            continue;
+        };
+
+        ev_turn_changed.send(TurnChangedEvent {
+            new_player: board.player,
+        });
+        ev_move_outcome.send(MoveOutcomeEvent {
+            outcome,
+            entity: ev.entity,
+        });
+
+        if let Some((piece, at)) = outcome.captured {
+            ev_piece_captured.send(PieceCapturedEvent {
+                piece,
+                at,
+                by: board.get_next_player(),
+                entity: outcome.captured_entity,
+            });
+        }
+    }
+}
+
+// Move the sprites to follow the board, sending pieces whose move was rejected back where they came from
+#[allow(clippy::too_many_arguments)]
+pub fn move_piece_sprites(
+    mut commands: Commands,
+    mut ev_move_outcome: EventReader<MoveOutcomeEvent>,
+    mut ev_move_rejected: EventReader<MoveRejectedEvent>,
+    mut transform_query: Query<&mut Transform>,
+    mut texture_atlas_query: Query<&mut TextureAtlas>,
+    board: Res<Board>,
+    layout: Res<BoardLayout>,
+    animation_settings: Res<AnimationSettings>,
+) {
+    let mut sprite_moves: Vec<(Entity, TilePos)> = ev_move_rejected
+        .read()
+        .map(|ev| (ev.entity, ev.piece_move.from))
+        .collect();
+
+    for ev in ev_move_outcome.read() {
+        sprite_moves.push((ev.entity, ev.outcome.destination));
+
+        // Move the rook's sprite alongside the castling king
+        if let Some(rook_move) = ev.outcome.castling_rook_move {
+            if let Some(rook_entity) = board.get_entity(rook_move.to) {
+                sprite_moves.push((rook_entity, rook_move.to));
+            }
+        }
+
+        // Update the sprite if the piece was promoted
+        if let Some(promotion) = ev.outcome.promotion {
+            if let Ok(mut texture_atlas) = texture_atlas_query.get_mut(ev.entity) {
+                texture_atlas.index = promotion.into();
+            }
+        }
+    }
+
+    for (entity, tile_pos) in sprite_moves {
+        let Ok(mut transform) = transform_query.get_mut(entity) else {
+            continue;
+        };
+
+        let (x, y) = board_to_pixel_coords(tile_pos.file, tile_pos.rank, &layout);
+
+        animate_to(
+            &mut commands,
+            entity,
+            &mut transform,
+            Vec3::new(x, y, 1.),
+            &animation_settings,
+        );
+    }
+}
+
+// Remove the sprites of captured pieces, including pawns captured en passant which weren't on the destination square
+pub fn piece_captured_event_reader(
+    mut commands: Commands,
+    mut ev_piece_captured: EventReader<PieceCapturedEvent>,
+) {
+    for ev in ev_piece_captured.read() {
+        if let Some(entity) = ev.entity {
+            commands.entity(entity).despawn();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::headless::ChessCorePlugin;
+
+    #[test]
+    fn piece_types() {
+        for (piece, piece_type) in [
+            (Piece::None, None),
+            (Piece::WQueen, Some(PieceType::Queen)),
+            (Piece::WKing, Some(PieceType::King)),
+            (Piece::WRook, Some(PieceType::Rook)),
+            (Piece::WKnight, Some(PieceType::Knight)),
+            (Piece::WBishop, Some(PieceType::Bishop)),
+            (Piece::WPawn, Some(PieceType::Pawn)),
+            (Piece::BQueen, Some(PieceType::Queen)),
+            (Piece::BKing, Some(PieceType::King)),
+            (Piece::BRook, Some(PieceType::Rook)),
+            (Piece::BKnight, Some(PieceType::Knight)),
+            (Piece::BBishop, Some(PieceType::Bishop)),
+            (Piece::BPawn, Some(PieceType::Pawn)),
+        ] {
+            assert_eq!(piece.piece_type(), piece_type, "{piece:?}");
+
+            // Putting the type back together with the colour gives the same piece
+            if let (Some(piece_type), Some(player)) = (piece_type, piece.to_player()) {
+                assert_eq!(Piece::new(piece_type, player), piece);
+            }
+        }
+    }
+
+    #[test]
+    fn piece_players() {
+        for (piece, player) in [
+            (Piece::None, None),
+            (Piece::WQueen, Some(Player::White)),
+            (Piece::WKing, Some(Player::White)),
+            (Piece::WRook, Some(Player::White)),
+            (Piece::WKnight, Some(Player::White)),
+            (Piece::WBishop, Some(Player::White)),
+            (Piece::WPawn, Some(Player::White)),
+            (Piece::BQueen, Some(Player::Black)),
+            (Piece::BKing, Some(Player::Black)),
+            (Piece::BRook, Some(Player::Black)),
+            (Piece::BKnight, Some(Player::Black)),
+            (Piece::BBishop, Some(Player::Black)),
+            (Piece::BPawn, Some(Player::Black)),
+        ] {
+            assert_eq!(piece.to_player(), player, "{piece:?}");
+            assert_eq!(piece.is_white(), player == Some(Player::White), "{piece:?}");
+            assert_eq!(piece.is_black(), player == Some(Player::Black), "{piece:?}");
+        }
+    }
+
+    #[test]
+    fn promotion_is_kept_in_flag() {
+        let from = TilePos::from_algebraic("a7").unwrap();
+        let to = TilePos::from_algebraic("a8").unwrap();
+        let piece_move = PieceMove::new(from, to);
+
+        assert_eq!(piece_move.get_promotion(), None);
+
+        let promotion = piece_move.with_promotion(Piece::WKnight);
+        assert_eq!(promotion.flag, MoveFlag::Promotion(Piece::WKnight));
+        assert_eq!(promotion.get_promotion(), Some(Piece::WKnight));
+
+        // Moves with different promotions are different moves, whatever else the flag says
+        assert_ne!(promotion, piece_move.with_promotion(Piece::WQueen));
+        assert_ne!(promotion, piece_move);
+        assert_eq!(piece_move, piece_move.with_flag(MoveFlag::Capture));
+    }
+
+    fn core_app(fen: &str) -> App {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, ChessCorePlugin))
+            .insert_resource(Board::from_fen(fen).unwrap());
+
+        app
+    }
+
+    fn send_move(app: &mut App, from: &str, to: &str) {
+        app.world.send_event(PieceMoveEvent {
+            piece_move: PieceMove::new(
+                TilePos::from_algebraic(from).unwrap(),
+                TilePos::from_algebraic(to).unwrap(),
+            ),
+            entity: Entity::PLACEHOLDER,
+        });
+        app.update();
+    }
+
+    fn turns_changed(app: &mut App) -> Vec<Player> {
+        app.world
+            .resource_mut::<Events<TurnChangedEvent>>()
+            .drain()
+            .map(|ev| ev.new_player)
+            .collect()
+    }
+
+    #[test]
+    fn turn_changed_once_per_move() {
+        let mut app = core_app("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1");
+
+        send_move(&mut app, "e2", "e4");
+        assert_eq!(turns_changed(&mut app), [Player::Black]);
+
+        send_move(&mut app, "e7", "e5");
+        assert_eq!(turns_changed(&mut app), [Player::White]);
+
+        // A rejected move doesn't change the turn
+        send_move(&mut app, "e4", "e5");
+        assert_eq!(turns_changed(&mut app), []);
+    }
+
+    #[test]
+    fn piece_captured_once_per_capture() {
+        let mut app = core_app("rnbqkbnr/ppp1pppp/8/3p4/4P3/8/PPPP1PPP/RNBQKBNR w KQkq d6 0 2");
+        let captures = |app: &mut App| {
+            app.world
+                .resource_mut::<Events<PieceCapturedEvent>>()
+                .drain()
+                .map(|ev| (ev.piece, ev.at.to_algebraic(), ev.by))
+                .collect::<Vec<_>>()
+        };
+
+        send_move(&mut app, "g1", "f3");
+        assert_eq!(captures(&mut app), []);
+
+        send_move(&mut app, "d5", "e4");
+        assert_eq!(
+            captures(&mut app),
+            [(Piece::WPawn, String::from("e4"), Player::Black)]
+        );
+
+        // En passant captures the pawn on its own square, rather than where the capturing pawn lands
+        send_move(&mut app, "d2", "d4");
+        send_move(&mut app, "e4", "d3");
+        assert_eq!(
+            captures(&mut app),
+            [(Piece::WPawn, String::from("d4"), Player::Black)]
+        );
+    }
+
+    #[test]
+    fn captured_sprite_is_despawned() {
+        let mut app = core_app("rnbqkbnr/ppp1pppp/8/3p4/4P3/8/PPPP1PPP/RNBQKBNR w KQkq d6 0 2");
+        app.add_systems(
+            Update,
+            piece_captured_event_reader.after(piece_move_event_reader),
+        );
+
+        // Give every piece an entity, like its sprite
+        let occupied = app.world.resource::<Board>().occupancy();
+        for tile_pos in occupied.iter() {
+            let entity = app.world.spawn_empty().id();
+            app.world
+                .resource_mut::<Board>()
+                .set_entity(tile_pos, Some(entity));
+        }
+        let captured = app
+            .world
+            .resource::<Board>()
+            .get_entity(TilePos::from_algebraic("d5").unwrap())
+            .unwrap();
+        let entity_count = app.world.entities().len();
+
+        send_move(&mut app, "e4", "d5");
+
+        assert_eq!(app.world.entities().len(), entity_count - 1);
+        assert!(app.world.get_entity(captured).is_none());
+    }
+
+    #[test]
+    fn drag_gate() {
+        let board = Board::default();
+        let e2 = TilePos::from_algebraic("e2").unwrap();
+        let e7 = TilePos::from_algebraic("e7").unwrap();
+        let e4 = TilePos::from_algebraic("e4").unwrap();
+        let no_premove = Premove::default();
+        let analysis_off = AnalysisMode::default();
+
+        // Only the side to move can drag, and only its own pieces
+        assert!(can_drag_piece(
+            &board,
+            e2,
+            &no_premove,
+            &analysis_off,
+            GameState::Playing
+        ));
+        assert!(!can_drag_piece(
+            &board,
+            e7,
+            &no_premove,
+            &analysis_off,
+            GameState::Playing
+        ));
+        assert!(!can_drag_piece(
+            &board,
+            e4,
+            &no_premove,
+            &analysis_off,
+            GameState::Playing
+        ));
+
+        // Nothing can be dragged once the game is over
+        assert!(!can_drag_piece(
+            &board,
+            e2,
+            &no_premove,
+            &analysis_off,
+            GameState::Over
+        ));
 
-        let (x, y) = board_to_pixel_coords(ev.piece_move.to.file, ev.piece_move.to.rank);
+        // In analysis mode either side can be dragged, but still not after the game has ended
+        let analysis_on = AnalysisMode { enabled: true };
+        assert!(can_drag_piece(
+            &board,
+            e7,
+            &no_premove,
+            &analysis_on,
+            GameState::Playing
+        ));
+        assert!(!can_drag_piece(
+            &board,
+            e7,
+            &no_premove,
+            &analysis_on,
+            GameState::Over
+        ));
 
-        transform.translation = Vec3::new(x, y, 1.);
+        // Black facing the computer can drag their pieces during White's turn to queue a premove
+        let premove = Premove {
+            piece_move: None,
+            player: Some(Player::Black),
+        };
+        assert!(can_drag_piece(
+            &board,
+            e7,
+            &premove,
+            &analysis_off,
+            GameState::Playing
+        ));
+        assert!(!can_drag_piece(
+            &board,
+            e7,
+            &premove,
+            &analysis_off,
+            GameState::Over
+        ));
     }
 }
@@ -0,0 +1,790 @@
+use crate::{
+    attack_tables::{
+        get_diagonal_attacks, get_king_attacks, get_knight_attacks, get_orthogonal_attacks,
+        SLIDING_DIRS,
+    },
+    bitboard::BitBoard,
+    board::{Board, Player, TilePos, CASTLING_KING_RANKS, CASTLING_ROOK_RANKS},
+    piece::{CastleSide, MoveFlag, Piece, PieceMove, PieceType, PROMOTION_PIECE_TYPES},
+};
+
+impl Board {
+    // Get the pseudo-legal moves for the piece at this position (moves may leave the king in check)
+    pub fn get_possible_moves(&self, from: TilePos) -> Vec<TilePos> {
+        let mut moves = match self.get_piece(from).piece_type() {
+            None => Vec::new(),
+            Some(PieceType::Pawn) => self.get_pawn_moves(from),
+            Some(PieceType::Knight) => self.get_knight_moves(from),
+            Some(PieceType::Bishop) => self.get_diagonal_moves(from),
+            Some(PieceType::Rook) => self.get_orthogonal_moves(from),
+            Some(PieceType::Queen) => {
+                let mut moves = self.get_orthogonal_moves(from);
+                moves.extend(self.get_diagonal_moves(from));
+                moves
+            }
+            Some(PieceType::King) => self.get_king_moves(from),
+        };
+
+        // Smaller boards have squares which can't be moved onto (a ray which leaves the board never comes back)
+        moves.retain(|&to| self.get_dimensions().contains(to));
+
+        moves
+    }
+
+    // Get the squares which the piece at this position can move to without leaving its own king in check
+    pub fn get_legal_moves(&mut self, from: TilePos) -> Vec<TilePos> {
+        let mut moves = self
+            .moves_from(from)
+            .into_iter()
+            .map(|piece_move| piece_move.to)
+            .collect::<Vec<_>>();
+
+        // Each promotion is a separate move onto the same square
+        moves.dedup();
+
+        moves
+    }
+
+    // Get the legal moves for the piece at this position, flagged and with one move for each promotion
+    pub fn moves_from(&mut self, from: TilePos) -> Vec<PieceMove> {
+        let Some(player) = self.get_piece(from).to_player() else {
+            return Vec::new();
+        };
+
+        // The king can move into an attack, and en passant removes a second piece which may have been shielding the king,
+        // so these are tested by making the move. Other pieces only need to stay in line with a pin and answer any check
+        let is_king = self.get_piece(from).piece_type() == Some(PieceType::King);
+        let evasion_mask = self.get_check_evasion_mask(player);
+        let pin_axis = self
+            .pinned_pieces(player)
+            .into_iter()
+            .find_map(|(pinned, axis)| (pinned == from).then_some(axis));
+        let is_en_passant = |to| {
+            self.get_piece(from).piece_type() == Some(PieceType::Pawn)
+                && Some(to) == self.get_en_passant_on_last_move()
+        };
+
+        let legal_moves = self
+            .get_possible_moves(from)
+            .into_iter()
+            .filter(|&to| {
+                if is_king || is_en_passant(to) {
+                    return self.is_legal_by_testing(from, to);
+                }
+
+                evasion_mask.is_none_or(|mask| mask.get_bit_at(to))
+                    && pin_axis.is_none_or(|axis| Self::is_along_axis(from, to, axis))
+            })
+            .collect::<Vec<_>>();
+
+        // Making every move is much slower, but can't miss a way of leaving the king in check
+        debug_assert_eq!(
+            legal_moves,
+            self.get_possible_moves(from)
+                .into_iter()
+                .filter(|&to| self.is_legal_by_testing(from, to))
+                .collect::<Vec<_>>(),
+            "Legal moves from {} don't match those found by making each move",
+            from.to_algebraic()
+        );
+
+        legal_moves
+            .into_iter()
+            .flat_map(|to| {
+                let piece_move = PieceMove::new(from, to);
+
+                if self.is_promotion_move(piece_move) {
+                    PROMOTION_PIECE_TYPES
+                        .map(|piece_type| piece_move.with_promotion(Piece::new(piece_type, player)))
+                        .to_vec()
+                } else {
+                    vec![piece_move]
+                }
+            })
+            .map(|piece_move| self.flag_move(piece_move))
+            .collect()
+    }
+
+    // Give this move the flag describing what it does in this position
+    pub fn flag_move(&self, piece_move: PieceMove) -> PieceMove {
+        piece_move.with_flag(self.get_move_flag(piece_move))
+    }
+
+    // Castling onto a rook is checked first, since the king lands on a square holding its own piece
+    pub fn get_move_flag(&self, piece_move: PieceMove) -> MoveFlag {
+        let PieceMove { from, to, .. } = piece_move;
+        let piece = self.get_piece(from);
+        let is_pawn = piece.piece_type() == Some(PieceType::Pawn);
+
+        if let Some((king_move, _)) = self.get_castling_piece_moves(piece_move) {
+            MoveFlag::Castle(if king_move.to.rank == CASTLING_KING_RANKS.0 {
+                CastleSide::KingSide
+            } else {
+                CastleSide::QueenSide
+            })
+        } else if self.is_promotion_move(piece_move) {
+            // Pawns are promoted to a queen unless otherwise specified
+            let player = piece.to_player().unwrap_or_default();
+            MoveFlag::Promotion(
+                piece_move
+                    .get_promotion()
+                    .unwrap_or(Piece::new(PieceType::Queen, player)),
+            )
+        } else if is_pawn && to.rank != from.rank && Some(to) == self.get_en_passant_on_last_move()
+        {
+            MoveFlag::EnPassant
+        } else if !self.is_empty(to) {
+            MoveFlag::Capture
+        } else if self.double_pawn_move_check(piece, from)
+            && from.offset(2 * Self::get_vertical_dir(piece), 0) == Some(to)
+        {
+            MoveFlag::DoublePawnPush
+        } else {
+            MoveFlag::Quiet
+        }
+    }
+
+    // Making the move takes an en passant capture off the board as well,
+    // which catches both pawns leaving a rank and exposing the king to a rook or queen
+    fn is_legal_by_testing(&self, from: TilePos, to: TilePos) -> bool {
+        let Some(player) = self.get_piece(from).to_player() else {
+            return false;
+        };
+
+        let mut board = self.clone();
+        board.apply_move(PieceMove::new(from, to));
+
+        !board.is_in_check(player)
+    }
+
+    // The squares which a piece other than the king can move to while this player is in check, or None when not in check
+    // A single check is answered by taking the checking piece or blocking its line, but a double check can only be escaped by the king
+    pub fn get_check_evasion_mask(&self, player: Player) -> Option<BitBoard> {
+        let king_pos = self.get_king_pos(player)?;
+        let checkers = self.get_attackers(king_pos, player.opponent());
+
+        match checkers.count() {
+            0 => None,
+            1 => {
+                let mut mask = checkers;
+                let checker = checkers.iter().next()?;
+
+                // Knights and pawns can't be blocked, and are never lined up with the king beyond the next square
+                let (d_file, d_rank) = (
+                    checker.file as isize - king_pos.file as isize,
+                    checker.rank as isize - king_pos.rank as isize,
+                );
+                if d_file == 0 || d_rank == 0 || d_file.abs() == d_rank.abs() {
+                    let step = (d_file.signum(), d_rank.signum());
+                    for tile_pos in
+                        std::iter::successors(king_pos.offset(step.0, step.1), |tile_pos| {
+                            tile_pos.offset(step.0, step.1)
+                        })
+                        .take_while(|&tile_pos| tile_pos != checker)
+                    {
+                        mask.set_bit_at(tile_pos, true);
+                    }
+                }
+
+                Some(mask)
+            }
+            _ => Some(BitBoard::default()),
+        }
+    }
+
+    // Each piece which can't leave the line between its king and an enemy slider, with the direction to its king
+    pub fn pinned_pieces(&self, player: Player) -> Vec<(TilePos, (isize, isize))> {
+        let Some(king_pos) = self.get_king_pos(player) else {
+            return Vec::new();
+        };
+
+        let mut pins = Vec::new();
+        for (dir_index, &(d_file, d_rank)) in SLIDING_DIRS.iter().enumerate() {
+            let slider_type = if dir_index < 4 {
+                PieceType::Rook
+            } else {
+                PieceType::Bishop
+            };
+
+            // Find the first two pieces along this ray from the king
+            let mut pieces = std::iter::successors(king_pos.offset(d_file, d_rank), |tile_pos| {
+                tile_pos.offset(d_file, d_rank)
+            })
+            .filter(|&tile_pos| !self.is_empty(tile_pos));
+
+            let (Some(blocker), Some(pinner)) = (pieces.next(), pieces.next()) else {
+                continue;
+            };
+
+            let pinner_piece = self.get_piece(pinner);
+            let pinner_type = pinner_piece.piece_type();
+            if self.get_piece(blocker).to_player() == Some(player)
+                && pinner_piece.to_player() == Some(player.opponent())
+                && (pinner_type == Some(slider_type) || pinner_type == Some(PieceType::Queen))
+            {
+                pins.push((blocker, (-d_file, -d_rank)));
+            }
+        }
+
+        pins
+    }
+
+    // Whether the move stays on the line through its starting square in this direction
+    fn is_along_axis(from: TilePos, to: TilePos, (d_file, d_rank): (isize, isize)) -> bool {
+        let d_to_file = to.file as isize - from.file as isize;
+        let d_to_rank = to.rank as isize - from.rank as isize;
+
+        d_to_file * d_rank == d_to_rank * d_file
+    }
+
+    // Every legal move for the player to move, with a separate move for each promotion piece
+    pub fn get_all_legal_moves(&mut self) -> Vec<PieceMove> {
+        self.occupancy_of(self.player)
+            .iter()
+            .flat_map(|from| self.moves_from(from))
+            .collect()
+    }
+
+    pub fn get_pawn_moves(&self, from: TilePos) -> Vec<TilePos> {
+        let piece = self.get_piece(from);
+        let vertical_dir = Self::get_vertical_dir(piece);
+
+        let mut moves = Vec::new();
+
+        // Pawns can move forward onto empty squares, and twice as far on their first move
+        if let Some(forward) = from.offset(vertical_dir, 0) {
+            if self.is_empty(forward) {
+                moves.push(forward);
+
+                if self.double_pawn_move_check(piece, from) {
+                    if let Some(double_forward) = from.offset(2 * vertical_dir, 0) {
+                        if self.is_empty(double_forward) {
+                            moves.push(double_forward);
+                        }
+                    }
+                }
+            }
+        }
+
+        // Pawns can capture diagonally, including onto the en passant square
+        for attack in self.get_pawn_attacks(from) {
+            let attacked_piece = self.get_piece(attack);
+
+            if piece
+                .to_player()
+                .is_some_and(|player| attacked_piece.to_player() == Some(player.opponent()))
+                || Some(attack) == self.get_en_passant_on_last_move()
+            {
+                moves.push(attack);
+            }
+        }
+
+        moves
+    }
+
+    // The diagonal squares a pawn attacks, regardless of whether they are occupied
+    pub fn get_pawn_attacks(&self, from: TilePos) -> Vec<TilePos> {
+        let vertical_dir = Self::get_vertical_dir(self.get_piece(from));
+
+        [-1, 1]
+            .into_iter()
+            .filter_map(|horizontal_dir| from.offset(vertical_dir, horizontal_dir))
+            .collect()
+    }
+
+    pub fn get_knight_moves(&self, from: TilePos) -> Vec<TilePos> {
+        self.get_attack_table_moves(from, get_knight_attacks(from))
+    }
+
+    pub fn get_king_moves(&self, from: TilePos) -> Vec<TilePos> {
+        let mut moves = self.get_attack_table_moves(from, get_king_attacks(from));
+        moves.extend(self.get_castling_moves(from));
+
+        moves
+    }
+
+    pub fn get_orthogonal_moves(&self, from: TilePos) -> Vec<TilePos> {
+        self.get_attack_table_moves(from, get_orthogonal_attacks(from, self.occupancy()))
+    }
+
+    pub fn get_diagonal_moves(&self, from: TilePos) -> Vec<TilePos> {
+        self.get_attack_table_moves(from, get_diagonal_attacks(from, self.occupancy()))
+    }
+
+    // Move to any of the attacked squares, so long as they aren't occupied by a friendly piece
+    fn get_attack_table_moves(&self, from: TilePos, attacks: BitBoard) -> Vec<TilePos> {
+        match self.get_piece(from).to_player() {
+            Some(player) => (attacks & !self.occupancy_of(player)).iter().collect(),
+            // An empty square has no piece to move
+            None => Vec::new(),
+        }
+    }
+
+    // Castling moves are written as the king moving two squares, or onto its own rook if it would move any other distance
+    fn get_castling_moves(&self, from: TilePos) -> Vec<TilePos> {
+        let Some(player) = self.get_piece(from).to_player() else {
+            return Vec::new();
+        };
+
+        let home_file = self.get_home_file(player);
+        if from != TilePos::new(home_file, self.get_king_start_rank()) {
+            return Vec::new();
+        }
+
+        let enemy = player.opponent();
+        if self.is_attacked(from, enemy) {
+            return Vec::new();
+        }
+
+        let (king_side, queen_side) = self.get_castling_rights(player);
+        let (king_side_rook, queen_side_rook) = self.get_castling_rook_ranks();
+        let rook = Piece::new(PieceType::Rook, player);
+
+        // The ranks between two ranks, including both ends
+        let span = |a: usize, b: usize| a.min(b)..=a.max(b);
+
+        let mut moves = Vec::new();
+
+        for (has_right, rook_rank, king_to, rook_to) in [
+            (
+                king_side,
+                king_side_rook,
+                CASTLING_KING_RANKS.0,
+                CASTLING_ROOK_RANKS.0,
+            ),
+            (
+                queen_side,
+                queen_side_rook,
+                CASTLING_KING_RANKS.1,
+                CASTLING_ROOK_RANKS.1,
+            ),
+        ] {
+            if !has_right || self.get_piece(TilePos::new(home_file, rook_rank)) != rook {
+                continue;
+            }
+
+            // Every square the king or rook passes through must be empty, apart from the squares they start on
+            let is_path_empty = span(from.rank, king_to)
+                .chain(span(rook_rank, rook_to))
+                .all(|rank| {
+                    rank == from.rank
+                        || rank == rook_rank
+                        || self.is_empty(TilePos::new(home_file, rank))
+                });
+
+            // The king can't pass through check
+            let is_path_safe = span(from.rank, king_to)
+                .all(|rank| !self.is_attacked(TilePos::new(home_file, rank), enemy));
+
+            if is_path_empty && is_path_safe {
+                let to_rank = if from.rank.abs_diff(king_to) == 2 {
+                    king_to
+                } else {
+                    rook_rank
+                };
+
+                moves.push(TilePos::new(home_file, to_rank));
+            }
+        }
+
+        moves
+    }
+
+    pub fn is_in_check(&mut self, player: Player) -> bool {
+        match self.get_king_pos(player) {
+            Some(king_pos) => self.is_attacked(king_pos, player.opponent()),
+            None => false,
+        }
+    }
+
+    pub fn is_checkmate(&mut self, player: Player) -> bool {
+        self.is_in_check(player) && !self.has_any_legal_move(player)
+    }
+
+    pub fn is_stalemate(&mut self, player: Player) -> bool {
+        !self.is_in_check(player) && !self.has_any_legal_move(player)
+    }
+
+    pub fn has_any_legal_move(&mut self, player: Player) -> bool {
+        self.occupancy_of(player)
+            .iter()
+            .any(|from| !self.get_legal_moves(from).is_empty())
+    }
+
+    // Every piece belonging to the attacking player which could capture on this square
+    pub fn get_attackers(&self, tile_pos: TilePos, by: Player) -> BitBoard {
+        let attackers = |piece_type| self.positions[Piece::new(piece_type, by)];
+        let occupancy = self.occupancy();
+        let queens = attackers(PieceType::Queen);
+
+        // Attacking pawns are one square behind this square, from their own point of view
+        let pawn_dir = -Self::get_vertical_dir(Piece::new(PieceType::Pawn, by));
+        let pawns = attackers(PieceType::Pawn);
+        let mut pawn_attackers = BitBoard::default();
+        for from in [-1, 1]
+            .into_iter()
+            .filter_map(|horizontal_dir| tile_pos.offset(pawn_dir, horizontal_dir))
+            .filter(|&from| pawns.get_bit_at(from))
+        {
+            pawn_attackers.set_bit_at(from, true);
+        }
+
+        pawn_attackers
+            | (get_knight_attacks(tile_pos) & attackers(PieceType::Knight))
+            | (get_king_attacks(tile_pos) & attackers(PieceType::King))
+            | (get_orthogonal_attacks(tile_pos, occupancy) & (attackers(PieceType::Rook) | queens))
+            | (get_diagonal_attacks(tile_pos, occupancy) & (attackers(PieceType::Bishop) | queens))
+    }
+
+    // Check if any piece belonging to the attacking player could capture on this square
+    // Attacks are symmetric, so this looks outwards from the square for each type of attacker
+    pub fn is_attacked(&self, tile_pos: TilePos, by: Player) -> bool {
+        let attackers = |piece_type| self.positions[Piece::new(piece_type, by)];
+        let occupancy = self.occupancy();
+        let queens = attackers(PieceType::Queen);
+
+        // Attacking pawns are one square behind this square, from their own point of view
+        let pawn_dir = -Self::get_vertical_dir(Piece::new(PieceType::Pawn, by));
+        let pawns = attackers(PieceType::Pawn);
+        let is_attacked_by_pawn = [-1, 1]
+            .into_iter()
+            .filter_map(|horizontal_dir| tile_pos.offset(pawn_dir, horizontal_dir))
+            .any(|from| pawns.get_bit_at(from));
+
+        is_attacked_by_pawn
+            || !(get_knight_attacks(tile_pos) & attackers(PieceType::Knight)).is_empty()
+            || !(get_king_attacks(tile_pos) & attackers(PieceType::King)).is_empty()
+            || !(get_orthogonal_attacks(tile_pos, occupancy)
+                & (attackers(PieceType::Rook) | queens))
+                .is_empty()
+            || !(get_diagonal_attacks(tile_pos, occupancy)
+                & (attackers(PieceType::Bishop) | queens))
+                .is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pos(algebraic: &str) -> TilePos {
+        TilePos::from_algebraic(algebraic).unwrap()
+    }
+
+    #[test]
+    fn all_legal_moves_from_start() {
+        let mut board = Board::default();
+        let moves = board.get_all_legal_moves();
+
+        assert_eq!(moves.len(), 20);
+        assert!(moves.contains(&board.flag_move(PieceMove::new(pos("e2"), pos("e4")))));
+        assert!(moves.contains(&PieceMove::new(pos("g1"), pos("f3"))));
+    }
+
+    fn sorted(mut squares: Vec<TilePos>) -> Vec<TilePos> {
+        squares.sort_by_key(TilePos::to_index);
+        squares
+    }
+
+    #[test]
+    fn rook_pin() {
+        let mut board = Board::from_fen("4r1k1/8/8/8/8/8/4N3/4K3 w - - 0 1").unwrap();
+
+        assert_eq!(board.pinned_pieces(Player::White), [(pos("e2"), (1, 0))]);
+        assert!(board.get_legal_moves(pos("e2")).is_empty());
+    }
+
+    #[test]
+    fn bishop_pin() {
+        let mut board = Board::from_fen("6k1/8/8/8/q7/8/2B5/3K4 w - - 0 1").unwrap();
+
+        let pins = board.pinned_pieces(Player::White);
+        assert_eq!(pins.len(), 1);
+        assert_eq!(pins[0].0, pos("c2"));
+
+        // The bishop can only move along the pin, up to and including the pinning queen
+        assert_eq!(
+            sorted(board.get_legal_moves(pos("c2"))),
+            sorted(vec![pos("b3"), pos("a4")])
+        );
+    }
+
+    #[test]
+    fn no_pin_with_second_blocker() {
+        let mut board = Board::from_fen("4r1k1/8/8/8/4B3/8/4N3/4K3 w - - 0 1").unwrap();
+
+        assert!(board.pinned_pieces(Player::White).is_empty());
+        assert_eq!(board.get_legal_moves(pos("e2")).len(), 6);
+    }
+
+    fn get_flag(board: &mut Board, from: &str, to: &str) -> MoveFlag {
+        board
+            .moves_from(pos(from))
+            .into_iter()
+            .find(|piece_move| piece_move.to == pos(to))
+            .unwrap()
+            .flag
+    }
+
+    #[test]
+    fn flagged_moves_from_square() {
+        let mut board = Board::from_fen("r3k2r/8/8/3pP3/8/8/8/R3K2R w KQkq d6 0 1").unwrap();
+
+        assert_eq!(get_flag(&mut board, "e5", "d6"), MoveFlag::EnPassant);
+        assert_eq!(get_flag(&mut board, "e5", "e6"), MoveFlag::Quiet);
+        assert_eq!(
+            get_flag(&mut board, "e1", "g1"),
+            MoveFlag::Castle(CastleSide::KingSide)
+        );
+        assert_eq!(get_flag(&mut board, "e1", "f1"), MoveFlag::Quiet);
+        assert_eq!(get_flag(&mut board, "a1", "a8"), MoveFlag::Capture);
+
+        let mut board = Board::from_fen("4k3/8/8/8/3p4/4P3/8/4K3 w - - 0 1").unwrap();
+        assert_eq!(get_flag(&mut board, "e3", "d4"), MoveFlag::Capture);
+        assert_eq!(get_flag(&mut board, "e3", "e4"), MoveFlag::Quiet);
+    }
+
+    #[test]
+    fn flags_applied_by_moves() {
+        let mut board = Board::from_fen("r3k2r/8/8/8/8/8/4P3/R3K2R b KQkq - 0 1").unwrap();
+
+        assert_eq!(
+            get_flag(&mut board, "e8", "c8"),
+            MoveFlag::Castle(CastleSide::QueenSide)
+        );
+        let outcome = board
+            .move_piece(PieceMove::new(pos("e8"), pos("c8")))
+            .unwrap();
+        assert_eq!(
+            outcome.castling_rook_move,
+            Some(PieceMove::new(pos("a8"), pos("d8")))
+        );
+
+        assert_eq!(get_flag(&mut board, "e2", "e4"), MoveFlag::DoublePawnPush);
+        assert_eq!(get_flag(&mut board, "e2", "e3"), MoveFlag::Quiet);
+        board
+            .move_piece(PieceMove::new(pos("e2"), pos("e4")))
+            .unwrap();
+        assert_eq!(board.get_en_passant_on_last_move(), Some(pos("e3")));
+
+        // Only pawn moves can be promotions
+        assert!(board
+            .move_piece(PieceMove::new(pos("d8"), pos("d1")).with_promotion(Piece::BQueen))
+            .is_err());
+    }
+
+    #[test]
+    fn promotion_flags() {
+        let mut board = Board::from_fen("4k3/8/8/8/8/8/1p6/R3K3 b - - 0 1").unwrap();
+
+        // Each promotion piece is its own move, both forwards and when capturing
+        let moves = board.moves_from(pos("b2"));
+        assert_eq!(moves.len(), 8);
+        assert!(moves
+            .iter()
+            .all(|piece_move| matches!(piece_move.flag, MoveFlag::Promotion(_))));
+
+        board
+            .move_piece(PieceMove::new(pos("b2"), pos("a1")).with_promotion(Piece::BKnight))
+            .unwrap();
+        assert_eq!(board.get_piece(pos("a1")), Piece::BKnight);
+    }
+
+    #[test]
+    fn en_passant_gives_discovered_check() {
+        // Both pawns leave the rank, so the rook sees Black's king, which only makes the capture a check
+        let mut board = Board::from_fen("8/8/8/1k1Pp2R/8/8/8/4K3 w - e6 0 1").unwrap();
+        assert!(board.get_legal_moves(pos("d5")).contains(&pos("e6")));
+
+        let outcome = board
+            .move_piece(PieceMove::new(pos("d5"), pos("e6")))
+            .unwrap();
+        assert!(outcome.check);
+        assert!(board.is_empty(pos("e5")));
+    }
+
+    #[test]
+    fn en_passant_exposes_own_king() {
+        let mut board = Board::from_fen("8/8/8/KPp4r/8/8/8/4k3 w - c6 0 1").unwrap();
+        assert!(!board.get_legal_moves(pos("b5")).contains(&pos("c6")));
+        assert!(board.get_legal_moves(pos("b5")).contains(&pos("b6")));
+    }
+
+    #[test]
+    fn perft_start_position() {
+        let mut board = Board::default();
+
+        assert_eq!(
+            (1..=4).map(|depth| board.perft(depth)).collect::<Vec<_>>(),
+            [20, 400, 8902, 197281]
+        );
+        assert_eq!(
+            board
+                .perft_divide(3)
+                .into_iter()
+                .map(|(_, count)| count)
+                .sum::<u64>(),
+            8902
+        );
+    }
+
+    #[test]
+    fn perft_check_evasions() {
+        // Checks along the rank and from pawns, with en passant captures which can expose the king
+        let mut board = Board::from_fen("8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1").unwrap();
+
+        assert_eq!(
+            (1..=4).map(|depth| board.perft(depth)).collect::<Vec<_>>(),
+            [14, 191, 2812, 43238]
+        );
+    }
+
+    #[test]
+    fn edge_file_pawn_captures() {
+        // A pawn on the a-file can only capture towards the b-file, and a b-file pawn can capture onto the a-file
+        let mut board = Board::from_fen("4k3/8/8/pp6/P1P5/8/8/4K3 w - - 0 1").unwrap();
+        assert_eq!(
+            sorted(board.get_legal_moves(pos("a4"))),
+            sorted(vec![pos("b5")])
+        );
+        assert_eq!(
+            sorted(board.get_legal_moves(pos("c4"))),
+            sorted(vec![pos("b5"), pos("c5")])
+        );
+
+        let mut board = Board::from_fen("4k3/8/8/1p6/P7/8/8/4K3 b - - 0 1").unwrap();
+        assert!(board.get_legal_moves(pos("b5")).contains(&pos("a4")));
+    }
+
+    #[test]
+    fn pawn_reaching_eighth_rank_promotes() {
+        let mut board = Board::from_fen("1r2k3/P7/8/8/8/8/8/4K3 w - - 0 1").unwrap();
+        let moves = board.moves_from(pos("a7"));
+
+        // Both the push to a8 and the capture on b8 give one move for each promotion piece
+        for to in ["a8", "b8"] {
+            let promotions = moves
+                .iter()
+                .filter(|piece_move| piece_move.to == pos(to))
+                .map(|piece_move| piece_move.flag)
+                .collect::<Vec<_>>();
+            assert_eq!(
+                promotions,
+                [Piece::WQueen, Piece::WRook, Piece::WBishop, Piece::WKnight]
+                    .map(MoveFlag::Promotion),
+                "{to}"
+            );
+        }
+    }
+
+    #[test]
+    fn known_stalemate() {
+        for fen in [
+            "7k/5Q2/6K1/8/8/8/8/8 b - - 0 1",
+            "k7/P7/1K6/8/8/8/8/8 b - - 0 1",
+        ] {
+            let mut board = Board::from_fen(fen).unwrap();
+
+            assert!(board.is_stalemate(Player::Black), "{fen}");
+            assert!(!board.is_checkmate(Player::Black), "{fen}");
+        }
+    }
+
+    #[test]
+    fn near_stalemate_with_one_legal_move() {
+        // The king can't move, but the pawn can still be pushed
+        let mut board = Board::from_fen("k7/P7/1K5p/8/8/8/8/8 b - - 0 1").unwrap();
+
+        assert!(!board.is_stalemate(Player::Black));
+        assert_eq!(
+            board.get_all_legal_moves(),
+            [PieceMove::new(pos("h6"), pos("h5"))]
+        );
+    }
+
+    #[test]
+    fn castling_destinations() {
+        let castles = |fen: &str, king: &str| {
+            let mut board = Board::from_fen(fen).unwrap();
+            let moves = board.get_legal_moves(pos(king));
+
+            ["c1", "g1", "c8", "g8"]
+                .into_iter()
+                .filter(|to| moves.contains(&pos(to)))
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            castles("r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 0 1", "e1"),
+            ["c1", "g1"]
+        );
+        assert_eq!(
+            castles("r3k2r/8/8/8/8/8/8/R3K2R b KQkq - 0 1", "e8"),
+            ["c8", "g8"]
+        );
+
+        // Blocked by a piece between the king and rook
+        assert_eq!(
+            castles("r3k2r/8/8/8/8/8/8/RN2K1NR w KQkq - 0 1", "e1"),
+            Vec::<&str>::new()
+        );
+        assert_eq!(
+            castles("r2qk1nr/8/8/8/8/8/8/R3K2R b KQkq - 0 1", "e8"),
+            Vec::<&str>::new()
+        );
+
+        // Revoked castling rights
+        assert_eq!(castles("r3k2r/8/8/8/8/8/8/R3K2R w Qk - 0 1", "e1"), ["c1"]);
+        assert_eq!(castles("r3k2r/8/8/8/8/8/8/R3K2R b Qk - 0 1", "e8"), ["g8"]);
+
+        // The king can't castle out of check, through an attacked square, or into check
+        assert_eq!(
+            castles("r3k2r/8/8/8/8/8/8/R3K2r w KQkq - 0 1", "e1"),
+            Vec::<&str>::new()
+        );
+        assert_eq!(
+            castles("r3k2r/8/8/8/2b5/8/8/R3K2R w KQkq - 0 1", "e1"),
+            ["c1"]
+        );
+        assert_eq!(
+            castles("r3k2r/8/8/8/8/8/6r1/R3K2R w KQkq - 0 1", "e1"),
+            ["c1"]
+        );
+    }
+
+    #[test]
+    fn slider_attacks_are_blocked() {
+        let board = Board::from_fen("4k3/8/8/8/8/8/8/R3K3 w - - 0 1").unwrap();
+        assert!(board.is_attacked(pos("a8"), Player::White));
+        assert!(board.is_attacked(pos("d1"), Player::White));
+
+        // A piece in between stops the rook's attack past it, but is attacked itself
+        let board = Board::from_fen("4k3/8/8/8/p7/8/8/R3K3 w - - 0 1").unwrap();
+        assert!(board.is_attacked(pos("a4"), Player::White));
+        assert!(!board.is_attacked(pos("a5"), Player::White));
+        assert!(!board.is_attacked(pos("a8"), Player::White));
+        assert!(board.get_attackers(pos("a8"), Player::White).is_empty());
+    }
+
+    #[test]
+    fn attacked_by_pawn_knight_and_king() {
+        let board = Board::from_fen("4k3/8/8/3p4/8/5N2/8/4K3 w - - 0 1").unwrap();
+
+        // Pawns only attack diagonally forwards
+        assert!(board.is_attacked(pos("c4"), Player::Black));
+        assert!(board.is_attacked(pos("e4"), Player::Black));
+        assert!(!board.is_attacked(pos("d4"), Player::Black));
+        assert!(!board.is_attacked(pos("c6"), Player::Black));
+
+        assert!(board.is_attacked(pos("g5"), Player::White));
+        assert!(board.is_attacked(pos("d2"), Player::White));
+        assert!(!board.is_attacked(pos("f4"), Player::White));
+
+        // Both the king and the knight attack d2
+        let attackers = board
+            .get_attackers(pos("d2"), Player::White)
+            .iter()
+            .collect::<Vec<_>>();
+        assert_eq!(sorted(attackers), sorted(vec![pos("e1"), pos("f3")]));
+    }
+}
@@ -0,0 +1,242 @@
+use bevy::{
+    prelude::*,
+    sprite::{MaterialMesh2dBundle, Mesh2dHandle},
+};
+use bevy_mod_picking::prelude::*;
+
+use crate::{
+    ai::{AiPlayer, AiSettings},
+    board::{Board, Player},
+    display::{board_to_pixel_coords, BoardLayout},
+    piece::{Piece, PieceMove, PieceMoveEvent, PieceType, TurnChangedEvent},
+    selection::SelectedPiece,
+    uci::UciOpponent,
+    undo_redo::UndoRedoEvent,
+};
+
+const PREMOVE_COLOUR: Color = Color::rgba(0.2, 0.4, 0.8, 0.5);
+
+// A move queued while the computer is thinking, which is played as soon as it is the queuing player's turn
+#[derive(Resource, Default)]
+pub struct Premove {
+    pub piece_move: Option<PieceMove>,
+    // The player facing the computer, since both players share the mouse otherwise
+    pub player: Option<Player>,
+}
+
+impl Premove {
+    // Whether the player who can premove is waiting for their turn
+    pub fn is_waiting(&self, board: &Board) -> bool {
+        self.player == Some(board.player.opponent())
+    }
+
+    // Queue a move of one of the waiting player's pieces, replacing any which was already queued
+    pub fn queue(&mut self, board: &Board, piece_move: PieceMove) -> bool {
+        if !self.is_waiting(board)
+            || board.get_piece(piece_move.from).to_player() != self.player
+            || piece_move.from == piece_move.to
+        {
+            return false;
+        }
+
+        self.piece_move = Some(piece_move);
+
+        true
+    }
+
+    // Take the queued move if it is now legal, discarding it otherwise
+    pub fn take_legal(&mut self, board: &mut Board) -> Option<PieceMove> {
+        let mut piece_move = self.piece_move.take()?;
+
+        // There is no time to choose a promotion piece, so queued promotions become queens
+        if board.is_promotion_move(piece_move) && piece_move.get_promotion().is_none() {
+            piece_move = piece_move.with_promotion(Piece::new(PieceType::Queen, board.player));
+        }
+
+        board
+            .get_all_legal_moves()
+            .contains(&piece_move)
+            .then_some(piece_move)
+    }
+
+    pub fn cancel(&mut self) {
+        self.piece_move = None;
+    }
+}
+
+// Keep track of which player is facing the computer, dropping the premove if there is no longer a computer opponent
+pub fn update_premove_player(
+    ai_player: Res<AiPlayer>,
+    ai_settings: Res<AiSettings>,
+    uci_opponent: Option<Res<UciOpponent>>,
+    mut premove: ResMut<Premove>,
+) {
+    let player = if ai_settings.enabled {
+        Some(ai_player.0.opponent())
+    } else {
+        uci_opponent.map(|uci_opponent| uci_opponent.player.opponent())
+    };
+
+    if premove.player != player {
+        premove.player = player;
+        premove.cancel();
+    }
+}
+
+// Play the queued move once the turn comes back to the player who queued it, unless the position was changed by undo or redo
+pub fn play_premove(
+    mut ev_turn_changed: EventReader<TurnChangedEvent>,
+    mut ev_undo_redo: EventReader<UndoRedoEvent>,
+    mut ev_piece_move: EventWriter<PieceMoveEvent>,
+    board: Res<Board>,
+    mut premove: ResMut<Premove>,
+) {
+    if ev_undo_redo.read().count() > 0 {
+        premove.cancel();
+    }
+
+    let Some(ev) = ev_turn_changed.read().last() else {
+        return;
+    };
+
+    if premove.piece_move.is_none() || premove.player != Some(ev.new_player) {
+        return;
+    }
+
+    if let Some(piece_move) = premove.take_legal(&mut board.clone()) {
+        if let Some(entity) = board.get_entity(piece_move.from) {
+            ev_piece_move.send(PieceMoveEvent { piece_move, entity });
+        }
+    }
+}
+
+// Cancel the premove, and any piece selected for one, by right-clicking
+pub fn cancel_premove_input(
+    mouse: Res<ButtonInput<MouseButton>>,
+    board: Res<Board>,
+    mut premove: ResMut<Premove>,
+    mut selected_piece: ResMut<SelectedPiece>,
+) {
+    if !mouse.just_pressed(MouseButton::Right) {
+        return;
+    }
+
+    premove.cancel();
+
+    let is_premove_selection = selected_piece
+        .selected
+        .is_some_and(|from| board.get_piece(from).to_player() != Some(board.player));
+    if is_premove_selection {
+        selected_piece.selected = None;
+    }
+}
+
+// Part of the tint on the squares of the queued move
+#[derive(Component)]
+pub struct PremoveMarker;
+
+pub fn display_premove(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    premove: Res<Premove>,
+    layout: Res<BoardLayout>,
+    marker_query: Query<Entity, With<PremoveMarker>>,
+) {
+    if !premove.is_changed() && !layout.is_changed() {
+        return;
+    }
+
+    for marker in marker_query.iter() {
+        commands.entity(marker).despawn();
+    }
+
+    let Some(piece_move) = premove.piece_move else {
+        return;
+    };
+
+    let material = materials.add(PREMOVE_COLOUR);
+
+    for tile_pos in [piece_move.from, piece_move.to] {
+        let (x, y) = board_to_pixel_coords(tile_pos.file, tile_pos.rank, &layout);
+
+        // Drawn between the squares and the pieces
+        commands.spawn((
+            MaterialMesh2dBundle {
+                mesh: Mesh2dHandle(
+                    meshes.add(Rectangle::new(layout.piece_size, layout.piece_size)),
+                ),
+                material: material.clone(),
+                transform: Transform::from_xyz(x, y, 0.25),
+                ..default()
+            },
+            Pickable::IGNORE,
+            PremoveMarker,
+        ));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::TilePos;
+
+    fn piece_move(from: &str, to: &str) -> PieceMove {
+        PieceMove::new(
+            TilePos::from_algebraic(from).unwrap(),
+            TilePos::from_algebraic(to).unwrap(),
+        )
+    }
+
+    // White is facing the computer, which is about to reply to 1. e4
+    fn waiting_for_black() -> (Board, Premove) {
+        let mut board = Board::default();
+        board.move_piece(piece_move("e2", "e4")).unwrap();
+
+        let premove = Premove {
+            piece_move: None,
+            player: Some(Player::White),
+        };
+
+        (board, premove)
+    }
+
+    #[test]
+    fn queue_only_while_waiting() {
+        let (board, mut premove) = waiting_for_black();
+        assert!(premove.is_waiting(&board));
+
+        // Only the waiting player's own pieces can be queued
+        assert!(!premove.queue(&board, piece_move("e7", "e5")));
+        assert!(premove.queue(&board, piece_move("d2", "d4")));
+        assert_eq!(premove.piece_move, Some(piece_move("d2", "d4")));
+
+        // Once it is White's turn, moves are made straight away instead
+        let mut premove = Premove {
+            piece_move: None,
+            player: Some(Player::White),
+        };
+        assert!(!premove.queue(&Board::default(), piece_move("d2", "d4")));
+    }
+
+    #[test]
+    fn queued_move_played_after_reply() {
+        let (mut board, mut premove) = waiting_for_black();
+        premove.queue(&board, piece_move("d2", "d4"));
+
+        board.move_piece(piece_move("e7", "e5")).unwrap();
+        assert_eq!(premove.take_legal(&mut board), Some(piece_move("d2", "d4")));
+        assert_eq!(premove.piece_move, None);
+    }
+
+    #[test]
+    fn queued_move_discarded_when_illegal() {
+        let (mut board, mut premove) = waiting_for_black();
+        premove.queue(&board, piece_move("e4", "e5"));
+
+        // Black's reply blocks the pawn
+        board.move_piece(piece_move("e7", "e5")).unwrap();
+        assert_eq!(premove.take_legal(&mut board), None);
+        assert_eq!(premove.piece_move, None);
+    }
+}
@@ -0,0 +1,129 @@
+use bevy::prelude::*;
+use bevy_mod_picking::prelude::*;
+
+use crate::{
+    board::Board,
+    display::{board_to_pixel_coords, get_piece_texture_atlas, BoardLayout},
+    piece::{Piece, PieceMove, PieceMoveEvent, PROMOTION_PIECE_TYPES},
+};
+
+// A pawn move onto the final file which is waiting for the player to choose a piece
+#[derive(Resource, Default)]
+pub struct PendingPromotion {
+    pub promotion: Option<(PieceMove, Entity)>,
+}
+
+#[derive(Component)]
+pub struct PromotionChoice(pub Piece);
+
+// Every entity which makes up the choice overlay, so that it can be cleaned up
+#[derive(Component)]
+pub struct PromotionOverlay;
+
+pub fn display_promotion_choices(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mut texture_atlas_layouts: ResMut<Assets<TextureAtlasLayout>>,
+    board: Res<Board>,
+    pending_promotion: Res<PendingPromotion>,
+    overlay_query: Query<Entity, With<PromotionOverlay>>,
+    layout: Res<BoardLayout>,
+) {
+    if !pending_promotion.is_changed() && !layout.is_changed() {
+        return;
+    }
+
+    for entity in overlay_query.iter() {
+        commands.entity(entity).despawn();
+    }
+
+    let Some((piece_move, _)) = pending_promotion.promotion else {
+        return;
+    };
+
+    let (texture, texture_atlas_layout) =
+        get_piece_texture_atlas(&asset_server, &mut texture_atlas_layouts);
+
+    let pawn = board.get_piece(piece_move.from);
+    let Some(player) = pawn.to_player() else {
+        return;
+    };
+    let choices = PROMOTION_PIECE_TYPES.map(|piece_type| Piece::new(piece_type, player));
+
+    // Stack the choices from the promotion square back towards the centre of the board
+    let vertical_dir = -Board::get_vertical_dir(pawn);
+    for (i, choice) in choices.into_iter().enumerate() {
+        let Some(tile_pos) = piece_move.to.offset(vertical_dir * i as isize, 0) else {
+            continue;
+        };
+        let (x, y) = board_to_pixel_coords(tile_pos.file, tile_pos.rank, &layout);
+
+        commands.spawn((
+            SpriteBundle {
+                sprite: Sprite {
+                    color: Color::rgba(0.8, 0.8, 0.8, 0.9),
+                    custom_size: Some(Vec2::splat(layout.piece_size)),
+                    ..default()
+                },
+                transform: Transform::from_xyz(x, y, 20.),
+                ..default()
+            },
+            Pickable::IGNORE,
+            PromotionOverlay,
+        ));
+
+        commands.spawn((
+            SpriteSheetBundle {
+                texture: texture.clone(),
+                atlas: TextureAtlas {
+                    layout: texture_atlas_layout.clone(),
+                    index: choice.into(),
+                },
+                transform: Transform::from_scale(Vec3::splat(layout.get_piece_scale()))
+                    .with_translation(Vec3::new(x, y, 21.)),
+                ..default()
+            },
+            PromotionChoice(choice),
+            PromotionOverlay,
+        ));
+    }
+}
+
+// Send the deferred move once a piece is chosen, or snap the pawn back if anything else is clicked
+pub fn promotion_choice_click(
+    mut click_er: EventReader<Pointer<Click>>,
+    choice_query: Query<&PromotionChoice>,
+    mut transform_query: Query<&mut Transform>,
+    mut pending_promotion: ResMut<PendingPromotion>,
+    mut ev_piece_move: EventWriter<PieceMoveEvent>,
+    layout: Res<BoardLayout>,
+) {
+    // The click which started the promotion shouldn't also choose or cancel it
+    if pending_promotion.is_changed() {
+        click_er.clear();
+        return;
+    }
+
+    for click in click_er.read() {
+        let Some((piece_move, entity)) = pending_promotion.promotion else {
+            return;
+        };
+
+        // Releasing the drag which moved the pawn also counts as clicking it
+        if click.target == entity {
+            continue;
+        }
+
+        if let Ok(PromotionChoice(choice)) = choice_query.get(click.target) {
+            ev_piece_move.send(PieceMoveEvent {
+                piece_move: piece_move.with_promotion(*choice),
+                entity,
+            });
+        } else if let Ok(mut transform) = transform_query.get_mut(entity) {
+            let (x, y) = board_to_pixel_coords(piece_move.from.file, piece_move.from.rank, &layout);
+            transform.translation = Vec3::new(x, y, 1.);
+        }
+
+        pending_promotion.promotion = None;
+    }
+}
@@ -0,0 +1,483 @@
+use std::{error::Error, fmt};
+
+use bevy::prelude::*;
+
+use crate::{
+    board::{Board, Player},
+    evaluate::evaluate,
+    game_over::{GameEndReason, GameOverEvent},
+    piece::TurnChangedEvent,
+    premove::Premove,
+};
+
+const BUTTON_FONT_SIZE: f32 = 28.;
+const BUTTON_MARGIN: f32 = 20.;
+const BUTTON_WIDTH: f32 = 160.;
+const BUTTON_COLOUR: Color = Color::rgba(0.15, 0.15, 0.15, 0.9);
+
+#[derive(Event)]
+pub struct ResignEvent {
+    pub player: Player,
+}
+
+#[derive(Event)]
+pub struct DrawOfferEvent {
+    pub player: Player,
+}
+
+#[derive(Event)]
+pub struct DrawResponseEvent {
+    pub player: Player,
+    pub accepted: bool,
+}
+
+// Sent to end the game with a threefold repetition or fifty move draw
+#[derive(Event)]
+pub struct DrawClaimEvent {
+    pub player: Player,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum AgreementError {
+    GameOver,
+    DrawAlreadyOffered(Player),
+    NoDrawOffered,
+    OwnDrawOffer(Player),
+    NoClaimableDraw,
+}
+
+impl fmt::Display for AgreementError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Could not end the game by agreement:")?;
+
+        match self {
+            AgreementError::GameOver => write!(f, "The game is already over"),
+            AgreementError::DrawAlreadyOffered(player) => {
+                write!(f, "{player:?} has already offered a draw")
+            }
+            AgreementError::NoDrawOffered => write!(f, "No draw has been offered"),
+            AgreementError::OwnDrawOffer(player) => {
+                write!(f, "{player:?} can not respond to their own draw offer")
+            }
+            AgreementError::NoClaimableDraw => write!(f, "There is no draw to claim"),
+        }
+    }
+}
+
+impl Error for AgreementError {}
+
+// Whether the game can still be resigned or drawn, and who is waiting for an answer to a draw offer
+#[derive(Resource, Clone, Copy, PartialEq, Eq, Default, Debug)]
+pub enum AgreementState {
+    #[default]
+    Playing,
+    DrawOffered(Player),
+    Over,
+}
+
+impl AgreementState {
+    pub fn resign(&mut self, player: Player) -> Result<GameOverEvent, AgreementError> {
+        if *self == AgreementState::Over {
+            return Err(AgreementError::GameOver);
+        }
+
+        *self = AgreementState::Over;
+
+        Ok(GameOverEvent {
+            winner: Some(player.opponent()),
+            reason: GameEndReason::Resignation,
+        })
+    }
+
+    pub fn offer_draw(&mut self, player: Player) -> Result<(), AgreementError> {
+        match *self {
+            AgreementState::Over => Err(AgreementError::GameOver),
+            AgreementState::DrawOffered(offered_by) => {
+                Err(AgreementError::DrawAlreadyOffered(offered_by))
+            }
+            AgreementState::Playing => {
+                *self = AgreementState::DrawOffered(player);
+                Ok(())
+            }
+        }
+    }
+
+    // Accepting a draw ends the game, whereas declining it carries on playing
+    pub fn respond_to_draw(
+        &mut self,
+        player: Player,
+        accepted: bool,
+    ) -> Result<Option<GameOverEvent>, AgreementError> {
+        match *self {
+            AgreementState::Over => Err(AgreementError::GameOver),
+            AgreementState::Playing => Err(AgreementError::NoDrawOffered),
+            AgreementState::DrawOffered(offered_by) if offered_by == player => {
+                Err(AgreementError::OwnDrawOffer(player))
+            }
+            AgreementState::DrawOffered(_) if accepted => {
+                *self = AgreementState::Over;
+
+                Ok(Some(GameOverEvent {
+                    winner: None,
+                    reason: GameEndReason::Agreement,
+                }))
+            }
+            AgreementState::DrawOffered(_) => {
+                *self = AgreementState::Playing;
+                Ok(None)
+            }
+        }
+    }
+
+    pub fn claim_draw(&mut self, board: &Board) -> Result<GameOverEvent, AgreementError> {
+        if *self == AgreementState::Over {
+            return Err(AgreementError::GameOver);
+        }
+
+        let reason = board
+            .get_claimable_draw()
+            .ok_or(AgreementError::NoClaimableDraw)?;
+
+        *self = AgreementState::Over;
+
+        Ok(GameOverEvent {
+            winner: None,
+            reason,
+        })
+    }
+
+    // The opponent can only accept a draw until they make their move
+    pub fn lapse_draw_offer(&mut self, player_to_move: Player) {
+        if *self == AgreementState::DrawOffered(player_to_move) {
+            *self = AgreementState::Playing;
+        }
+    }
+}
+
+// The computer accepts a draw unless it thinks it is winning
+pub fn get_computer_draw_response(board: &Board, computer: Player) -> bool {
+    let score = match computer {
+        Player::White => evaluate(board),
+        Player::Black => -evaluate(board),
+    };
+
+    score <= 0
+}
+
+#[derive(Component, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum AgreementButton {
+    Resign,
+    OfferDraw,
+    AcceptDraw,
+    DeclineDraw,
+    ClaimDraw,
+}
+
+impl AgreementButton {
+    fn get_text(self) -> &'static str {
+        match self {
+            AgreementButton::Resign => "Resign",
+            AgreementButton::OfferDraw => "Offer draw",
+            AgreementButton::AcceptDraw => "Accept draw",
+            AgreementButton::DeclineDraw => "Decline draw",
+            AgreementButton::ClaimDraw => "Claim draw",
+        }
+    }
+
+    // Answering a draw offer is only possible while one is waiting, and claiming a draw once the position allows it
+    fn is_visible(self, state: AgreementState, can_claim_draw: bool) -> bool {
+        match self {
+            AgreementButton::Resign => state != AgreementState::Over,
+            AgreementButton::OfferDraw => state == AgreementState::Playing,
+            AgreementButton::AcceptDraw | AgreementButton::DeclineDraw => {
+                matches!(state, AgreementState::DrawOffered(_))
+            }
+            AgreementButton::ClaimDraw => state != AgreementState::Over && can_claim_draw,
+        }
+    }
+}
+
+pub fn spawn_agreement_buttons(mut commands: Commands) {
+    commands
+        .spawn(NodeBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                flex_direction: FlexDirection::Column,
+                row_gap: Val::Px(BUTTON_MARGIN / 2.),
+                top: Val::Percent(45.),
+                right: Val::Px(BUTTON_MARGIN),
+                width: Val::Px(BUTTON_WIDTH),
+                ..default()
+            },
+            ..default()
+        })
+        .with_children(|parent| {
+            for button in [
+                AgreementButton::Resign,
+                AgreementButton::OfferDraw,
+                AgreementButton::AcceptDraw,
+                AgreementButton::DeclineDraw,
+                AgreementButton::ClaimDraw,
+            ] {
+                parent
+                    .spawn((
+                        ButtonBundle {
+                            style: Style {
+                                justify_content: JustifyContent::Center,
+                                display: if button.is_visible(AgreementState::default(), false) {
+                                    Display::Flex
+                                } else {
+                                    Display::None
+                                },
+                                ..default()
+                            },
+                            background_color: BUTTON_COLOUR.into(),
+                            ..default()
+                        },
+                        button,
+                    ))
+                    .with_children(|parent| {
+                        parent.spawn(TextBundle::from_section(
+                            button.get_text(),
+                            TextStyle {
+                                font_size: BUTTON_FONT_SIZE,
+                                color: Color::WHITE,
+                                ..default()
+                            },
+                        ));
+                    });
+            }
+        });
+}
+
+// Resign or offer a draw for the player facing the computer, or the player to move when two people are playing
+#[allow(clippy::too_many_arguments)]
+pub fn agreement_button_click(
+    interaction_query: Query<(&Interaction, &AgreementButton), Changed<Interaction>>,
+    board: Res<Board>,
+    premove: Res<Premove>,
+    state: Res<AgreementState>,
+    mut ev_resign: EventWriter<ResignEvent>,
+    mut ev_draw_offer: EventWriter<DrawOfferEvent>,
+    mut ev_draw_response: EventWriter<DrawResponseEvent>,
+    mut ev_draw_claim: EventWriter<DrawClaimEvent>,
+) {
+    let player = premove.player.unwrap_or(board.player);
+
+    for (interaction, button) in interaction_query.iter() {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+
+        match button {
+            AgreementButton::Resign => {
+                ev_resign.send(ResignEvent { player });
+            }
+            AgreementButton::OfferDraw => {
+                ev_draw_offer.send(DrawOfferEvent { player });
+            }
+            AgreementButton::AcceptDraw | AgreementButton::DeclineDraw => {
+                // The answer comes from whoever the draw was offered to
+                if let AgreementState::DrawOffered(offered_by) = *state {
+                    ev_draw_response.send(DrawResponseEvent {
+                        player: offered_by.opponent(),
+                        accepted: *button == AgreementButton::AcceptDraw,
+                    });
+                }
+            }
+            AgreementButton::ClaimDraw => {
+                ev_draw_claim.send(DrawClaimEvent { player });
+            }
+        }
+    }
+}
+
+pub fn resign_event_reader(
+    mut ev_resign: EventReader<ResignEvent>,
+    mut state: ResMut<AgreementState>,
+    mut ev_game_over: EventWriter<GameOverEvent>,
+) {
+    for ev in ev_resign.read() {
+        match state.resign(ev.player) {
+            Ok(game_over) => {
+                ev_game_over.send(game_over);
+            }
+            Err(e) => warn!("{e}"),
+        }
+    }
+}
+
+// Record the offer, with the computer answering straight away if it was offered to it
+pub fn draw_offer_event_reader(
+    mut ev_draw_offer: EventReader<DrawOfferEvent>,
+    mut state: ResMut<AgreementState>,
+    board: Res<Board>,
+    premove: Res<Premove>,
+    mut ev_draw_response: EventWriter<DrawResponseEvent>,
+) {
+    for ev in ev_draw_offer.read() {
+        if let Err(e) = state.offer_draw(ev.player) {
+            warn!("{e}");
+            continue;
+        }
+
+        info!("{:?} offers a draw", ev.player);
+
+        if premove.player == Some(ev.player) {
+            let computer = ev.player.opponent();
+
+            ev_draw_response.send(DrawResponseEvent {
+                player: computer,
+                accepted: get_computer_draw_response(&board, computer),
+            });
+        }
+    }
+}
+
+pub fn draw_response_event_reader(
+    mut ev_draw_response: EventReader<DrawResponseEvent>,
+    mut state: ResMut<AgreementState>,
+    mut ev_game_over: EventWriter<GameOverEvent>,
+) {
+    for ev in ev_draw_response.read() {
+        match state.respond_to_draw(ev.player, ev.accepted) {
+            Ok(Some(game_over)) => {
+                ev_game_over.send(game_over);
+            }
+            Ok(None) => info!("{:?} declines the draw", ev.player),
+            Err(e) => warn!("{e}"),
+        }
+    }
+}
+
+pub fn draw_claim_event_reader(
+    mut ev_draw_claim: EventReader<DrawClaimEvent>,
+    mut state: ResMut<AgreementState>,
+    board: Res<Board>,
+    mut ev_game_over: EventWriter<GameOverEvent>,
+) {
+    for ev in ev_draw_claim.read() {
+        match state.claim_draw(&board) {
+            Ok(game_over) => {
+                info!("{:?} claims a draw", ev.player);
+                ev_game_over.send(game_over);
+            }
+            Err(e) => warn!("{e}"),
+        }
+    }
+}
+
+// However the game ended, it can no longer be resigned or drawn
+pub fn agreement_game_over_reader(
+    mut ev_game_over: EventReader<GameOverEvent>,
+    mut state: ResMut<AgreementState>,
+) {
+    if ev_game_over.read().last().is_some() {
+        *state = AgreementState::Over;
+    }
+}
+
+pub fn agreement_turn_changed_reader(
+    mut ev_turn_changed: EventReader<TurnChangedEvent>,
+    mut state: ResMut<AgreementState>,
+) {
+    if let Some(ev) = ev_turn_changed.read().last() {
+        state.lapse_draw_offer(ev.new_player);
+    }
+}
+
+pub fn update_agreement_buttons(
+    state: Res<AgreementState>,
+    board: Res<Board>,
+    mut button_query: Query<(&AgreementButton, &mut Style)>,
+) {
+    if !state.is_changed() && !board.is_changed() {
+        return;
+    }
+
+    let can_claim_draw = board.get_claimable_draw().is_some();
+
+    for (button, mut style) in button_query.iter_mut() {
+        style.display = if button.is_visible(*state, can_claim_draw) {
+            Display::Flex
+        } else {
+            Display::None
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn offer_and_decline() {
+        let mut state = AgreementState::default();
+        assert_eq!(
+            state.respond_to_draw(Player::Black, true).map(|_| ()),
+            Err(AgreementError::NoDrawOffered)
+        );
+
+        state.offer_draw(Player::White).unwrap();
+        assert_eq!(
+            state.offer_draw(Player::Black),
+            Err(AgreementError::DrawAlreadyOffered(Player::White))
+        );
+        assert_eq!(
+            state.respond_to_draw(Player::White, true).map(|_| ()),
+            Err(AgreementError::OwnDrawOffer(Player::White))
+        );
+
+        assert_eq!(
+            state
+                .respond_to_draw(Player::Black, false)
+                .map(|game_over| game_over.is_none()),
+            Ok(true)
+        );
+        assert_eq!(state, AgreementState::Playing);
+    }
+
+    #[test]
+    fn offer_and_accept() {
+        let mut state = AgreementState::default();
+        state.offer_draw(Player::Black).unwrap();
+
+        let game_over = state.respond_to_draw(Player::White, true).unwrap().unwrap();
+        assert_eq!(
+            (game_over.winner, game_over.reason),
+            (None, GameEndReason::Agreement)
+        );
+        assert_eq!(state, AgreementState::Over);
+        assert_eq!(
+            state.offer_draw(Player::White),
+            Err(AgreementError::GameOver)
+        );
+    }
+
+    #[test]
+    fn offer_lapses_when_offerer_moves_again() {
+        let mut state = AgreementState::default();
+        state.offer_draw(Player::Black).unwrap();
+
+        state.lapse_draw_offer(Player::White);
+        assert_eq!(state, AgreementState::DrawOffered(Player::Black));
+
+        state.lapse_draw_offer(Player::Black);
+        assert_eq!(state, AgreementState::Playing);
+    }
+
+    #[test]
+    fn resign_only_once() {
+        let mut state = AgreementState::default();
+
+        let game_over = state.resign(Player::White).unwrap();
+        assert_eq!(
+            (game_over.winner, game_over.reason),
+            (Some(Player::Black), GameEndReason::Resignation)
+        );
+        assert_eq!(
+            state.resign(Player::Black).map(|_| ()),
+            Err(AgreementError::GameOver)
+        );
+    }
+}
@@ -0,0 +1,332 @@
+use std::{error::Error, fmt};
+
+use crate::{
+    board::{Board, Player, TilePos, CASTLING_ROOK_RANKS},
+    display::BOARD_SIZE,
+    piece::{Piece, PieceMove, PieceType},
+};
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum SanError {
+    InvalidMove(String),
+    IllegalMove(String),
+    AmbiguousMove(String),
+}
+
+impl fmt::Display for SanError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Could not parse move using SAN:")?;
+
+        match self {
+            SanError::InvalidMove(san) => write!(f, "\"{san}\" is not a valid move"),
+            SanError::IllegalMove(san) => {
+                write!(f, "\"{san}\" is not a legal move in this position")
+            }
+            SanError::AmbiguousMove(san) => {
+                write!(f, "\"{san}\" could refer to more than one move")
+            }
+        }
+    }
+}
+
+impl Error for SanError {}
+
+impl Board {
+    // Standard Algebraic Notation for a move in the current position
+    pub fn to_san(&mut self, piece_move: PieceMove) -> String {
+        let piece = self.get_piece(piece_move.from);
+
+        let mut san = if let Some(rook_move) = self.get_castling_rook_move(piece_move) {
+            if rook_move.to.rank == CASTLING_ROOK_RANKS.1 {
+                String::from("O-O-O")
+            } else {
+                String::from("O-O")
+            }
+        } else {
+            let is_pawn = piece.piece_type() == Some(PieceType::Pawn);
+            let is_capture = self.get_piece(piece_move.to) != Piece::None
+                || (is_pawn && piece_move.from.rank != piece_move.to.rank);
+
+            let mut san = String::new();
+            let from_algebraic = piece_move.from.to_algebraic();
+
+            if is_pawn {
+                // Pawn captures are identified by the file the pawn came from
+                if is_capture {
+                    san.push(from_algebraic.as_bytes()[0] as char);
+                }
+            } else {
+                san.push(piece.to_algebraic().to_ascii_uppercase());
+                san += self.get_san_disambiguation(piece_move).as_str();
+            }
+
+            if is_capture {
+                san.push('x');
+            }
+
+            san += piece_move.to.to_algebraic().as_str();
+
+            if self.is_promotion_move(piece_move) {
+                let promotion = piece_move.get_promotion().unwrap_or(Piece::WQueen);
+
+                san.push('=');
+                san.push(promotion.to_algebraic().to_ascii_uppercase());
+            }
+
+            san
+        };
+
+        // Mark checks and checkmates
+        let mut board = self.clone();
+        board.apply_move(piece_move);
+
+        let opponent = board.player;
+        if board.is_checkmate(opponent) {
+            san.push('#');
+        } else if board.is_in_check(opponent) {
+            san.push('+');
+        }
+
+        san
+    }
+
+    // The least information needed to tell this piece apart from others of its type which could reach the same square
+    fn get_san_disambiguation(&mut self, piece_move: PieceMove) -> String {
+        let piece = self.get_piece(piece_move.from);
+
+        let mut others = Vec::new();
+        for file in 0..BOARD_SIZE {
+            for rank in 0..BOARD_SIZE {
+                let from = TilePos::new(file, rank);
+
+                if from != piece_move.from
+                    && self.get_piece(from) == piece
+                    && self.get_legal_moves(from).contains(&piece_move.to)
+                {
+                    others.push(from);
+                }
+            }
+        }
+
+        let from_algebraic = piece_move.from.to_algebraic();
+        let (letter, number) = from_algebraic.split_at(1);
+
+        if others.is_empty() {
+            String::new()
+        } else if others
+            .iter()
+            .all(|other| other.rank != piece_move.from.rank)
+        {
+            letter.to_string()
+        } else if others
+            .iter()
+            .all(|other| other.file != piece_move.from.file)
+        {
+            number.to_string()
+        } else {
+            from_algebraic
+        }
+    }
+
+    // Find the legal move in the current position which this Standard Algebraic Notation refers to
+    pub fn parse_san(&mut self, san: &str) -> Result<PieceMove, SanError> {
+        // Check, checkmate, and annotation markers aren't needed to find the move
+        let trimmed = san.trim_end_matches(['+', '#', '!', '?']);
+        let player = self.player;
+
+        if let Some(king_pos) = self.get_king_pos(player) {
+            let castling_rook_rank = match trimmed {
+                "O-O" | "0-0" => Some(CASTLING_ROOK_RANKS.0),
+                "O-O-O" | "0-0-0" => Some(CASTLING_ROOK_RANKS.1),
+                _ => None,
+            };
+
+            // Find the king's legal castling move for this side, however it is written
+            if let Some(rook_rank) = castling_rook_rank {
+                return self
+                    .get_legal_moves(king_pos)
+                    .into_iter()
+                    .map(|to| PieceMove::new(king_pos, to))
+                    .find(|&piece_move| {
+                        self.get_castling_rook_move(piece_move)
+                            .is_some_and(|rook_move| rook_move.to.rank == rook_rank)
+                    })
+                    .ok_or_else(|| SanError::IllegalMove(san.to_string()));
+            }
+        }
+
+        // Split off the promotion piece
+        let (trimmed, promotion_chr) = match trimmed.split_once('=') {
+            Some((trimmed, promotion)) => (trimmed, promotion.chars().next()),
+            None => (trimmed, None),
+        };
+
+        // Leading letter gives the piece, a pawn otherwise
+        let mut chars = trimmed
+            .chars()
+            .filter(|&chr| chr != 'x')
+            .collect::<Vec<_>>();
+        let piece_chr = match chars.first() {
+            Some(&chr @ ('K' | 'Q' | 'R' | 'B' | 'N')) => {
+                chars.remove(0);
+                chr
+            }
+            _ => 'P',
+        };
+
+        if chars.len() < 2 {
+            return Err(SanError::InvalidMove(san.to_string()));
+        }
+        let to = TilePos::from_algebraic(&chars[chars.len() - 2..].iter().collect::<String>())
+            .map_err(|_| SanError::InvalidMove(san.to_string()))?;
+
+        // Any characters before the destination narrow down where the piece came from
+        let mut from_rank = None;
+        let mut from_file = None;
+        for &chr in &chars[..chars.len() - 2] {
+            match chr {
+                'a'..='h' => from_rank = Some((chr as u8 - b'a') as usize),
+                '1'..='8' => from_file = Some(BOARD_SIZE - (chr as u8 - b'0') as usize),
+                _ => return Err(SanError::InvalidMove(san.to_string())),
+            }
+        }
+
+        let colour_chr = |chr: char| match player {
+            Player::White => chr,
+            Player::Black => chr.to_ascii_lowercase(),
+        };
+        let piece = Piece::from_algebraic(colour_chr(piece_chr))
+            .ok_or_else(|| SanError::InvalidMove(san.to_string()))?;
+        let promotion = match promotion_chr {
+            Some(chr @ ('Q' | 'R' | 'B' | 'N')) => Piece::from_algebraic(colour_chr(chr)),
+            Some(_) => return Err(SanError::InvalidMove(san.to_string())),
+            None => None,
+        };
+
+        let mut candidates = Vec::new();
+        for file in 0..BOARD_SIZE {
+            for rank in 0..BOARD_SIZE {
+                let from = TilePos::new(file, rank);
+
+                if self.get_piece(from) == piece
+                    && from_file.is_none_or(|from_file| from_file == file)
+                    && from_rank.is_none_or(|from_rank| from_rank == rank)
+                    && self.get_legal_moves(from).contains(&to)
+                {
+                    candidates.push(from);
+                }
+            }
+        }
+
+        match candidates[..] {
+            [from] => {
+                let mut piece_move = PieceMove::new(from, to);
+                if let Some(promotion) = promotion {
+                    piece_move = piece_move.with_promotion(promotion);
+                }
+
+                Ok(piece_move)
+            }
+            [] => Err(SanError::IllegalMove(san.to_string())),
+            _ => Err(SanError::AmbiguousMove(san.to_string())),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pos(algebraic: &str) -> TilePos {
+        TilePos::from_algebraic(algebraic).unwrap()
+    }
+
+    fn san_move(fen: &str, from: &str, to: &str) -> String {
+        let mut board = Board::from_fen(fen).unwrap();
+        board.to_san(PieceMove::new(pos(from), pos(to)))
+    }
+
+    #[test]
+    fn to_san_without_disambiguation() {
+        let start = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1";
+
+        assert_eq!(san_move(start, "g1", "f3"), "Nf3");
+        assert_eq!(san_move(start, "e2", "e4"), "e4");
+    }
+
+    #[test]
+    fn to_san_file_disambiguation() {
+        // Both knights can reach d2, but they are on different files
+        let fen = "4k3/8/8/8/8/5N2/8/1N2K3 w - - 0 1";
+
+        assert_eq!(san_move(fen, "b1", "d2"), "Nbd2");
+        assert_eq!(san_move(fen, "f3", "d2"), "Nfd2");
+    }
+
+    #[test]
+    fn to_san_rank_disambiguation() {
+        // Both rooks can reach a3, but they are on the same file
+        let fen = "4k3/8/8/R7/8/8/8/R3K3 w - - 0 1";
+
+        assert_eq!(san_move(fen, "a1", "a3"), "R1a3");
+        assert_eq!(san_move(fen, "a5", "a3"), "R5a3");
+    }
+
+    #[test]
+    fn parse_pawn_capture() {
+        let mut board =
+            Board::from_fen("rnbqkbnr/ppp1pppp/8/3p4/4P3/8/PPPP1PPP/RNBQKBNR w KQkq d6 0 2")
+                .unwrap();
+
+        assert_eq!(
+            board.parse_san("exd5"),
+            Ok(PieceMove::new(pos("e4"), pos("d5")))
+        );
+    }
+
+    #[test]
+    fn parse_queenside_castling() {
+        for (fen, king, rook_to) in [
+            ("r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 0 1", "e1", "d1"),
+            ("r3k2r/8/8/8/8/8/8/R3K2R b KQkq - 0 1", "e8", "d8"),
+        ] {
+            let mut board = Board::from_fen(fen).unwrap();
+            let piece_move = board.parse_san("O-O-O").unwrap();
+
+            assert_eq!(piece_move.from, pos(king));
+            assert_eq!(
+                board.get_castling_rook_move(piece_move).map(|m| m.to),
+                Some(pos(rook_to))
+            );
+        }
+    }
+
+    #[test]
+    fn parse_promotion_with_check() {
+        let mut board = Board::from_fen("k7/4P3/8/8/8/8/8/4K3 w - - 0 1").unwrap();
+
+        assert_eq!(
+            board.parse_san("e8=Q+"),
+            Ok(PieceMove::new(pos("e7"), pos("e8")).with_promotion(Piece::WQueen))
+        );
+        assert_eq!(
+            board.parse_san("e8=N"),
+            Ok(PieceMove::new(pos("e7"), pos("e8")).with_promotion(Piece::WKnight))
+        );
+    }
+
+    #[test]
+    fn parse_rejects_ambiguous_move() {
+        // Both knights can reach e2
+        let mut board = Board::from_fen("4k3/8/8/8/8/2N5/8/4K1N1 w - - 0 1").unwrap();
+
+        assert_eq!(
+            board.parse_san("Ne2"),
+            Err(SanError::AmbiguousMove(String::from("Ne2")))
+        );
+        assert_eq!(
+            board.parse_san("Nce2"),
+            Ok(PieceMove::new(pos("c3"), pos("e2")))
+        );
+    }
+}
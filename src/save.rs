@@ -0,0 +1,220 @@
+use std::{error::Error, fmt, fs, io, path::Path};
+
+use bevy::prelude::*;
+
+use crate::{
+    board::{Board, TilePos},
+    captured_tray::CapturedPieces,
+    display::{spawn_missing_pieces, BoardLayout, BOARD_SIZE},
+    game_over::GameOverEvent,
+    pgn::{get_pgn_result_tag, PgnError, PgnHeaders},
+    piece::TurnChangedEvent,
+    promotion::PendingPromotion,
+    selection::SelectedPiece,
+};
+
+const SAVE_FILE_PATH: &str = "savegame.pgn";
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum GameLoadError {
+    Io(io::ErrorKind),
+    InvalidPgn(PgnError),
+    InvalidPly(String),
+}
+
+impl fmt::Display for GameLoadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Could not load game from file:")?;
+
+        match self {
+            GameLoadError::Io(kind) => write!(f, "{kind}"),
+            GameLoadError::InvalidPgn(pgn_error) => write!(f, "{pgn_error}"),
+            GameLoadError::InvalidPly(ply) => {
+                write!(f, "'{ply}' is not a ply within the saved moves")
+            }
+        }
+    }
+}
+
+impl Error for GameLoadError {}
+
+impl Board {
+    // Saved as PGN, with how many of the moves have been played (the rest can be redone)
+    pub fn save_game(&self, path: &Path, mut headers: PgnHeaders) -> io::Result<()> {
+        let mut end_board = self.clone();
+        while end_board.redo_move().is_some() {}
+
+        headers
+            .extra
+            .push(("Ply".to_string(), self.get_ply().to_string()));
+
+        fs::write(path, end_board.to_pgn_with_headers(headers) + "\n")
+    }
+
+    pub fn load_game(path: &Path) -> Result<Board, GameLoadError> {
+        let contents = fs::read_to_string(path).map_err(|e| GameLoadError::Io(e.kind()))?;
+        let mut board = Board::from_pgn(&contents).map_err(GameLoadError::InvalidPgn)?;
+
+        // Games without a ply are loaded at their final position
+        let headers = PgnHeaders::from_pgn(&contents);
+
+        if let Some(ply) = headers.get_extra("Ply") {
+            let ply = ply
+                .parse::<usize>()
+                .ok()
+                .filter(|&ply| ply <= board.get_ply())
+                .ok_or_else(|| GameLoadError::InvalidPly(ply.to_string()))?;
+
+            while board.get_ply() > ply {
+                board.undo_move();
+            }
+        }
+
+        Ok(board)
+    }
+}
+
+// Save the game with F5, along with its result once it is over
+pub fn save_game_input(
+    keys: Res<ButtonInput<KeyCode>>,
+    board: Res<Board>,
+    mut ev_turn_changed: EventReader<TurnChangedEvent>,
+    mut ev_game_over: EventReader<GameOverEvent>,
+    mut headers: Local<PgnHeaders>,
+) {
+    // Carrying on from the end of the game (such as by undoing a move) leaves the result open again
+    if ev_turn_changed.read().last().is_some() {
+        headers.result = PgnHeaders::default().result;
+    }
+
+    for ev in ev_game_over.read() {
+        headers.result = get_pgn_result_tag(ev.winner).to_string();
+    }
+
+    if !keys.just_pressed(KeyCode::F5) {
+        return;
+    }
+
+    match board.save_game(Path::new(SAVE_FILE_PATH), headers.clone()) {
+        Ok(()) => info!("Saved game to {SAVE_FILE_PATH}"),
+        Err(e) => error!("Could not save game to {SAVE_FILE_PATH}: {e}"),
+    }
+}
+
+// Load the saved game with F9, replacing every piece sprite
+#[allow(clippy::too_many_arguments)]
+pub fn load_game_input(
+    mut commands: Commands,
+    keys: Res<ButtonInput<KeyCode>>,
+    asset_server: Res<AssetServer>,
+    mut texture_atlas_layouts: ResMut<Assets<TextureAtlasLayout>>,
+    mut board: ResMut<Board>,
+    mut captured_pieces: ResMut<CapturedPieces>,
+    mut selected_piece: ResMut<SelectedPiece>,
+    pending_promotion: Res<PendingPromotion>,
+    layout: Res<BoardLayout>,
+    mut ev_turn_changed: EventWriter<TurnChangedEvent>,
+) {
+    if !keys.just_pressed(KeyCode::F9)
+        || selected_piece.dragged.is_some()
+        || pending_promotion.promotion.is_some()
+    {
+        return;
+    }
+
+    let mut loaded_board = match Board::load_game(Path::new(SAVE_FILE_PATH)) {
+        Ok(loaded_board) => loaded_board,
+        Err(e) => {
+            error!("{e}");
+            return;
+        }
+    };
+
+    for file in 0..BOARD_SIZE {
+        for rank in 0..BOARD_SIZE {
+            if let Some(entity) = board.get_entity(TilePos::new(file, rank)) {
+                commands.entity(entity).despawn();
+            }
+        }
+    }
+
+    spawn_missing_pieces(
+        &mut commands,
+        &asset_server,
+        &mut texture_atlas_layouts,
+        &mut loaded_board,
+        &layout,
+    );
+
+    *captured_pieces = CapturedPieces::from_board(&loaded_board);
+    selected_piece.selected = None;
+    *board = loaded_board;
+
+    ev_turn_changed.send(TurnChangedEvent {
+        new_player: board.player,
+    });
+
+    info!("Loaded game from {SAVE_FILE_PATH}");
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::*;
+    use crate::piece::PieceMove;
+
+    // Each test saves to its own file, since tests run in parallel
+    fn temp_save_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("chess_{name}_{}.pgn", std::process::id()))
+    }
+
+    fn move_piece(board: &mut Board, from: &str, to: &str) {
+        board
+            .move_piece(PieceMove::new(
+                TilePos::from_algebraic(from).unwrap(),
+                TilePos::from_algebraic(to).unwrap(),
+            ))
+            .unwrap();
+    }
+
+    #[test]
+    fn save_and_load() {
+        let path = temp_save_path("save_and_load");
+        let start_fen = "r3k2r/8/8/8/8/8/8/R3K2R b KQkq - 3 20";
+
+        let mut board = Board::from_fen(start_fen).unwrap();
+        for (from, to) in [("a8", "a1"), ("e1", "e2"), ("e8", "g8"), ("h1", "h8")] {
+            move_piece(&mut board, from, to);
+        }
+        board.undo_move();
+        board.save_game(&path, PgnHeaders::default()).unwrap();
+
+        // The undone move is saved, and can still be redone after loading
+        let mut loaded = Board::load_game(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+        assert_eq!(loaded.to_fen(), board.to_fen());
+        assert_eq!(loaded.get_ply(), 3);
+
+        loaded.redo_move();
+        board.redo_move();
+        assert_eq!(loaded.to_fen(), board.to_fen());
+
+        while loaded.undo_move().is_some() {}
+        assert_eq!(loaded.to_fen(), start_fen);
+    }
+
+    #[test]
+    fn load_errors() {
+        let path = temp_save_path("load_errors");
+        fs::write(&path, "[Ply \"9\"]\n1. e4").unwrap();
+        let result = Board::load_game(&path).map(|_| ());
+        fs::remove_file(&path).unwrap();
+        assert_eq!(result, Err(GameLoadError::InvalidPly("9".to_string())));
+
+        assert_eq!(
+            Board::load_game(Path::new("/nonexistent/savegame.pgn")).map(|_| ()),
+            Err(GameLoadError::Io(io::ErrorKind::NotFound))
+        );
+    }
+}
@@ -0,0 +1,169 @@
+use crate::{
+    board::{Board, Player},
+    evaluate::{evaluate, CHECKMATE_SCORE},
+    opening_book::OpeningBook,
+    piece::PieceMove,
+    transposition::{NodeType, TranspositionTable},
+};
+
+// The move chosen by the search, and its score in centipawns for the player who makes it
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub struct SearchResult {
+    pub piece_move: PieceMove,
+    pub score: i32,
+}
+
+impl Board {
+    // Play from the opening book when it knows the position, otherwise search for a move
+    pub fn best_move(&mut self, depth: usize, book: Option<&OpeningBook>) -> Option<PieceMove> {
+        if let Some(book_move) = book.and_then(|book| book.get_move(self)) {
+            return Some(book_move);
+        }
+
+        self.search(depth).map(|result| result.piece_move)
+    }
+
+    // Negamax search with alpha-beta pruning, returning None when there are no legal moves
+    pub fn search(&mut self, depth: usize) -> Option<SearchResult> {
+        self.search_with_table(depth, &mut TranspositionTable::default())
+    }
+
+    // Search using a transposition table which can be kept between searches
+    pub fn search_with_table(
+        &mut self,
+        depth: usize,
+        table: &mut TranspositionTable,
+    ) -> Option<SearchResult> {
+        let mut alpha = -CHECKMATE_SCORE - 1;
+        let beta = CHECKMATE_SCORE + 1;
+
+        let mut best = None;
+        for piece_move in self.get_ordered_moves(self.zobrist_hash(), table) {
+            let mut board = self.clone();
+            board.apply_move(piece_move);
+
+            let score = -board.negamax(depth.saturating_sub(1), 1, -beta, -alpha, table);
+
+            if best.is_none() || score > alpha {
+                alpha = alpha.max(score);
+                best = Some(SearchResult { piece_move, score });
+            }
+        }
+
+        best
+    }
+
+    fn negamax(
+        &mut self,
+        depth: usize,
+        ply: i32,
+        mut alpha: i32,
+        beta: i32,
+        table: &mut TranspositionTable,
+    ) -> i32 {
+        if self.is_fifty_move_draw() || self.is_threefold_repetition() {
+            return 0;
+        }
+
+        let hash = self.zobrist_hash();
+        if let Some(score) = table.probe(hash, depth, ply, alpha, beta) {
+            return score;
+        }
+
+        let moves = self.get_ordered_moves(hash, table);
+        if moves.is_empty() {
+            // Prefer quicker checkmates by scoring them further from zero
+            return if self.is_in_check(self.player) {
+                -CHECKMATE_SCORE + ply
+            } else {
+                0
+            };
+        }
+
+        if depth == 0 {
+            return match self.player {
+                Player::White => evaluate(self),
+                Player::Black => -evaluate(self),
+            };
+        }
+
+        let original_alpha = alpha;
+        let mut best_move = None;
+
+        for piece_move in moves {
+            let mut board = self.clone();
+            board.apply_move(piece_move);
+
+            let score = -board.negamax(depth - 1, ply + 1, -beta, -alpha, table);
+            if score >= beta {
+                table.store(
+                    hash,
+                    depth,
+                    ply,
+                    beta,
+                    NodeType::LowerBound,
+                    Some(piece_move),
+                );
+                return beta;
+            }
+            if score > alpha {
+                alpha = score;
+                best_move = Some(piece_move);
+            }
+        }
+
+        let node_type = if alpha > original_alpha {
+            NodeType::Exact
+        } else {
+            NodeType::UpperBound
+        };
+        table.store(hash, depth, ply, alpha, node_type, best_move);
+
+        alpha
+    }
+
+    // Every legal move, starting with the best move found the last time this position was searched
+    fn get_ordered_moves(&mut self, hash: u64, table: &TranspositionTable) -> Vec<PieceMove> {
+        let mut moves = self.get_all_legal_moves();
+
+        let best_move = table.get(hash).and_then(|entry| entry.best_move);
+        if let Some(index) = moves
+            .iter()
+            .position(|&piece_move| Some(piece_move) == best_move)
+        {
+            moves[..=index].rotate_right(1);
+        }
+
+        moves
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::TilePos;
+
+    fn piece_move(from: &str, to: &str) -> PieceMove {
+        PieceMove::new(
+            TilePos::from_algebraic(from).unwrap(),
+            TilePos::from_algebraic(to).unwrap(),
+        )
+    }
+
+    #[test]
+    fn finds_mate_in_one() {
+        let mut board = Board::from_fen("6k1/5ppp/8/8/8/8/8/R5K1 w - - 0 1").unwrap();
+        let result = board.search(2).unwrap();
+        assert_eq!(result.piece_move, piece_move("a1", "a8"));
+        assert_eq!(result.score, CHECKMATE_SCORE - 1);
+
+        let mut board = Board::from_fen("r5k1/8/8/8/8/8/5PPP/6K1 b - - 0 1").unwrap();
+        assert_eq!(board.best_move(2, None), Some(piece_move("a8", "a1")));
+    }
+
+    #[test]
+    fn no_move_when_mated() {
+        let mut board = Board::from_fen("7k/6Q1/6K1/8/8/8/8/8 b - - 0 1").unwrap();
+        assert_eq!(board.best_move(2, None), None);
+    }
+}
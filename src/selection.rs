@@ -0,0 +1,194 @@
+use bevy::prelude::*;
+use bevy_mod_picking::prelude::*;
+
+use crate::{
+    board::{Board, Player, TilePos},
+    display::{board_to_pixel_coords, BoardLayout},
+    editor::EditorMode,
+    piece::{PieceMove, PieceMoveEvent},
+    premove::Premove,
+    promotion::PendingPromotion,
+};
+
+// The piece chosen by clicking on it, which moves to the next legal square that is clicked
+#[derive(Resource, Default)]
+pub struct SelectedPiece {
+    pub selected: Option<TilePos>,
+    // Releasing a dragged piece also counts as clicking it, so that click needs to be ignored
+    pub dragged: Option<Entity>,
+}
+
+impl SelectedPiece {
+    // Move the selected piece if this is one of its legal squares, otherwise select the clicked piece if it can move
+    pub fn click(&mut self, board: &Board, tile_pos: TilePos) -> Option<PieceMove> {
+        self.click_as(board, tile_pos, board.player)
+    }
+
+    // Clicks by a player waiting for their turn choose a premove, which can go to any square not holding their own piece
+    pub fn click_as(
+        &mut self,
+        board: &Board,
+        tile_pos: TilePos,
+        player: Player,
+    ) -> Option<PieceMove> {
+        let mut board = board.clone();
+
+        if let Some(from) = self.selected.take() {
+            let is_move = if player == board.player {
+                board.get_legal_moves(from).contains(&tile_pos)
+            } else {
+                board.get_piece(tile_pos).to_player() != Some(player)
+            };
+
+            if is_move {
+                return Some(PieceMove::new(from, tile_pos));
+            }
+
+            // Clicking the selected piece again deselects it
+            if from == tile_pos {
+                return None;
+            }
+        }
+
+        if board.get_piece(tile_pos).to_player() == Some(player) {
+            self.selected = Some(tile_pos);
+        }
+
+        None
+    }
+}
+
+// Called when either a piece or a board square is clicked
+#[allow(clippy::too_many_arguments)]
+pub fn on_tile_click(
+    click: Listener<Pointer<Click>>,
+    tile_query: Query<&TilePos>,
+    mut transform_query: Query<&mut Transform>,
+    board: Res<Board>,
+    mut selected_piece: ResMut<SelectedPiece>,
+    mut pending_promotion: ResMut<PendingPromotion>,
+    mut premove: ResMut<Premove>,
+    editor_mode: Res<EditorMode>,
+    mut ev_piece_move: EventWriter<PieceMoveEvent>,
+    layout: Res<BoardLayout>,
+) {
+    // The editor handles its own clicks
+    if selected_piece.dragged.take() == Some(click.target)
+        || pending_promotion.promotion.is_some()
+        || editor_mode.enabled
+    {
+        return;
+    }
+
+    // Board squares are tagged with their position, pieces are found through the board
+    let Some(tile_pos) = tile_query
+        .get(click.target)
+        .ok()
+        .copied()
+        .or_else(|| board.get_entity_pos(click.target))
+    else {
+        return;
+    };
+
+    click_tile(
+        tile_pos,
+        &board,
+        &mut selected_piece,
+        &mut pending_promotion,
+        &mut premove,
+        &mut transform_query,
+        &mut ev_piece_move,
+        &layout,
+    );
+}
+
+// Select the piece on this square, or move the selected piece to it, whether the square was clicked or chosen with the keyboard
+#[allow(clippy::too_many_arguments)]
+pub fn click_tile(
+    tile_pos: TilePos,
+    board: &Board,
+    selected_piece: &mut SelectedPiece,
+    pending_promotion: &mut PendingPromotion,
+    premove: &mut Premove,
+    transform_query: &mut Query<&mut Transform>,
+    ev_piece_move: &mut EventWriter<PieceMoveEvent>,
+    layout: &BoardLayout,
+) {
+    let player = if premove.is_waiting(board) {
+        board.player.opponent()
+    } else {
+        board.player
+    };
+
+    let Some(piece_move) = selected_piece.click_as(board, tile_pos, player) else {
+        return;
+    };
+    if premove.queue(board, piece_move) {
+        return;
+    }
+
+    let Some(entity) = board.get_entity(piece_move.from) else {
+        return;
+    };
+
+    // Wait for the promotion piece to be chosen, showing the pawn on the final file in the meantime
+    if board.is_promotion_move(piece_move) {
+        if let Ok(mut transform) = transform_query.get_mut(entity) {
+            let (x, y) = board_to_pixel_coords(piece_move.to.file, piece_move.to.rank, layout);
+            transform.translation = Vec3::new(x, y, 1.);
+        }
+
+        pending_promotion.promotion = Some((piece_move, entity));
+    } else {
+        ev_piece_move.send(PieceMoveEvent { piece_move, entity });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pos(algebraic: &str) -> TilePos {
+        TilePos::from_algebraic(algebraic).unwrap()
+    }
+
+    #[test]
+    fn select_then_move() {
+        let board = Board::default();
+        let mut selected_piece = SelectedPiece::default();
+
+        // Empty squares and the opponent's pieces can't be selected
+        assert_eq!(selected_piece.click(&board, pos("e4")), None);
+        assert_eq!(selected_piece.click(&board, pos("e7")), None);
+        assert_eq!(selected_piece.selected, None);
+
+        assert_eq!(selected_piece.click(&board, pos("e2")), None);
+        assert_eq!(selected_piece.selected, Some(pos("e2")));
+
+        assert_eq!(
+            selected_piece.click(&board, pos("e4")),
+            Some(PieceMove::new(pos("e2"), pos("e4")))
+        );
+        assert_eq!(selected_piece.selected, None);
+    }
+
+    #[test]
+    fn reselect_and_deselect() {
+        let board = Board::default();
+        let mut selected_piece = SelectedPiece::default();
+        selected_piece.click(&board, pos("e2"));
+
+        // Clicking another of the player's pieces selects it instead
+        assert_eq!(selected_piece.click(&board, pos("g1")), None);
+        assert_eq!(selected_piece.selected, Some(pos("g1")));
+
+        // An illegal square clears the selection
+        assert_eq!(selected_piece.click(&board, pos("g4")), None);
+        assert_eq!(selected_piece.selected, None);
+
+        // As does clicking the selected piece again
+        selected_piece.click(&board, pos("g1"));
+        assert_eq!(selected_piece.click(&board, pos("g1")), None);
+        assert_eq!(selected_piece.selected, None);
+    }
+}
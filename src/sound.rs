@@ -0,0 +1,190 @@
+use std::time::Duration;
+
+use bevy::{audio::Volume, prelude::*};
+
+use crate::{
+    board::MoveOutcome,
+    game_over::{GameEndReason, GameOverEvent},
+    piece::MoveOutcomeEvent,
+};
+
+const SOUND_VOLUME: f32 = 0.3;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum SoundKind {
+    Move,
+    Capture,
+    Castle,
+    Check,
+    Checkmate,
+}
+
+impl SoundKind {
+    // Each sound is a short tone, with (frequency in Hz, length in milliseconds)
+    fn get_tone(self) -> (f32, u64) {
+        match self {
+            SoundKind::Move => (440., 60),
+            SoundKind::Capture => (330., 120),
+            SoundKind::Castle => (520., 100),
+            SoundKind::Check => (880., 150),
+            SoundKind::Checkmate => (220., 600),
+        }
+    }
+}
+
+// Check is the most important thing to hear about, then castling and captures
+pub fn sound_for(outcome: &MoveOutcome) -> SoundKind {
+    if outcome.check {
+        SoundKind::Check
+    } else if outcome.castling_rook_move.is_some() {
+        SoundKind::Castle
+    } else if outcome.captured.is_some() {
+        SoundKind::Capture
+    } else {
+        SoundKind::Move
+    }
+}
+
+// Handles to the sound for each kind of move
+#[derive(Resource)]
+pub struct SoundAssets {
+    move_sound: Handle<Pitch>,
+    capture: Handle<Pitch>,
+    castle: Handle<Pitch>,
+    check: Handle<Pitch>,
+    checkmate: Handle<Pitch>,
+}
+
+impl SoundAssets {
+    pub fn get(&self, sound_kind: SoundKind) -> Handle<Pitch> {
+        match sound_kind {
+            SoundKind::Move => self.move_sound.clone(),
+            SoundKind::Capture => self.capture.clone(),
+            SoundKind::Castle => self.castle.clone(),
+            SoundKind::Check => self.check.clone(),
+            SoundKind::Checkmate => self.checkmate.clone(),
+        }
+    }
+}
+
+#[derive(Resource, Default)]
+pub struct SoundSettings {
+    pub muted: bool,
+}
+
+pub fn setup_sounds(mut commands: Commands, mut pitches: ResMut<Assets<Pitch>>) {
+    let mut add_tone = |sound_kind: SoundKind| {
+        let (frequency, millis) = sound_kind.get_tone();
+        pitches.add(Pitch::new(frequency, Duration::from_millis(millis)))
+    };
+
+    commands.insert_resource(SoundAssets {
+        move_sound: add_tone(SoundKind::Move),
+        capture: add_tone(SoundKind::Capture),
+        castle: add_tone(SoundKind::Castle),
+        check: add_tone(SoundKind::Check),
+        checkmate: add_tone(SoundKind::Checkmate),
+    });
+}
+
+// Mute and unmute the sounds with S
+pub fn mute_input(keys: Res<ButtonInput<KeyCode>>, mut settings: ResMut<SoundSettings>) {
+    if keys.just_pressed(KeyCode::KeyS) {
+        settings.muted = !settings.muted;
+    }
+}
+
+// Play a sound for the latest move, or for checkmate if it ended the game
+pub fn play_move_sounds(
+    mut commands: Commands,
+    mut ev_move_outcome: EventReader<MoveOutcomeEvent>,
+    mut ev_game_over: EventReader<GameOverEvent>,
+    sounds: Res<SoundAssets>,
+    settings: Res<SoundSettings>,
+) {
+    let is_checkmate = ev_game_over
+        .read()
+        .any(|ev| ev.reason == GameEndReason::Checkmate);
+
+    let Some(ev) = ev_move_outcome.read().last() else {
+        return;
+    };
+
+    if settings.muted {
+        return;
+    }
+
+    let sound_kind = if is_checkmate {
+        SoundKind::Checkmate
+    } else {
+        sound_for(&ev.outcome)
+    };
+
+    commands.spawn(PitchBundle {
+        source: sounds.get(sound_kind),
+        settings: PlaybackSettings::DESPAWN.with_volume(Volume::new(SOUND_VOLUME)),
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        board::{Board, TilePos},
+        piece::PieceMove,
+    };
+
+    fn outcome_of(fen: &str, from: &str, to: &str) -> MoveOutcome {
+        let mut board = Board::from_fen(fen).unwrap();
+
+        board
+            .move_piece(PieceMove::new(
+                TilePos::from_algebraic(from).unwrap(),
+                TilePos::from_algebraic(to).unwrap(),
+            ))
+            .unwrap()
+    }
+
+    #[test]
+    fn sound_for_each_outcome() {
+        for (fen, from, to, sound_kind) in [
+            (
+                "4k3/8/8/8/8/8/4P3/4K3 w - - 0 1",
+                "e2",
+                "e4",
+                SoundKind::Move,
+            ),
+            (
+                "4k3/8/8/3p4/4P3/8/8/4K3 w - - 0 1",
+                "e4",
+                "d5",
+                SoundKind::Capture,
+            ),
+            (
+                "4k3/8/8/8/8/8/8/4K2R w K - 0 1",
+                "e1",
+                "g1",
+                SoundKind::Castle,
+            ),
+            (
+                "4k3/8/8/8/8/8/8/R3K3 w - - 0 1",
+                "a1",
+                "a8",
+                SoundKind::Check,
+            ),
+            // Check is played over the capture that gave it
+            (
+                "r3k3/8/8/8/8/8/8/R3K3 w - - 0 1",
+                "a1",
+                "a8",
+                SoundKind::Check,
+            ),
+        ] {
+            assert_eq!(
+                sound_for(&outcome_of(fen, from, to)),
+                sound_kind,
+                "{from}{to}"
+            );
+        }
+    }
+}
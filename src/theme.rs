@@ -0,0 +1,95 @@
+use bevy::prelude::*;
+
+#[derive(Resource, Clone, Copy, PartialEq, Debug)]
+pub struct BoardTheme {
+    pub light: Color,
+    pub dark: Color,
+    pub highlight: Color,
+    pub check: Color,
+}
+
+impl Default for BoardTheme {
+    fn default() -> Self {
+        PURPLE_THEME
+    }
+}
+
+pub const PURPLE_THEME: BoardTheme = BoardTheme {
+    light: Color::WHITE,
+    dark: Color::PURPLE,
+    highlight: Color::rgb(0.85, 0.8, 0.35),
+    check: Color::rgb(0.9, 0.2, 0.2),
+};
+
+pub const BROWN_THEME: BoardTheme = BoardTheme {
+    light: Color::rgb(0.94, 0.85, 0.71),
+    dark: Color::rgb(0.71, 0.53, 0.39),
+    highlight: Color::rgb(0.8, 0.82, 0.4),
+    check: Color::rgb(0.9, 0.2, 0.2),
+};
+
+pub const GREEN_THEME: BoardTheme = BoardTheme {
+    light: Color::rgb(0.93, 0.93, 0.82),
+    dark: Color::rgb(0.46, 0.59, 0.34),
+    highlight: Color::rgb(0.73, 0.79, 0.27),
+    check: Color::rgb(0.9, 0.2, 0.2),
+};
+
+// The built-in themes, in the order they are cycled through
+pub const THEME_PRESETS: [BoardTheme; 3] = [PURPLE_THEME, BROWN_THEME, GREEN_THEME];
+
+#[derive(Event)]
+pub struct BoardThemeEvent {
+    pub theme: BoardTheme,
+}
+
+// Cycle through the built-in themes with T
+pub fn theme_cycle_input(
+    keys: Res<ButtonInput<KeyCode>>,
+    theme: Res<BoardTheme>,
+    mut ev_board_theme: EventWriter<BoardThemeEvent>,
+) {
+    if !keys.just_pressed(KeyCode::KeyT) {
+        return;
+    }
+
+    // A custom theme goes back to the first preset
+    let next_index = THEME_PRESETS
+        .iter()
+        .position(|preset| preset == theme.as_ref())
+        .map_or(0, |index| (index + 1) % THEME_PRESETS.len());
+
+    ev_board_theme.send(BoardThemeEvent {
+        theme: THEME_PRESETS[next_index],
+    });
+}
+
+// Store the new theme, the squares are then re-tinted by highlight_squares
+pub fn board_theme_event_reader(
+    mut ev_board_theme: EventReader<BoardThemeEvent>,
+    mut theme: ResMut<BoardTheme>,
+) {
+    for ev in ev_board_theme.read() {
+        *theme = ev.theme;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn theme_event_updates_theme() {
+        let mut app = App::new();
+        app.init_resource::<BoardTheme>()
+            .add_event::<BoardThemeEvent>()
+            .add_systems(Update, board_theme_event_reader);
+
+        app.update();
+        assert_eq!(*app.world.resource::<BoardTheme>(), PURPLE_THEME);
+
+        app.world.send_event(BoardThemeEvent { theme: GREEN_THEME });
+        app.update();
+        assert_eq!(*app.world.resource::<BoardTheme>(), GREEN_THEME);
+    }
+}
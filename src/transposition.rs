@@ -0,0 +1,186 @@
+use crate::{evaluate::CHECKMATE_SCORE, piece::PieceMove};
+
+pub const DEFAULT_TABLE_CAPACITY: usize = 1 << 16;
+
+// Checkmate scores are adjusted by how far into the search they were found, so any score this close to checkmate is one
+const CHECKMATE_THRESHOLD: i32 = CHECKMATE_SCORE - 1_000;
+
+// How a stored score relates to the position's real score, since alpha-beta cutoffs only give a bound
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum NodeType {
+    Exact,
+    LowerBound,
+    UpperBound,
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub struct TranspositionEntry {
+    pub hash: u64,
+    pub depth: usize,
+    pub score: i32,
+    pub node_type: NodeType,
+    pub best_move: Option<PieceMove>,
+}
+
+// Scores of positions which have already been searched, indexed by their Zobrist hash
+pub struct TranspositionTable {
+    entries: Vec<Option<TranspositionEntry>>,
+}
+
+impl Default for TranspositionTable {
+    fn default() -> Self {
+        Self::new(DEFAULT_TABLE_CAPACITY)
+    }
+}
+
+impl TranspositionTable {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            entries: vec![None; capacity.max(1)],
+        }
+    }
+
+    pub fn get(&self, hash: u64) -> Option<TranspositionEntry> {
+        self.entries[self.get_index(hash)].filter(|entry| entry.hash == hash)
+    }
+
+    // Entries searched to a greater depth are kept over shallower ones for the same slot, unless the new entry is for the same position
+    pub fn insert(&mut self, entry: TranspositionEntry) {
+        let index = self.get_index(entry.hash);
+
+        if self.entries[index]
+            .is_none_or(|existing| existing.hash == entry.hash || entry.depth >= existing.depth)
+        {
+            self.entries[index] = Some(entry);
+        }
+    }
+
+    // A stored score which settles this search, so long as it was searched at least as deeply
+    pub fn probe(&self, hash: u64, depth: usize, ply: i32, alpha: i32, beta: i32) -> Option<i32> {
+        let entry = self.get(hash).filter(|entry| entry.depth >= depth)?;
+        let score = score_from_table(entry.score, ply);
+
+        match entry.node_type {
+            NodeType::Exact => Some(score),
+            NodeType::LowerBound if score >= beta => Some(beta),
+            NodeType::UpperBound if score <= alpha => Some(alpha),
+            _ => None,
+        }
+    }
+
+    // Store the result of a search which was this many plies from the root
+    pub fn store(
+        &mut self,
+        hash: u64,
+        depth: usize,
+        ply: i32,
+        score: i32,
+        node_type: NodeType,
+        best_move: Option<PieceMove>,
+    ) {
+        self.insert(TranspositionEntry {
+            hash,
+            depth,
+            score: score_to_table(score, ply),
+            node_type,
+            best_move,
+        });
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.fill(None);
+    }
+
+    fn get_index(&self, hash: u64) -> usize {
+        (hash % self.entries.len() as u64) as usize
+    }
+}
+
+// Checkmate scores are stored relative to the position, rather than the root of the search which found them
+fn score_to_table(score: i32, ply: i32) -> i32 {
+    if score > CHECKMATE_THRESHOLD {
+        score + ply
+    } else if score < -CHECKMATE_THRESHOLD {
+        score - ply
+    } else {
+        score
+    }
+}
+
+fn score_from_table(score: i32, ply: i32) -> i32 {
+    if score > CHECKMATE_THRESHOLD {
+        score - ply
+    } else if score < -CHECKMATE_THRESHOLD {
+        score + ply
+    } else {
+        score
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::Board;
+
+    #[test]
+    fn store_and_probe() {
+        let mut table = TranspositionTable::new(16);
+        table.store(5, 3, 0, 40, NodeType::Exact, None);
+
+        assert_eq!(table.probe(5, 3, 0, -100, 100), Some(40));
+        assert_eq!(table.probe(5, 4, 0, -100, 100), None);
+        // Same slot, but a different position
+        assert_eq!(table.probe(21, 1, 0, -100, 100), None);
+
+        table.store(6, 3, 0, 40, NodeType::LowerBound, None);
+        assert_eq!(table.probe(6, 3, 0, -100, 30), Some(30));
+        assert_eq!(table.probe(6, 3, 0, -100, 100), None);
+    }
+
+    #[test]
+    fn deeper_entries_are_kept() {
+        let mut table = TranspositionTable::new(16);
+        table.store(5, 4, 0, 10, NodeType::Exact, None);
+        table.store(21, 2, 0, 20, NodeType::Exact, None);
+        assert_eq!(table.get(5).map(|entry| entry.score), Some(10));
+
+        table.store(5, 1, 0, 30, NodeType::Exact, None);
+        assert_eq!(table.get(5).map(|entry| entry.score), Some(30));
+
+        table.clear();
+        assert_eq!(table.get(5), None);
+    }
+
+    #[test]
+    fn checkmate_scores_move_with_ply() {
+        let mut table = TranspositionTable::new(16);
+        table.store(5, 2, 3, CHECKMATE_SCORE - 5, NodeType::Exact, None);
+
+        // Found 5 plies from the root at ply 3, so it is 2 plies from the stored position
+        assert_eq!(
+            table.probe(5, 2, 1, -CHECKMATE_SCORE, CHECKMATE_SCORE),
+            Some(CHECKMATE_SCORE - 3)
+        );
+    }
+
+    #[test]
+    fn search_with_table_matches_search() {
+        let fen = "r1bqkbnr/pppp1ppp/2n5/4p3/4P3/5N2/PPPP1PPP/RNBQKB1R w KQkq - 2 3";
+        let mut table = TranspositionTable::default();
+
+        let result = Board::from_fen(fen).unwrap().search(3);
+        assert_eq!(
+            Board::from_fen(fen)
+                .unwrap()
+                .search_with_table(3, &mut table),
+            result
+        );
+        // Searching again reuses the stored entries
+        assert_eq!(
+            Board::from_fen(fen)
+                .unwrap()
+                .search_with_table(3, &mut table),
+            result
+        );
+    }
+}
@@ -0,0 +1,354 @@
+use std::{
+    error::Error,
+    fmt,
+    io::{self, BufRead, BufReader, Write},
+    process::{Child, ChildStdin, ChildStdout, Command, Stdio},
+};
+
+use bevy::{
+    prelude::*,
+    tasks::{block_on, futures_lite::future, AsyncComputeTaskPool, Task},
+};
+
+use crate::{
+    ai::AiSettings,
+    board::{Board, Player, TilePos},
+    game_over::GameState,
+    piece::{piece_move_event_reader, Piece, PieceMove, PieceMoveEvent},
+};
+
+pub const DEFAULT_UCI_DEPTH: usize = 10;
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum UciError {
+    Io(io::ErrorKind),
+    EngineExited,
+    InvalidMove(String),
+}
+
+impl fmt::Display for UciError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Could not communicate with UCI engine:")?;
+
+        match self {
+            UciError::Io(kind) => write!(f, "{kind}"),
+            UciError::EngineExited => write!(f, "Engine closed its output"),
+            UciError::InvalidMove(uci) => write!(f, "\"{uci}\" is not a valid move"),
+        }
+    }
+}
+
+impl Error for UciError {}
+
+impl From<io::Error> for UciError {
+    fn from(error: io::Error) -> Self {
+        UciError::Io(error.kind())
+    }
+}
+
+// An external engine, such as Stockfish, running as a subprocess
+pub struct UciEngine {
+    process: Child,
+    stdin: ChildStdin,
+    stdout: BufReader<ChildStdout>,
+}
+
+impl UciEngine {
+    pub fn new(path: &str) -> Result<Self, UciError> {
+        let mut process = Command::new(path)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()?;
+
+        let (Some(stdin), Some(stdout)) = (process.stdin.take(), process.stdout.take()) else {
+            return Err(UciError::EngineExited);
+        };
+
+        let mut engine = Self {
+            process,
+            stdin,
+            stdout: BufReader::new(stdout),
+        };
+
+        engine.send("uci")?;
+        engine.read_until("uciok")?;
+        engine.is_ready()?;
+
+        Ok(engine)
+    }
+
+    // Wait for the engine to finish processing any previous commands
+    pub fn is_ready(&mut self) -> Result<(), UciError> {
+        self.send("isready")?;
+        self.read_until("readyok")?;
+
+        Ok(())
+    }
+
+    // Ask the engine for its move in this position, or None if it has no legal moves
+    pub fn best_move(
+        &mut self,
+        board: &Board,
+        depth: usize,
+    ) -> Result<Option<PieceMove>, UciError> {
+        self.send(format!("position fen {}", board.to_fen()).as_str())?;
+        self.is_ready()?;
+        self.send(format!("go depth {depth}").as_str())?;
+
+        let line = self.read_until("bestmove")?;
+        let uci = line.split_whitespace().nth(1).unwrap_or_default();
+
+        if uci == "(none)" || uci == "0000" {
+            return Ok(None);
+        }
+
+        board
+            .parse_uci_move(uci)
+            .map(Some)
+            .ok_or_else(|| UciError::InvalidMove(uci.to_string()))
+    }
+
+    fn send(&mut self, command: &str) -> Result<(), UciError> {
+        writeln!(self.stdin, "{command}")?;
+        self.stdin.flush()?;
+
+        Ok(())
+    }
+
+    // Skip lines of output until one starts with this token, returning that line
+    fn read_until(&mut self, token: &str) -> Result<String, UciError> {
+        let mut line = String::new();
+
+        loop {
+            line.clear();
+            if self.stdout.read_line(&mut line)? == 0 {
+                return Err(UciError::EngineExited);
+            }
+
+            if line.split_whitespace().next() == Some(token) {
+                return Ok(line.trim().to_string());
+            }
+        }
+    }
+}
+
+impl Drop for UciEngine {
+    fn drop(&mut self) {
+        let _ = self.send("quit");
+        let _ = self.process.wait();
+    }
+}
+
+impl Board {
+    // Long algebraic notation used by UCI, such as "e2e4" or "e7e8q"
+    pub fn parse_uci_move(&self, uci: &str) -> Option<PieceMove> {
+        let from = TilePos::from_algebraic(uci.get(0..2)?).ok()?;
+        let to = TilePos::from_algebraic(uci.get(2..4)?).ok()?;
+
+        let piece_move = PieceMove::new(from, to);
+
+        match uci.get(4..)?.chars().next() {
+            Some(chr @ ('q' | 'r' | 'b' | 'n')) => {
+                let promotion = match self.get_piece(from).to_player()? {
+                    Player::White => Piece::from_algebraic(chr.to_ascii_uppercase()),
+                    Player::Black => Piece::from_algebraic(chr),
+                }?;
+
+                Some(piece_move.with_promotion(promotion))
+            }
+            Some(_) => None,
+            None => Some(piece_move),
+        }
+    }
+}
+
+// The engine, which is moved into the search task while it is thinking
+#[derive(Resource)]
+pub struct UciOpponent {
+    pub engine: Option<UciEngine>,
+    pub player: Player,
+    pub depth: usize,
+}
+
+// The engine is handed back alongside its reply
+type UciSearch = Task<(UciEngine, Result<Option<PieceMove>, UciError>)>;
+
+#[derive(Resource, Default)]
+pub struct UciTask {
+    task: Option<(u64, UciSearch)>,
+}
+
+// Plays against an external engine when the UCI_ENGINE environment variable gives its path
+pub struct UciEnginePlugin;
+
+impl Plugin for UciEnginePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<UciTask>().add_systems(
+            Update,
+            (
+                start_uci_search.after(piece_move_event_reader),
+                poll_uci_search.after(start_uci_search),
+            ),
+        );
+
+        let Ok(path) = std::env::var("UCI_ENGINE") else {
+            return;
+        };
+
+        match UciEngine::new(&path) {
+            Ok(engine) => {
+                app.insert_resource(UciOpponent {
+                    engine: Some(engine),
+                    player: Player::Black,
+                    depth: DEFAULT_UCI_DEPTH,
+                });
+
+                // Only one opponent should reply to each move
+                app.insert_resource(AiSettings {
+                    enabled: false,
+                    ..default()
+                });
+            }
+            Err(e) => error!("{e}"),
+        }
+    }
+}
+
+// Hand the engine the position when a move makes it the engine's turn
+pub fn start_uci_search(
+    mut ev_piece_move: EventReader<PieceMoveEvent>,
+    board: Res<Board>,
+    game_state: Res<GameState>,
+    uci_opponent: Option<ResMut<UciOpponent>>,
+    mut uci_task: ResMut<UciTask>,
+) {
+    let moved = ev_piece_move.read().count() > 0;
+
+    let Some(mut uci_opponent) = uci_opponent else {
+        return;
+    };
+
+    if !(moved || uci_opponent.is_added())
+        || board.player != uci_opponent.player
+        || game_state.is_over()
+    {
+        return;
+    }
+
+    let Some(mut engine) = uci_opponent.engine.take() else {
+        return;
+    };
+
+    let search_board = board.clone();
+    let depth = uci_opponent.depth;
+    let task = AsyncComputeTaskPool::get().spawn(async move {
+        let best_move = engine.best_move(&search_board, depth);
+        (engine, best_move)
+    });
+
+    uci_task.task = Some((board.zobrist_hash(), task));
+}
+
+// Send the engine's move once it replies, so long as the position hasn't changed and the game is still going
+pub fn poll_uci_search(
+    board: Res<Board>,
+    game_state: Res<GameState>,
+    uci_opponent: Option<ResMut<UciOpponent>>,
+    mut uci_task: ResMut<UciTask>,
+    mut ev_piece_move: EventWriter<PieceMoveEvent>,
+) {
+    let (Some(mut uci_opponent), Some((hash, task))) = (uci_opponent, &mut uci_task.task) else {
+        return;
+    };
+
+    let Some((engine, best_move)) = block_on(future::poll_once(task)) else {
+        return;
+    };
+
+    match best_move {
+        Ok(Some(piece_move)) if *hash == board.zobrist_hash() && !game_state.is_over() => {
+            if let Some(entity) = board.get_entity(piece_move.from) {
+                ev_piece_move.send(PieceMoveEvent { piece_move, entity });
+            }
+        }
+        Ok(_) => {}
+        Err(e) => error!("{e}"),
+    }
+
+    uci_opponent.engine = Some(engine);
+    uci_task.task = None;
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use std::{fs, os::unix::fs::PermissionsExt, path::PathBuf};
+
+    use super::*;
+
+    // Replies with a promotion for the first position, and with no move for any other
+    const MOCK_ENGINE: &str = r#"#!/bin/sh
+while read -r line; do
+    case "$line" in
+        uci) echo "id name Mock"; echo "uciok" ;;
+        isready) echo "readyok" ;;
+        "position fen k7/4P3/"*) reply="bestmove e7e8q ponder a8b7" ;;
+        position*) reply="bestmove (none)" ;;
+        go*) echo "info depth 1 score cp 900"; echo "$reply" ;;
+        quit) exit 0 ;;
+    esac
+done
+"#;
+
+    fn mock_engine_path() -> PathBuf {
+        let path = std::env::temp_dir().join(format!("mock_uci_engine_{}.sh", std::process::id()));
+        fs::write(&path, MOCK_ENGINE).unwrap();
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o755)).unwrap();
+
+        path
+    }
+
+    fn pos(algebraic: &str) -> TilePos {
+        TilePos::from_algebraic(algebraic).unwrap()
+    }
+
+    #[test]
+    fn mock_engine_moves() {
+        let path = mock_engine_path();
+        let mut engine = UciEngine::new(path.to_str().unwrap()).unwrap();
+
+        let board = Board::from_fen("k7/4P3/8/8/8/8/8/4K3 w - - 0 1").unwrap();
+        assert_eq!(
+            engine.best_move(&board, 5),
+            Ok(Some(
+                PieceMove::new(pos("e7"), pos("e8")).with_promotion(Piece::WQueen)
+            ))
+        );
+
+        let board = Board::from_fen("7k/6Q1/6K1/8/8/8/8/8 b - - 0 1").unwrap();
+        assert_eq!(engine.best_move(&board, 5), Ok(None));
+
+        drop(engine);
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn missing_engine() {
+        assert!(UciEngine::new("/nonexistent/uci_engine").is_err());
+    }
+
+    #[test]
+    fn parse_uci_moves() {
+        let board = Board::from_fen("4k3/1P6/8/8/8/8/8/4K3 w - - 0 1").unwrap();
+
+        assert_eq!(
+            board.parse_uci_move("e1d1"),
+            Some(PieceMove::new(pos("e1"), pos("d1")))
+        );
+        assert_eq!(
+            board.parse_uci_move("b7b8n"),
+            Some(PieceMove::new(pos("b7"), pos("b8")).with_promotion(Piece::WKnight))
+        );
+        assert_eq!(board.parse_uci_move("e1"), None);
+        assert_eq!(board.parse_uci_move("b7b8x"), None);
+    }
+}
@@ -0,0 +1,184 @@
+use bevy::{ecs::system::SystemParam, prelude::*};
+
+use crate::{
+    animation::MoveAnimation,
+    board::{Board, TilePos},
+    display::{board_to_pixel_coords, spawn_missing_pieces, BoardLayout},
+    editor::EditorMode,
+    piece::{Piece, PieceCapturedEvent, PieceMove, TurnChangedEvent},
+};
+
+#[derive(Event)]
+pub struct UndoRedoEvent {
+    pub piece_move: PieceMove,
+    // Where the king and rook went, if the move was castling
+    pub castling: Option<(PieceMove, PieceMove)>,
+    pub captured: Option<(Piece, TilePos)>,
+    pub undone: bool,
+}
+
+// The events sent when a move is undone or redone, so that everything watching the board can catch up
+#[derive(SystemParam)]
+pub struct UndoRedoWriters<'w> {
+    undo_redo: EventWriter<'w, UndoRedoEvent>,
+    turn_changed: EventWriter<'w, TurnChangedEvent>,
+    piece_captured: EventWriter<'w, PieceCapturedEvent>,
+}
+
+impl UndoRedoWriters<'_> {
+    // Take back the last move, returning whether there was one
+    pub fn undo(&mut self, board: &mut Board) -> bool {
+        let Some(piece_move) = board.undo_move() else {
+            return false;
+        };
+
+        self.undo_redo.send(UndoRedoEvent {
+            piece_move,
+            castling: board.get_castling_piece_moves(piece_move),
+            captured: board.get_redo_capture(),
+            undone: true,
+        });
+        self.turn_changed.send(TurnChangedEvent {
+            new_player: board.player,
+        });
+
+        true
+    }
+
+    // Replay the last move which was undone, returning whether there was one
+    pub fn redo(&mut self, board: &mut Board) -> bool {
+        let Some(piece_move) = board.get_redo_move() else {
+            return false;
+        };
+        let castling = board.get_castling_piece_moves(piece_move);
+        let captured = board.get_redo_capture();
+        let captured_entity = captured.and_then(|(_, at)| board.get_entity(at));
+
+        board.redo_move();
+
+        // Remove the sprite of any piece captured by replaying the move
+        if let Some((piece, at)) = captured {
+            self.piece_captured.send(PieceCapturedEvent {
+                piece,
+                at,
+                by: board.get_next_player(),
+                entity: captured_entity,
+            });
+        }
+
+        self.undo_redo.send(UndoRedoEvent {
+            piece_move,
+            castling,
+            captured,
+            undone: false,
+        });
+        self.turn_changed.send(TurnChangedEvent {
+            new_player: board.player,
+        });
+
+        true
+    }
+}
+
+// Undo with Ctrl+Z and redo with Ctrl+Y, except in the editor where the moves no longer match the board
+pub fn undo_redo_input(
+    keys: Res<ButtonInput<KeyCode>>,
+    mut board: ResMut<Board>,
+    mut undo_redo_writers: UndoRedoWriters,
+    editor_mode: Res<EditorMode>,
+) {
+    if !keys.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight]) || editor_mode.enabled {
+        return;
+    }
+
+    if keys.just_pressed(KeyCode::KeyZ) {
+        undo_redo_writers.undo(&mut board);
+    } else if keys.just_pressed(KeyCode::KeyY) {
+        undo_redo_writers.redo(&mut board);
+    }
+}
+
+// Move sprites to match the board after an undo or redo, and respawn the sprites of restored pieces
+#[allow(clippy::too_many_arguments)]
+pub fn undo_redo_event_reader(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mut texture_atlas_layouts: ResMut<Assets<TextureAtlasLayout>>,
+    mut ev_undo_redo: EventReader<UndoRedoEvent>,
+    mut board: ResMut<Board>,
+    mut transform_query: Query<&mut Transform>,
+    mut texture_atlas_query: Query<&mut TextureAtlas>,
+    layout: Res<BoardLayout>,
+) {
+    let mut any_events = false;
+
+    for ev in ev_undo_redo.read() {
+        any_events = true;
+
+        // Find where the moved piece (and rook, if castling) are now
+        let (piece_move, rook_move) = match ev.castling {
+            Some((king_move, rook_move)) => (king_move, Some(rook_move)),
+            None => (ev.piece_move, None),
+        };
+        let moved_tiles = [Some(piece_move), rook_move]
+            .into_iter()
+            .flatten()
+            .map(|piece_move| {
+                if ev.undone {
+                    piece_move.from
+                } else {
+                    piece_move.to
+                }
+            });
+
+        for tile_pos in moved_tiles {
+            sync_piece_sprite(
+                &mut commands,
+                &board,
+                tile_pos,
+                &mut transform_query,
+                &mut texture_atlas_query,
+                &layout,
+            );
+        }
+    }
+
+    if !any_events {
+        return;
+    }
+
+    // Pieces restored by an undo don't have a sprite
+    spawn_missing_pieces(
+        &mut commands,
+        &asset_server,
+        &mut texture_atlas_layouts,
+        &mut board,
+        &layout,
+    );
+}
+
+// Move the sprite of the piece on this square to match the board, updating its texture in case of promotion
+fn sync_piece_sprite(
+    commands: &mut Commands,
+    board: &Board,
+    tile_pos: TilePos,
+    transform_query: &mut Query<&mut Transform>,
+    texture_atlas_query: &mut Query<&mut TextureAtlas>,
+    layout: &BoardLayout,
+) {
+    let Some(entity) = board.get_entity(tile_pos) else {
+        return;
+    };
+
+    // Stop the piece finishing a move which has just been undone
+    commands.entity(entity).remove::<MoveAnimation>();
+
+    if let Ok(mut transform) = transform_query.get_mut(entity) {
+        let (x, y) = board_to_pixel_coords(tile_pos.file, tile_pos.rank, layout);
+        transform.translation = Vec3::new(x, y, 1.);
+    }
+
+    if let Ok(mut texture_atlas) = texture_atlas_query.get_mut(entity) {
+        texture_atlas.index = board.get_piece(tile_pos).into();
+    }
+}
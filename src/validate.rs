@@ -0,0 +1,246 @@
+use std::{error::Error, fmt};
+
+use crate::{
+    board::{Board, FenError, Player, TilePos},
+    display::BOARD_SIZE,
+    piece::{Piece, PieceType},
+};
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum PositionError {
+    KingCount { player: Player, count: u32 },
+    PawnOnBackRank(TilePos),
+    InvalidCastling(Player),
+    OpponentInCheck(Player),
+}
+
+impl fmt::Display for PositionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Position can not be played from:")?;
+
+        match self {
+            PositionError::KingCount { player, count } => {
+                write!(f, "{player:?} has {count} kings instead of one")
+            }
+            PositionError::PawnOnBackRank(tile_pos) => write!(
+                f,
+                "There is a pawn on {}, which is on a back rank",
+                tile_pos.to_algebraic()
+            ),
+            PositionError::InvalidCastling(player) => write!(
+                f,
+                "{player:?} can castle without a king and rook on their first rank"
+            ),
+            PositionError::OpponentInCheck(player) => {
+                write!(f, "{player:?} is in check, even though it isn't their turn")
+            }
+        }
+    }
+}
+
+impl Error for PositionError {}
+
+impl Board {
+    // Create a board from FEN, as long as the position can also be played from
+    pub fn from_fen_validated<T: AsRef<str>>(fen_string: T) -> Result<Self, FenError> {
+        let mut board = Board::from_fen(fen_string)?;
+        board.validate().map_err(FenError::IllegalPosition)?;
+
+        Ok(board)
+    }
+
+    // Find every reason the position can't be played from:
+    // each player needs exactly one king, pawns can't be on either back rank, castling needs its king and rook in place,
+    // and the player who just moved can't have left their king in check
+    pub fn validate(&mut self) -> Result<(), Vec<PositionError>> {
+        let mut errors = Vec::new();
+
+        for player in [Player::White, Player::Black] {
+            let count = self.piece_count(Piece::new(PieceType::King, player));
+            if count != 1 {
+                errors.push(PositionError::KingCount { player, count });
+            }
+        }
+
+        let back_files = [
+            self.get_home_file(Player::White),
+            self.get_home_file(Player::Black),
+        ];
+        for pawn in [Piece::WPawn, Piece::BPawn] {
+            errors.extend(
+                self.positions[pawn]
+                    .iter()
+                    .filter(|tile_pos| back_files.contains(&tile_pos.file))
+                    .map(PositionError::PawnOnBackRank),
+            );
+        }
+
+        for player in [Player::White, Player::Black] {
+            if !self.has_castling_pieces(player) {
+                errors.push(PositionError::InvalidCastling(player));
+            }
+        }
+
+        let opponent = self.get_next_player();
+        if self.is_in_check(opponent) {
+            errors.push(PositionError::OpponentInCheck(opponent));
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+
+    // The first reason the position can't be played from, if there is one
+    pub fn validate_position(&self) -> Result<(), PositionError> {
+        self.clone()
+            .validate()
+            .map_err(|mut errors| errors.remove(0))
+    }
+
+    // Whether the king and a rook on the correct side are on the first rank for each of this player's castling rights
+    fn has_castling_pieces(&self, player: Player) -> bool {
+        let (king_side, queen_side) = self.get_castling_rights(player);
+        if !king_side && !queen_side {
+            return true;
+        }
+
+        let home_file = self.get_home_file(player);
+        let Some(king_pos) = self
+            .get_king_pos(player)
+            .filter(|king_pos| king_pos.file == home_file)
+        else {
+            return false;
+        };
+
+        let rook = Piece::new(PieceType::Rook, player);
+        let has_rook = |mut ranks: std::ops::Range<usize>| {
+            ranks.any(|rank| self.get_piece(TilePos::new(home_file, rank)) == rook)
+        };
+
+        (!king_side || has_rook(king_pos.rank + 1..BOARD_SIZE))
+            && (!queen_side || has_rook(0..king_pos.rank))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn validate_fen(fen: &str) -> Result<(), Vec<PositionError>> {
+        Board::from_fen(fen).unwrap().validate()
+    }
+
+    fn pos(algebraic: &str) -> TilePos {
+        TilePos::from_algebraic(algebraic).unwrap()
+    }
+
+    #[test]
+    fn legal_positions() {
+        assert_eq!(Board::default().validate(), Ok(()));
+        assert_eq!(validate_fen("k7/8/8/8/8/8/8/R3K3 b - - 0 1"), Ok(()));
+        assert!(Board::from_fen_validated("k7/8/8/8/8/8/8/R3K3 b - - 0 1").is_ok());
+    }
+
+    #[test]
+    fn king_count() {
+        assert_eq!(
+            validate_fen("8/8/8/8/8/8/8/4K3 w - - 0 1"),
+            Err(vec![PositionError::KingCount {
+                player: Player::Black,
+                count: 0
+            }])
+        );
+        assert_eq!(
+            validate_fen("k7/8/8/8/8/8/8/K3K3 w - - 0 1"),
+            Err(vec![PositionError::KingCount {
+                player: Player::White,
+                count: 2
+            }])
+        );
+    }
+
+    #[test]
+    fn pawn_on_back_rank() {
+        assert_eq!(
+            validate_fen("k2P3P/8/8/8/8/8/8/K1p5 w - - 0 1"),
+            Err(vec![
+                PositionError::PawnOnBackRank(pos("d8")),
+                PositionError::PawnOnBackRank(pos("h8")),
+                PositionError::PawnOnBackRank(pos("c1")),
+            ])
+        );
+    }
+
+    #[test]
+    fn invalid_castling() {
+        let mut board = Board::from_fen("4k3/8/8/8/8/8/8/R3K3 w - - 0 1").unwrap();
+
+        board.set_castling_rights(Player::White, (false, true));
+        assert_eq!(board.validate(), Ok(()));
+
+        board.set_castling_rights(Player::White, (true, true));
+        assert_eq!(
+            board.validate(),
+            Err(vec![PositionError::InvalidCastling(Player::White)])
+        );
+    }
+
+    #[test]
+    fn opponent_in_check() {
+        assert_eq!(
+            validate_fen("k7/8/8/8/8/8/8/R3K3 w - - 0 1"),
+            Err(vec![PositionError::OpponentInCheck(Player::Black)])
+        );
+
+        let Err(e) = Board::from_fen_validated("k7/8/8/8/8/8/8/R3K3 w - - 0 1") else {
+            panic!("A position with the player not to move in check was accepted");
+        };
+        assert_eq!(
+            e,
+            FenError::IllegalPosition(vec![PositionError::OpponentInCheck(Player::Black)])
+        );
+    }
+
+    #[test]
+    fn every_error_is_collected() {
+        let mut board = Board::from_fen("8/8/8/8/8/8/8/p3K3 w - - 0 1").unwrap();
+        board.set_castling_rights(Player::White, (true, false));
+
+        assert_eq!(
+            board.validate(),
+            Err(vec![
+                PositionError::KingCount {
+                    player: Player::Black,
+                    count: 0
+                },
+                PositionError::PawnOnBackRank(pos("a1")),
+                PositionError::InvalidCastling(Player::White),
+            ])
+        );
+        assert_eq!(
+            board.validate_position(),
+            Err(PositionError::KingCount {
+                player: Player::Black,
+                count: 0
+            })
+        );
+    }
+
+    #[test]
+    fn fen_castling_without_pieces() {
+        // The FEN parser reads the rights as written, and validating is what rejects them
+        let fen = "4k3/8/8/8/8/8/8/4K3 w KQ";
+        assert!(Board::from_fen(fen).is_ok());
+
+        let Err(e) = Board::from_fen_validated(fen) else {
+            panic!("Castling rights without a rook were accepted");
+        };
+        assert_eq!(
+            e,
+            FenError::IllegalPosition(vec![PositionError::InvalidCastling(Player::White)])
+        );
+    }
+}
@@ -0,0 +1,111 @@
+use std::sync::LazyLock;
+
+use crate::{
+    board::{Board, Player, TilePos},
+    display::BOARD_SIZE,
+    piece::{Piece, COLOUR_AMT, PIECE_AMT},
+};
+
+static ZOBRIST_KEYS: LazyLock<ZobristKeys> = LazyLock::new(ZobristKeys::new);
+
+struct ZobristKeys {
+    pieces: [[u64; BOARD_SIZE * BOARD_SIZE]; PIECE_AMT * COLOUR_AMT],
+    black_to_move: u64,
+    castling: [u64; 2 * COLOUR_AMT],
+    en_passant: [u64; BOARD_SIZE],
+}
+
+impl ZobristKeys {
+    fn new() -> Self {
+        // Use a fixed seed so that hashes are the same across runs
+        let mut state = 0x2545_F491_4F6C_DD1D_u64;
+        let mut next_key = || {
+            // SplitMix64
+            state = state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+            let mut z = state;
+            z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+            z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+            z ^ (z >> 31)
+        };
+
+        let mut keys = Self {
+            pieces: [[0; BOARD_SIZE * BOARD_SIZE]; PIECE_AMT * COLOUR_AMT],
+            black_to_move: 0,
+            castling: [0; 2 * COLOUR_AMT],
+            en_passant: [0; BOARD_SIZE],
+        };
+
+        for piece_keys in keys.pieces.iter_mut() {
+            for key in piece_keys.iter_mut() {
+                *key = next_key();
+            }
+        }
+        keys.black_to_move = next_key();
+        for key in keys.castling.iter_mut() {
+            *key = next_key();
+        }
+        for key in keys.en_passant.iter_mut() {
+            *key = next_key();
+        }
+
+        keys
+    }
+}
+
+impl Board {
+    // Hash of the position, including castling rights and en passant, but not the move counters
+    pub fn zobrist_hash(&self) -> u64 {
+        let keys = &*ZOBRIST_KEYS;
+
+        let mut hash = 0;
+
+        for (piece_index, piece_keys) in keys.pieces.iter().enumerate() {
+            let board = self.positions[Into::<Piece>::into(piece_index)];
+
+            for (index, key) in piece_keys.iter().enumerate() {
+                if board.get_bit(index) {
+                    hash ^= key;
+                }
+            }
+        }
+
+        if self.player == Player::Black {
+            hash ^= keys.black_to_move;
+        }
+
+        for player in [Player::White, Player::Black] {
+            let (king_side, queen_side) = self.get_castling_rights(player);
+
+            if king_side {
+                hash ^= keys.castling[2 * player as usize];
+            }
+            if queen_side {
+                hash ^= keys.castling[2 * player as usize + 1];
+            }
+        }
+
+        // En passant only changes the position if the capture could actually be made
+        if let Some(en_passant) = self.get_en_passant_on_last_move() {
+            if self.can_capture_en_passant(en_passant) {
+                hash ^= keys.en_passant[en_passant.rank];
+            }
+        }
+
+        hash
+    }
+
+    fn can_capture_en_passant(&self, en_passant: TilePos) -> bool {
+        let pawn = match self.player {
+            Player::White => Piece::WPawn,
+            Player::Black => Piece::BPawn,
+        };
+
+        // The capturing pawn sits beside the pawn which moved, one file behind the en passant square
+        let vertical_dir = -Board::get_vertical_dir(pawn);
+
+        [-1, 1]
+            .into_iter()
+            .filter_map(|horizontal_dir| en_passant.offset(vertical_dir, horizontal_dir))
+            .any(|tile_pos| self.get_piece(tile_pos) == pawn)
+    }
+}